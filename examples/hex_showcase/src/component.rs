@@ -1,61 +1,35 @@
-use iced_data_navigator::hex::viewer::{self, ContentStyler};
+use iced_data_navigator::hex::legend::{self, LegendEntry};
+use iced_data_navigator::hex::palette::HighlightPalette;
+use iced_data_navigator::hex::source::{FileSource, SourceStats, StatsHandle, StatsSource};
+use iced_data_navigator::hex::viewer::{self, CellStyle, Highlight, Layer, StyleLayers};
 
 use rand::prelude::*;
-use iced::{Color, Element, Font, Pixels, Theme, Right};
+use iced::{Element, Font, Pixels, Theme, Right};
 use iced::widget::{column, container, row, text};
 use iced_core::Length;
-use std::fs::File;
-use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::ops::Range;
 use std::path::PathBuf;
 
+/// The layers listed by the legend, in order.
+const LEGEND_LAYERS: [Layer; 2] = [Layer::Regions, Layer::Selection];
+
 pub enum Action {
     None,
 }
 
-#[derive(Debug)]
-struct Reader {
-    reader: BufReader<File>,
-    size: u64,
-}
-
-impl Reader {
-    fn new(path: &PathBuf) -> Self {
-        let file = File::open(path).unwrap();
-        let mut reader = BufReader::new(file);
-
-        let size = reader.seek(SeekFrom::End(0)).unwrap();
-        reader.seek(SeekFrom::Start(0)).unwrap();
-
-        Reader {
-            reader,
-            size
-        }
-    }
-}
-
-impl viewer::Source for Reader {
-    fn read(&mut self, offset: u64, buf: &mut [u8]) -> usize {
-        self.reader.seek(SeekFrom::Start(offset)).unwrap();
-        self.reader.read(buf).unwrap()
-    }
-
-    fn size(&mut self) -> u64 {
-        self.size
-    }
-}
-
 #[derive(Debug, Clone, Copy)]
 pub enum Message {
     CursorMoved(u64),
     Scrolled(viewer::Viewport),
     LogicalViewportSizeChanged(viewer::Viewport),
     Selected(Option<viewer::Selection>),
+    LegendToggled(usize, bool),
 }
 
 pub struct HexComponent {
     theme: Theme,
     content: viewer::Content,
+    stats: Option<StatsHandle>,
     viewport: viewer::Viewport,
     font: Option<Font>,
     font_size: Option<Pixels>,
@@ -67,7 +41,7 @@ pub struct HexComponent {
     style: Option<viewer::Style>,
     horizontal_navigation: Option<viewer::Navigation>,
     vertical_navigation: Option<viewer::Navigation>,
-    content_styler: ContentStyler,
+    style_layers: StyleLayers,
     rng: ThreadRng,
 }
 
@@ -76,6 +50,7 @@ impl HexComponent {
         Self {
             theme,
             content: viewer::Content::default(),
+            stats: None,
             viewport: viewer::Viewport::default(),
             font: None,
             font_size: None,
@@ -87,19 +62,25 @@ impl HexComponent {
             style: None,
             horizontal_navigation: None,
             vertical_navigation: None,
-            content_styler: ContentStyler::default(),
+            style_layers: StyleLayers::default(),
             rng: rand::rng(),
         }
     }
 
     pub fn open_file(&mut self, path: &PathBuf) {
-        let reader = Reader::new(path);
-        self.content = viewer::Content::new(reader);
+        let source = StatsSource::new(FileSource::open(path).unwrap());
+        self.stats = Some(source.handle());
+        self.content = viewer::Content::new(source);
+    }
+
+    /// The statistics of the reads of the open file, if there is one.
+    pub fn source_stats(&self) -> Option<SourceStats> {
+        self.stats.as_ref().map(StatsHandle::get)
     }
 
     pub fn set_theme(&mut self, theme: Theme) {
+        // The highlights get their colors from the theme, so they don't need to be rebuilt.
         self.theme = theme;
-        self.rebuild_content_styler_cache();
     }
 
     pub fn set_font(&mut self, font: Font) {
@@ -131,16 +112,12 @@ impl HexComponent {
     }
 
     pub fn random_highlight(&mut self) {
-        self.clear_content_styler();
+        self.style_layers.styler_mut(Layer::Regions).clear();
 
         for _ in 0..self.rng.random_range(5 .. 20) {
             let range = self.get_random_interval();
-            let text_color = self.get_random_color();
-            let background_color = self.get_random_color();
-            for i in range {
-                self.content_styler.set_text(i, text_color);
-                self.content_styler.set_background(i, background_color);
-            }
+            let highlight = Highlight::Custom(self.rng.random_range(0 .. 8));
+            self.style_layers.styler_mut(Layer::Regions).set_highlight_range(range, highlight);
         }
     }
 
@@ -154,12 +131,10 @@ impl HexComponent {
             Message::Scrolled(viewport) => {
                 self.viewport = viewport;
                 self.update_content();
-                self.rebuild_content_styler_cache();
             }
             Message::LogicalViewportSizeChanged(viewport) => {
                 self.viewport = viewport;
                 self.update_content();
-                self.rebuild_content_styler_cache();
             }
             Message::Selected(selection_maybe) => {
                 self.selection = selection_maybe;
@@ -168,6 +143,10 @@ impl HexComponent {
                 }
                 self.rebuild_content_styler_cache();
             }
+            Message::LegendToggled(index, visible) => {
+                // Toggling a layer doesn't require its styling to be rebuilt.
+                self.style_layers.set_enabled(LEGEND_LAYERS[index], visible);
+            }
         }
 
         Action::None
@@ -180,6 +159,7 @@ impl HexComponent {
         .on_scrolled(Message::Scrolled)
         .on_logical_viewport_resized(Message::LogicalViewportSizeChanged)
         .on_selection(Message::Selected)
+        .selection(self.selection)
         .font_maybe(self.font)
         .font_size_maybe(self.font_size)
         .virtual_columns(self.columns)
@@ -187,8 +167,18 @@ impl HexComponent {
         .padding_settings(self.layout_settings)
         .horizontal_navigation_maybe(self.horizontal_navigation)
         .vertical_navigation_maybe(self.vertical_navigation)
-        .content_styler(&self.content_styler)
+        .style_layers(&self.style_layers)
             .height(Length::Fill);
+
+        // A block selection can span every row of the source, so it's styled while drawing
+        // instead of with a range per row.
+        if let Some(selection) = self.selection.filter(|selection| selection.block.is_some())
+            && self.style_layers.is_enabled(Layer::Selection)
+        {
+            hex_viewer = hex_viewer.style_provider(move |offset: u64, _: u8| {
+                selection.contains(offset).then(|| CellStyle::new().highlight(Highlight::Match))
+            });
+        }
         
         if let Some(configured_style) = self.style {
             let style_fn = move |_: &Theme, _: viewer::Status| {
@@ -202,11 +192,24 @@ impl HexComponent {
 
         let status_bar = row![
             if let Some(selection) = &self.selection {
-                text!("Sel length: {:#0X} {}", selection.length, selection.length).font(font)
+                let count = selection.byte_count();
+                text!("Sel length: {:#0X} {}", count, count).font(font)
             } else {
                 text("")
             },
             text!("Cursor: {:#0X} {}", self.cursor, self.cursor).font(font),
+            legend::legend()
+                .entries(LEGEND_LAYERS.iter().map(|&layer| {
+                    let (label, color) = match layer {
+                        Layer::Selection => {
+                            ("Selection", viewer::default_highlight(&self.theme, Highlight::Match))
+                        }
+                        _ => ("Regions", HighlightPalette::color_of(&self.theme, 0)),
+                    };
+
+                    LegendEntry::new(label, color).visible(self.style_layers.is_enabled(layer))
+                }))
+                .on_toggle(Message::LegendToggled),
         ]
         .spacing(30);
 
@@ -226,49 +229,25 @@ impl HexComponent {
         self.content.update(self.viewport);
     }
 
-    fn clear_content_styler(&mut self) {
-        self.content_styler.clear(self.viewport.size());
-    }
-
     fn rebuild_content_styler_cache(&mut self) {
-        self.clear_content_styler();
-        // The ContentStyler only knows about the current viewport, so potentially only part of the
-        // data horizontally and vertically. We need to translate the selection, which is a
-        // contiguous range in absolute space to this little viewport window.
-        if let Some(selection) = self.selection {
-            let (text, background) = highlight_color(&self.theme);
-            for (row, range) in self.viewport.iter_rows().enumerate() {
-                let start = range.start.max(selection.offset);
-                let end = range.end.min(selection.offset + selection.length);
-                for index in start..end {
-                    let index = (self.viewport.columns() * row as u64 + index - range.start) as usize;
-
-                    self.content_styler.set_text(index, text);
-                    self.content_styler.set_background(index, background);
-                }
-            }
-
+        // The selection is styled by absolute offsets, so it stays valid when the viewport scrolls
+        // or is resized. Block selections are styled in `view` instead.
+        let styler = self.style_layers.styler_mut(Layer::Selection);
+        styler.clear();
+
+        if let Some(selection) = self.selection
+            && selection.block.is_none()
+        {
+            let range = selection.offset..selection.offset + selection.length;
+            styler.set_highlight_range(range, Highlight::Match);
         }
     }
 
-    fn get_random_interval(&mut self) -> Range<usize> {
-        let offset = self.rng.random_range(0 .. self.viewport.size().saturating_sub(50));
+    fn get_random_interval(&mut self) -> Range<u64> {
+        let offset = self.viewport.offset()
+            + self.rng.random_range(0 .. (self.viewport.size() as u64).saturating_sub(50));
         let length = self.rng.random_range(0 .. 50);
 
         Range {start: offset, end: offset + length}
     }
-
-    fn get_random_color(&mut self) -> Color {
-        let r = self.rng.random_range(0. .. 1.);
-        let g = self.rng.random_range(0. .. 1.);
-        let b = self.rng.random_range(0. .. 1.);
-        Color::from_rgb(r, g, b)
-    }
-}
-
-fn highlight_color(theme: &Theme) -> (Color, Color) {
-    (
-        theme.extended_palette().primary.weak.text,
-        theme.extended_palette().primary.weak.color
-    )
 }
@@ -4,6 +4,8 @@
 //     windows_subsystem = "windows"
 // )]
 
+use iced_data_navigator::hex::format::format_size;
+use iced_data_navigator::hex::source::SourceStats;
 use iced_data_navigator::hex::viewer;
 
 use iced::{Element, Font, Function, Length, Settings, Task, Theme, Window};
@@ -16,6 +18,7 @@ use iced::widget::{
 use std::fmt;
 use std::io;
 use std::path::PathBuf;
+use std::time::Duration;
 
 mod component;
 
@@ -159,8 +162,7 @@ enum Message {
     PaddingChanged(usize, f32),
     RandomHighlightPressed,
     DebugToggled(bool),
-    PresetLayoutCompact,
-    PresetLayoutSpacious,
+    PresetLayout(usize),
     NavigationOptionChanged(usize, NavigationOption),
     NavigationAlignedChanged(usize, viewer::Alignment),
     HorizontalStepChanged(viewer::Step),
@@ -244,12 +246,8 @@ impl Application {
                 self.debug = value;
                 Task::none()
             }
-            Message::PresetLayoutCompact => {
-                self.set_preset_layout(viewer::PaddingSettings::compact());
-                Task::none()
-            }
-            Message::PresetLayoutSpacious => {
-                self.set_preset_layout(viewer::PaddingSettings::spacious());
+            Message::PresetLayout(index) => {
+                self.set_preset_layout(viewer::PaddingSettings::PRESETS[index].1);
                 Task::none()
             }
             Message::RandomHighlightPressed => {
@@ -378,10 +376,11 @@ impl Application {
                 "Padding",
                 self.create_padding()
                     .chain(std::iter::once(
-                        Element::from(row![
-                            button("Compact").on_press(Message::PresetLayoutCompact),
-                            button("Spacious").on_press(Message::PresetLayoutSpacious)
-                        ].spacing(10.0))
+                        Element::from(Row::with_children(
+                            viewer::PaddingSettings::PRESETS.iter().enumerate().map(|(index, (name, _))| {
+                                button(*name).on_press(Message::PresetLayout(index)).into()
+                            })
+                        ).spacing(10.0))
                     ))
             ),
             group_settings(
@@ -411,7 +410,7 @@ impl Application {
             row![settings, space::horizontal().width(20.)]
         );
         
-        let content: Element<'_, Message> = column![
+        let mut content = column![
             action(
                 open_icon(),
                 "Open file",
@@ -421,7 +420,15 @@ impl Application {
                 container(scrollable_settings).align_x(alignment::Horizontal::Left),
                 container(hex_viewer),
             ].spacing(10)
-        ].padding(10).spacing(10.0).into();
+        ].padding(10).spacing(10.0);
+
+        if self.debug
+            && let Some(stats) = self.hex_viewer.source_stats()
+        {
+            content = content.push(source_stats(stats));
+        }
+
+        let content: Element<'_, Message> = content.into();
 
         if self.debug {
             content.explain(self.theme.palette().text)
@@ -579,6 +586,25 @@ fn configure_row(
     .align_y(alignment::Vertical::Center)
 }
 
+/// The read statistics of the open file, shown while debugging.
+fn source_stats<'a>(stats: SourceStats) -> Element<'a, Message> {
+    let latency = |latency: Option<Duration>| {
+        latency.map_or_else(|| String::from("-"), |latency| format!("{latency:.1?}"))
+    };
+
+    text!(
+        "Reads: {}  Read: {}  Errors: {}  Pending: {}  Average latency: {}  Max latency: {}",
+        stats.requests,
+        format_size(stats.bytes),
+        stats.errors,
+        stats.pending,
+        latency(stats.average_latency()),
+        latency(Some(stats.max_latency).filter(|_| stats.requests > 0)),
+    )
+    .font(Font::MONOSPACE)
+    .into()
+}
+
 #[derive(Debug, Clone)]
 pub enum Error {
     DialogClosed,
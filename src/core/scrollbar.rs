@@ -180,6 +180,7 @@ where
     thumb_width: f32,
     status: Status,
     class: Theme::ScrollClass<'a>,
+    minimap: Option<&'a [Color]>,
 }
 
 impl<'a, Theme> VerticalScrollbar<'a, Theme>
@@ -203,6 +204,16 @@ where
         self
     }
 
+    /// Fills the track with a preview of the content, such as a [`Minimap`]. The colors are spread
+    /// evenly over the track from top to bottom, and the thumb becomes a translucent window over
+    /// them. Widen the track and thumb to make the preview readable.
+    ///
+    /// [`Minimap`]: crate::hex::minimap::Minimap
+    pub fn minimap(mut self, colors: &'a [Color]) -> Self {
+        self.minimap = Some(colors);
+        self
+    }
+
     /// The width that the scrollbar wants to have.
     pub fn width(&self) -> f32 {
         self.track_width.max(self.thumb_width)
@@ -256,6 +267,7 @@ where
             thumb_width: 10.0,
             status: Status::Enabled(BarStatus::Active),
             class: Theme::scroll_default(),
+            minimap: None,
         }
     }
 }
@@ -264,6 +276,10 @@ impl<'a, Theme> Scrollbar for VerticalScrollbar<'a, Theme>
 where
     Theme: Catalog
 {
+    fn minimap(&self) -> Option<&[Color]> {
+        self.minimap
+    }
+
     fn layout(&self, bounds: Rectangle, viewport: Viewport) -> Option<Layout> {
         if bounds.width == 0.0 || bounds.height == 0.0 {
             return None
@@ -329,6 +345,11 @@ where
 trait Scrollbar {
     fn layout(&self, bounds: Rectangle, scroll_state: Viewport) -> Option<Layout>;
 
+    /// The colors to fill the track with, if any. See [`VerticalScrollbar::minimap`].
+    fn minimap(&self) -> Option<&[Color]> {
+        None
+    }
+
     /// Find the region that the cursor is in. The region isn't limited to the scrollbar itself:
     /// for the [`HorizontalScrollbar`] the y-axis is irrelevant and for the [`VerticalScrollbar`]
     /// the x-axis is irrelevant.
@@ -574,26 +595,78 @@ where
         );
     }
 
-    // Draw the thumb.
+    let minimap = scrollbar.minimap().filter(|colors| !colors.is_empty());
+
+    if let Some(colors) = minimap {
+        draw_minimap(renderer, layout.track, colors);
+    }
+
+    // Draw the thumb. Over a minimap it's a translucent window with an opaque outline.
+    let thumb_style = match minimap {
+        Some(_) => ThumbStyle {
+            color: style.thumb_style.color.scale_alpha(0.35),
+            border: border::Border {
+                color: style.thumb_style.color,
+                width: style.thumb_style.border.width.max(1.0),
+                ..style.thumb_style.border
+            },
+        },
+        None => style.thumb_style,
+    };
+
     if !scroll_state.is_fully_visible()
         && layout.thumb.width > 0.0
         && layout.thumb.height > 0.0
-        && (style.thumb_style.color != Color::TRANSPARENT
-        || (style.thumb_style.border.color != Color::TRANSPARENT
-        && style.thumb_style.border.width > 0.0))
+        && (thumb_style.color != Color::TRANSPARENT
+        || (thumb_style.border.color != Color::TRANSPARENT
+        && thumb_style.border.width > 0.0))
     {
         renderer.fill_quad(
             renderer::Quad {
                 bounds: layout.thumb,
                 //bounds: new_bounds,
-                border: style.thumb_style.border,
+                border: thumb_style.border,
                 ..renderer::Quad::default()
             },
-            style.thumb_style.color,
+            thumb_style.color,
         );
     }
 }
 
+/// Spreads the colors evenly over the track, from top to bottom. If there are more colors than
+/// pixels, colors are skipped. Runs of the same color are drawn as one quad.
+fn draw_minimap<Renderer>(renderer: &mut Renderer, track: Rectangle, colors: &[Color])
+where
+    Renderer: iced_core::Renderer
+{
+    let stripes = colors.len().min(track.height.floor().max(1.0) as usize);
+    let stripe_height = track.height / stripes as f32;
+    let mut start = 0;
+
+    for stripe in 1..=stripes {
+        let color = colors[start * colors.len() / stripes];
+
+        if stripe < stripes && colors[stripe * colors.len() / stripes] == color {
+            continue;
+        }
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: Rectangle {
+                    x: track.x,
+                    y: track.y + start as f32 * stripe_height,
+                    width: track.width,
+                    height: (stripe - start) as f32 * stripe_height,
+                },
+                ..renderer::Quad::default()
+            },
+            color,
+        );
+
+        start = stripe;
+    }
+}
+
 /// The result of handling an event.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ScrollResult {
@@ -0,0 +1,242 @@
+use crate::hex::rules::{Rule, RuleSet};
+
+use iced_core::Color;
+use std::ops::Range;
+
+/// Bookmarks, annotations and regions found while analysing data, in a form that can be exchanged
+/// between analysts and tools.
+///
+/// With the `serde` feature enabled an `AnnotationSet` can be loaded from and saved to TOML or
+/// JSON. All offsets are absolute offsets into the [`Source`], and ranges include `start` but not
+/// `end`. In TOML an annotation set looks like this:
+///
+/// ```toml
+/// version = 1
+///
+/// [[bookmark]]
+/// offset = 4096
+/// label = "Start of the image data"
+///
+/// [[annotation]]
+/// start = 0
+/// end = 8
+/// text = "PNG signature, checked by the loader"
+///
+/// [[region]]
+/// start = 8
+/// end = 33
+/// label = "IHDR chunk"
+/// color = "#2b3a55"
+/// ```
+///
+/// In JSON the same keys are used, with arrays named `bookmark`, `annotation` and `region`. All
+/// lists, `label` and `color` may be left out. Colors are hex strings such as `"#ff8800"` or
+/// `"#ff880080"`. The schema is deliberately flat, so converters from and to the formats of other
+/// tools, such as 010 Editor bookmarks or ImHex patterns, are straightforward to write.
+///
+/// [`Source`]: crate::hex::viewer::Source
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnnotationSet {
+    /// The version of the schema, [`AnnotationSet::VERSION`] when created by this crate. Readers
+    /// can use it to detect files written by a newer version.
+    #[cfg_attr(feature = "serde", serde(default = "AnnotationSet::default_version"))]
+    pub version: u32,
+    /// The bookmarks, in no particular order.
+    #[cfg_attr(feature = "serde", serde(default, rename = "bookmark"))]
+    pub bookmarks: Vec<Bookmark>,
+    /// The annotations, in no particular order.
+    #[cfg_attr(feature = "serde", serde(default, rename = "annotation"))]
+    pub annotations: Vec<Annotation>,
+    /// The regions, in order of increasing precedence when they overlap.
+    #[cfg_attr(feature = "serde", serde(default, rename = "region"))]
+    pub regions: Vec<Region>,
+}
+
+impl Default for AnnotationSet {
+    fn default() -> Self {
+        Self {
+            version: Self::VERSION,
+            bookmarks: Vec::new(),
+            annotations: Vec::new(),
+            regions: Vec::new(),
+        }
+    }
+}
+
+impl AnnotationSet {
+    /// The current version of the schema.
+    pub const VERSION: u32 = 1;
+
+    /// Creates an empty [`AnnotationSet`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a [`Bookmark`].
+    pub fn bookmark(mut self, bookmark: Bookmark) -> Self {
+        self.bookmarks.push(bookmark);
+        self
+    }
+
+    /// Adds an [`Annotation`].
+    pub fn annotation(mut self, annotation: Annotation) -> Self {
+        self.annotations.push(annotation);
+        self
+    }
+
+    /// Adds a [`Region`], which takes precedence over all regions added before it.
+    pub fn region(mut self, region: Region) -> Self {
+        self.regions.push(region);
+        self
+    }
+
+    /// Adds everything in `other`, for instance the findings of another analyst. Its regions take
+    /// precedence over the current ones. Nothing is deduplicated.
+    pub fn merge(&mut self, other: AnnotationSet) {
+        self.bookmarks.extend(other.bookmarks);
+        self.annotations.extend(other.annotations);
+        self.regions.extend(other.regions);
+    }
+
+    /// Whether there are no bookmarks, annotations or regions.
+    pub fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty() && self.annotations.is_empty() && self.regions.is_empty()
+    }
+
+    /// Creates a [`RuleSet`] with a range rule for every region, so the regions can be shown with
+    /// [`RuleSet::apply`]. Regions without a color get a rule that only labels them.
+    pub fn region_rules(&self) -> RuleSet {
+        self.regions.iter().fold(RuleSet::new(), |rules, region| {
+            let mut rule = Rule::range(region.range());
+
+            if let Some(label) = &region.label {
+                rule = rule.label(label.clone());
+            }
+
+            if let Some(color) = region.color {
+                rule = rule.background(color);
+            }
+
+            rules.rule(rule)
+        })
+    }
+
+    /// Parses an [`AnnotationSet`] from TOML.
+    #[cfg(feature = "serde")]
+    pub fn from_toml(toml: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(toml)
+    }
+
+    /// Serializes the [`AnnotationSet`] to TOML.
+    #[cfg(feature = "serde")]
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
+    /// Parses an [`AnnotationSet`] from JSON.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Serializes the [`AnnotationSet`] to JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    #[cfg(feature = "serde")]
+    fn default_version() -> u32 {
+        Self::VERSION
+    }
+}
+
+/// A named offset to return to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bookmark {
+    /// The bookmarked offset.
+    pub offset: u64,
+    /// The name of the bookmark.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub label: Option<String>,
+}
+
+impl Bookmark {
+    /// Creates a [`Bookmark`] at `offset` without a name.
+    pub fn new(offset: u64) -> Self {
+        Self { offset, label: None }
+    }
+
+    /// Sets the name.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+/// A note about a range of bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Annotation {
+    /// The first annotated offset.
+    pub start: u64,
+    /// The offset after the last annotated offset.
+    pub end: u64,
+    /// The note.
+    pub text: String,
+}
+
+impl Annotation {
+    /// Creates an [`Annotation`] of `range`.
+    pub fn new(range: Range<u64>, text: impl Into<String>) -> Self {
+        Self { start: range.start, end: range.end, text: text.into() }
+    }
+
+    /// The annotated range.
+    pub fn range(&self) -> Range<u64> {
+        self.start..self.end
+    }
+}
+
+/// A named and optionally colored range of bytes, such as a structure or a field.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Region {
+    /// The first offset of the region.
+    pub start: u64,
+    /// The offset after the last offset of the region.
+    pub end: u64,
+    /// The name of the region.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub label: Option<String>,
+    /// The background color of the region.
+    #[cfg_attr(feature = "serde", serde(
+        default, with = "crate::hex::rules::hex_color", skip_serializing_if = "Option::is_none"))]
+    pub color: Option<Color>,
+}
+
+impl Region {
+    /// Creates a [`Region`] of `range` without a name or color.
+    pub fn new(range: Range<u64>) -> Self {
+        Self { start: range.start, end: range.end, label: None, color: None }
+    }
+
+    /// Sets the name.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Sets the background color.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// The range of the region.
+    pub fn range(&self) -> Range<u64> {
+        self.start..self.end
+    }
+}
@@ -0,0 +1,136 @@
+/// The base that offsets are written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Radix {
+    /// Base 16, with the digits `0-9A-F`.
+    #[default]
+    Hexadecimal,
+    /// Base 10.
+    Decimal,
+    /// Base 8.
+    Octal,
+    /// Base 2.
+    Binary,
+}
+
+impl Radix {
+    /// The base, for instance 16 for [`Radix::Hexadecimal`].
+    pub fn base(self) -> u64 {
+        match self {
+            Radix::Hexadecimal => 16,
+            Radix::Decimal => 10,
+            Radix::Octal => 8,
+            Radix::Binary => 2,
+        }
+    }
+
+    /// The prefix of a number in this radix in Rust and C-like languages, such as `0x`. Empty for
+    /// [`Radix::Decimal`].
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Radix::Hexadecimal => "0x",
+            Radix::Decimal => "",
+            Radix::Octal => "0o",
+            Radix::Binary => "0b",
+        }
+    }
+
+    /// The number of digits needed to write `value`, at least 1.
+    pub fn digits(self, value: u64) -> usize {
+        let mut digits = 1;
+        let mut value = value / self.base();
+
+        while value > 0 {
+            digits += 1;
+            value /= self.base();
+        }
+
+        digits
+    }
+}
+
+/// Formats `offset` in `radix`, without a prefix or leading zeros. Hexadecimal digits are lowercase
+/// if `lowercase` is set, like [`HexViewer::lowercase_hex`] does for the address area. If `grouping`
+/// isn't 0, the digits are separated by `_` into groups of that many digits, counted from the
+/// right, as in Rust literals: `format_offset(0x12345678, Radix::Hexadecimal, 4, false)` is
+/// `"1234_5678"`.
+///
+/// [`HexViewer::lowercase_hex`]: crate::hex::viewer::HexViewer::lowercase_hex
+pub fn format_offset(offset: u64, radix: Radix, grouping: usize, lowercase: bool) -> String {
+    let digits = match radix {
+        Radix::Hexadecimal if lowercase => format!("{offset:x}"),
+        Radix::Hexadecimal => format!("{offset:X}"),
+        Radix::Decimal => format!("{offset}"),
+        Radix::Octal => format!("{offset:o}"),
+        Radix::Binary => format!("{offset:b}"),
+    };
+
+    if grouping == 0 {
+        return digits;
+    }
+
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / grouping);
+
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(grouping) {
+            grouped.push('_');
+        }
+
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+/// Formats `offset` in hexadecimal with a `0x` prefix, followed by its decimal value, such as
+/// `"0x1F00 (7936)"`.
+pub fn format_hex_dec(offset: u64) -> String {
+    format!("0x{offset:X} ({offset})")
+}
+
+/// Formats a number of bytes with a binary unit, such as `"512 B"`, `"1.5 KiB"` or `"4.0 GiB"`.
+/// Sizes from 1 KiB on are rounded to one decimal.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+
+    // Move on to the next unit before rounding would show 1024.0.
+    while value >= 1023.95 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Formats seconds since the Unix epoch as a date and time in UTC, such as
+/// `"2024-05-01 13:37:00"`.
+pub fn format_utc(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+
+    // Converts days since the epoch to a date in the proleptic Gregorian calendar, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+    )
+}
@@ -0,0 +1,296 @@
+use crate::hex::format::{format_hex_dec, Radix};
+use crate::hex::viewer::from_address;
+
+use iced_core::alignment::Vertical;
+use iced_core::widget::Id;
+use iced_core::{text, Element, Length, Pixels, Theme};
+use iced_widget::{row, text as label_text, text_input};
+use std::fmt;
+
+/// A small widget with an input for an offset to go to, for a "Go to offset" (CTRL+G) feature.
+///
+/// The input accepts an absolute offset, such as `4096`, `0x1000` or `1000h`, or a delta from the
+/// cursor, such as `+0x200` or `-16`, see [`GotoTarget::parse`]. The offset it resolves to is shown
+/// next to it, and pressing ENTER publishes the message set by [`GotoBar::on_submit`] with it. The
+/// application owns the text of the input, like it owns the cursor of the [`HexViewer`], and passes
+/// the offset on to the `HexViewer`:
+///
+/// ```ignore
+/// Message::GotoInput(input) => self.goto_input = input,
+/// Message::Goto(offset) => {
+///     self.cursor = offset;
+///     return operate(viewer::scroll_to(VIEWER, offset, Alignment::Center));
+/// }
+///
+/// // In view:
+/// goto_bar(&self.goto_input, self.cursor, self.content.size())
+///     .id(GOTO_BAR)
+///     .on_input(Message::GotoInput)
+///     .on_submit(Message::Goto)
+/// ```
+///
+/// Focus the input with CTRL+G by running `iced::widget::operation::focus` with the [`Id`] set by
+/// [`GotoBar::id`].
+///
+/// [`HexViewer`]: crate::hex::viewer::HexViewer
+pub struct GotoBar<'a, Message> {
+    value: String,
+    cursor: u64,
+    size: u64,
+    base_address: u64,
+    radix: Radix,
+    on_input: Option<Box<dyn Fn(String) -> Message + 'a>>,
+    on_submit: Option<Box<dyn Fn(u64) -> Message + 'a>>,
+    id: Option<Id>,
+    placeholder: String,
+    text_size: Option<Pixels>,
+    width: Length,
+}
+
+impl<'a, Message> GotoBar<'a, Message> {
+    /// Creates a `GotoBar` with the text `value` in its input. Deltas are applied to the `cursor`,
+    /// and offsets from `size` on are rejected.
+    pub fn new(value: impl Into<String>, cursor: u64, size: u64) -> Self {
+        Self {
+            value: value.into(),
+            cursor,
+            size,
+            base_address: 0,
+            radix: Radix::Decimal,
+            on_input: None,
+            on_submit: None,
+            id: None,
+            placeholder: String::from("Go to offset"),
+            text_size: None,
+            width: Length::Fixed(200.0),
+        }
+    }
+
+    /// Sets the address of the first byte, as set with [`HexViewer::base_address`]. Absolute
+    /// inputs are then addresses, and the offset they resolve to is the address minus `base`.
+    /// Deltas aren't affected, and the cursor and the published offset stay offsets from 0.
+    ///
+    /// [`HexViewer::base_address`]: crate::hex::viewer::HexViewer::base_address
+    pub fn base_address(mut self, base: u64) -> Self {
+        self.base_address = base;
+        self
+    }
+
+    /// Sets the [`Radix`] of numbers without a prefix, [`Radix::Decimal`] by default.
+    pub fn radix(mut self, radix: Radix) -> Self {
+        self.radix = radix;
+        self
+    }
+
+    /// Sets the message that should be produced when the text in the input changes. If unset, the
+    /// input is disabled.
+    pub fn on_input(mut self, func: impl Fn(String) -> Message + 'a) -> Self {
+        self.on_input = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the message that should be produced with the resolved offset when ENTER is pressed.
+    /// Nothing is produced while the input can't be resolved.
+    pub fn on_submit(mut self, func: impl Fn(u64) -> Message + 'a) -> Self {
+        self.on_submit = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the [`Id`] of the input, to focus it.
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the placeholder of the input, "Go to offset" by default.
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Sets the text size. If unset, the [`Renderer`]'s default text size is used.
+    ///
+    /// [`Renderer`]: iced_core::Renderer
+    pub fn text_size(mut self, size: impl Into<Pixels>) -> Self {
+        self.text_size = Some(size.into());
+        self
+    }
+
+    /// Sets the width of the input, 200 by default.
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    /// The offset the input resolves to.
+    pub fn target(&self) -> Result<u64, GotoError> {
+        let target = match GotoTarget::parse(&self.value, self.radix)? {
+            GotoTarget::Absolute(address) => GotoTarget::Absolute(
+                from_address(self.base_address, address).ok_or(GotoError::OutOfRange)?),
+            target => target,
+        };
+
+        target.resolve(self.cursor, self.size)
+    }
+}
+
+/// An offset to go to, as entered in a [`GotoBar`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GotoTarget {
+    /// An absolute offset.
+    Absolute(u64),
+    /// A number of bytes after the cursor.
+    Forward(u64),
+    /// A number of bytes before the cursor.
+    Backward(u64),
+}
+
+impl GotoTarget {
+    /// Parses an offset, or a delta from the cursor if it starts with `+` or `-`.
+    ///
+    /// The number is read in `radix`, unless it has a prefix: `0x` for hexadecimal, `0o` for
+    /// octal or `0b` for binary. A hexadecimal number may also end with `h` instead, as in
+    /// `1F00h`. If `radix` is [`Radix::Hexadecimal`], `0o` and `0b` are read as hexadecimal
+    /// digits. Whitespace and `_` are ignored, so offsets copied from the
+    /// [`HexViewer`] with digit grouping can be pasted.
+    ///
+    /// [`HexViewer`]: crate::hex::viewer::HexViewer
+    pub fn parse(input: &str, radix: Radix) -> Result<Self, GotoError> {
+        let input: String = input.chars()
+            .filter(|char| !char.is_whitespace() && *char != '_')
+            .collect();
+
+        if input.is_empty() {
+            return Err(GotoError::Empty);
+        }
+
+        let (sign, number) = match input.as_bytes()[0] {
+            b'+' => (Some(true), &input[1..]),
+            b'-' => (Some(false), &input[1..]),
+            _ => (None, input.as_str()),
+        };
+
+        let prefix = number.get(..2).map(str::to_ascii_lowercase);
+
+        let (radix, digits) = match prefix.as_deref() {
+            Some("0x") => (Radix::Hexadecimal, &number[2..]),
+            Some("0o") if radix != Radix::Hexadecimal => (Radix::Octal, &number[2..]),
+            Some("0b") if radix != Radix::Hexadecimal => (Radix::Binary, &number[2..]),
+            _ => match number.strip_suffix(['h', 'H']) {
+                Some(digits) => (Radix::Hexadecimal, digits),
+                None => (radix, number),
+            },
+        };
+
+        // `from_str_radix` accepts a sign of its own, which was taken off already.
+        if digits.is_empty() || digits.starts_with(['+', '-']) {
+            return Err(GotoError::Invalid);
+        }
+
+        let value = u64::from_str_radix(digits, radix.base() as u32)
+            .map_err(|_| GotoError::Invalid)?;
+
+        Ok(match sign {
+            None => GotoTarget::Absolute(value),
+            Some(true) => GotoTarget::Forward(value),
+            Some(false) => GotoTarget::Backward(value),
+        })
+    }
+
+    /// The absolute offset, with deltas applied to the `cursor`. Offsets before the start or from
+    /// `size` on are out of range.
+    pub fn resolve(self, cursor: u64, size: u64) -> Result<u64, GotoError> {
+        let offset = match self {
+            GotoTarget::Absolute(offset) => Some(offset),
+            GotoTarget::Forward(delta) => cursor.checked_add(delta),
+            GotoTarget::Backward(delta) => cursor.checked_sub(delta),
+        };
+
+        offset.filter(|offset| *offset < size).ok_or(GotoError::OutOfRange)
+    }
+}
+
+/// Why the input of a [`GotoBar`] can't be resolved to an offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GotoError {
+    /// There's no input.
+    Empty,
+    /// The input isn't a number.
+    Invalid,
+    /// The offset is before the start or beyond the end of the data.
+    OutOfRange,
+}
+
+impl fmt::Display for GotoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GotoError::Empty => write!(f, "No offset"),
+            GotoError::Invalid => write!(f, "Not a number"),
+            GotoError::OutOfRange => write!(f, "Out of range"),
+        }
+    }
+}
+
+impl std::error::Error for GotoError {}
+
+impl<'a, Message, Renderer> From<GotoBar<'a, Message>> for Element<'a, Message, Theme, Renderer>
+where
+    Message: Clone + 'a,
+    Renderer: text::Renderer + 'a,
+{
+    fn from(bar: GotoBar<'a, Message>) -> Self {
+        let target = bar.target();
+        let GotoBar {
+            value, base_address, on_input, on_submit, id, placeholder, text_size, width, ..
+        } = bar;
+
+        let invalid = matches!(target, Err(GotoError::Invalid | GotoError::OutOfRange));
+
+        let mut input = text_input(&placeholder, &value)
+            .on_input_maybe(on_input)
+            .on_submit_maybe(on_submit.zip(target.ok()).map(|(func, offset)| func(offset)))
+            .width(width)
+            .style(move |theme: &Theme, status| {
+                let mut style = text_input::default(theme, status);
+
+                if invalid {
+                    style.border.color = theme.extended_palette().danger.base.color;
+                }
+
+                style
+            });
+
+        if let Some(id) = id {
+            input = input.id(id);
+        }
+
+        let mut hint = match target {
+            // The input is an address, so the offset isn't obvious.
+            Ok(offset) if base_address > 0 => {
+                label_text(format!("Offset {}", format_hex_dec(offset)))
+            }
+            Ok(offset) => label_text(format_hex_dec(offset)),
+            Err(GotoError::Empty) => label_text(""),
+            Err(error) => label_text(error.to_string()),
+        };
+
+        if let Some(size) = text_size {
+            input = input.size(size);
+            hint = hint.size(size);
+        }
+
+        row![input, hint]
+            .spacing(8)
+            .align_y(Vertical::Center)
+            .into()
+    }
+}
+
+/// Creates a new [`GotoBar`].
+pub fn goto_bar<'a, Message>(
+    value: impl Into<String>,
+    cursor: u64,
+    size: u64,
+) -> GotoBar<'a, Message> {
+    GotoBar::new(value, cursor, size)
+}
@@ -0,0 +1,159 @@
+use crate::hex::minimap::{entropy, mix};
+use crate::hex::viewer::{CellStyle, Content, OffsetStyler, StyleProvider, StyleRuns};
+
+use iced_core::Color;
+use std::io;
+use std::ops::Range;
+
+/// Maps a value per byte or per range, such as entropy, age or frequency, to a color on a
+/// [`Gradient`], to color the bytes by it.
+///
+/// Values from [`Heatmap::range`] are spread over the gradient, values outside of it get the color
+/// at its nearest end. Values that are computed from the offset and value of a byte are colored
+/// while drawing with [`Heatmap::provider`]. Values that are computed beforehand, such as the
+/// entropy per block from [`entropy_blocks`], are turned into [`StyleRuns`] or written to an
+/// [`OffsetStyler`]:
+///
+/// ```ignore
+/// let blocks = heatmap::entropy_blocks(&mut content, 0..content.size(), 256)?;
+/// let runs = Heatmap::new(Gradient::new(cold, hot)).range(0.0, 8.0).runs(blocks);
+///
+/// // In view:
+/// hex_viewer_widget(&content).style_runs(&runs)
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heatmap {
+    gradient: Gradient,
+    min: f32,
+    max: f32,
+}
+
+impl Heatmap {
+    /// Creates a [`Heatmap`] that spreads values from 0 to 1 over the `gradient`.
+    pub fn new(gradient: Gradient) -> Self {
+        Self { gradient, min: 0.0, max: 1.0 }
+    }
+
+    /// Sets the values that map to the start and the end of the gradient.
+    pub fn range(mut self, min: f32, max: f32) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// The color of `value`.
+    pub fn color(&self, value: f32) -> Color {
+        let span = self.max - self.min;
+        let t = if span == 0.0 { 0.0 } else { (value - self.min) / span };
+
+        self.gradient.color(t)
+    }
+
+    /// A [`StyleProvider`] that colors the background of every byte by the value `scalar` computes
+    /// from its offset and value. Bytes without a value aren't styled.
+    pub fn provider<F>(self, scalar: F) -> impl StyleProvider
+    where
+        F: Fn(u64, u8) -> Option<f32>,
+    {
+        move |offset: u64, value: u8| {
+            scalar(offset, value).map(|value| CellStyle::new().background(self.color(value)))
+        }
+    }
+
+    /// Colors the background of every range by its value. Where ranges overlap, the range that
+    /// starts first wins.
+    pub fn runs(&self, values: impl IntoIterator<Item = (Range<u64>, f32)>) -> StyleRuns {
+        values.into_iter()
+            .map(|(range, value)| (range, CellStyle::new().background(self.color(value))))
+            .collect()
+    }
+
+    /// Sets the background of every range in the `styler` by its value. Later ranges replace
+    /// earlier ones where they overlap.
+    pub fn apply(
+        &self,
+        styler: &mut OffsetStyler,
+        values: impl IntoIterator<Item = (Range<u64>, f32)>,
+    ) {
+        for (range, value) in values {
+            styler.set_background_range(range, self.color(value));
+        }
+    }
+}
+
+/// Colors spread over the positions from 0 to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    /// Sorted by position.
+    stops: Vec<(f32, Color)>,
+}
+
+impl Gradient {
+    /// Creates a gradient from `start` at 0 to `end` at 1.
+    pub fn new(start: Color, end: Color) -> Self {
+        Self { stops: vec![(0.0, start), (1.0, end)] }
+    }
+
+    /// Adds a `color` at `position`, from 0 to 1, such as a middle color at 0.5.
+    pub fn stop(mut self, position: f32, color: Color) -> Self {
+        let position = position.clamp(0.0, 1.0);
+        let index = self.stops.partition_point(|&(stop, _)| stop <= position);
+
+        self.stops.insert(index, (position, color));
+        self
+    }
+
+    /// The color at `position`, which is clamped from 0 to 1. Colors between stops are
+    /// interpolated.
+    pub fn color(&self, position: f32) -> Color {
+        let position = if position.is_nan() { 0.0 } else { position.clamp(0.0, 1.0) };
+        let index = self.stops.partition_point(|&(stop, _)| stop <= position);
+
+        match (index.checked_sub(1).map(|index| self.stops[index]), self.stops.get(index)) {
+            (Some((from, from_color)), Some(&(to, to_color))) if to > from => {
+                mix(from_color, to_color, (position - from) / (to - from))
+            }
+            (Some((_, color)), _) | (None, Some(&(_, color))) => color,
+            (None, None) => Color::TRANSPARENT,
+        }
+    }
+}
+
+/// Computes the Shannon entropy of every block of `block_size` bytes in `range`, in bits per byte
+/// from 0 to 8, to color them with a [`Heatmap`] with a range from 0 to 8. The last block may be
+/// shorter, and so may a block the [`Source`] returns fewer bytes for. The next block then starts
+/// where it ended, so no bytes are skipped.
+///
+/// All of `range` is read, so compute this for the part of the data that's of interest, or in the
+/// background for large sources. Blocks that the [`Source`] can't provide yet are left out.
+/// Returns an error only if the `Source` fails with anything other than
+/// [`io::ErrorKind::WouldBlock`].
+///
+/// [`Source`]: crate::hex::viewer::Source
+pub fn entropy_blocks(
+    content: &mut Content,
+    range: Range<u64>,
+    block_size: usize,
+) -> io::Result<Vec<(Range<u64>, f32)>> {
+    let end = range.end.min(content.size());
+    let block_size = block_size.max(1);
+    let mut block = vec![0; block_size];
+    let mut blocks = Vec::new();
+    let mut start = range.start;
+
+    while start < end {
+        let length = ((end - start) as usize).min(block_size);
+
+        match content.read_source(start, &mut block[..length]) {
+            Ok(0) => break,
+            Ok(read) => {
+                blocks.push((start..start + read as u64, entropy(&block[..read])));
+                start += read as u64;
+            }
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => start += length as u64,
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(blocks)
+}
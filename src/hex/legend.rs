@@ -0,0 +1,154 @@
+use crate::hex::palette::HighlightColor;
+
+use iced_core::alignment::Vertical;
+use iced_core::{text, Element, Length, Pixels, Theme};
+use iced_widget::{checkbox, column, container, row, text as label_text};
+use std::rc::Rc;
+
+/// A small widget that lists highlight categories, such as the selection, search matches or
+/// regions, with their colors and a visibility toggle for each.
+///
+/// The legend doesn't keep track of the visibility itself: like the [`HexViewer`]'s cursor, the
+/// application owns that state, sets it with [`LegendEntry::visible`] and updates it when the
+/// message set by [`Legend::on_toggle`] is published. Feed the result back into the styling of the
+/// [`HexViewer`].
+///
+/// [`HexViewer`]: crate::hex::viewer::HexViewer
+pub struct Legend<'a, Message> {
+    entries: Vec<LegendEntry>,
+    on_toggle: Option<Box<dyn Fn(usize, bool) -> Message + 'a>>,
+    text_size: Option<Pixels>,
+    spacing: f32,
+}
+
+impl<'a, Message> Legend<'a, Message> {
+    /// Creates an empty legend.
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            on_toggle: None,
+            text_size: None,
+            spacing: 4.0,
+        }
+    }
+
+    /// Adds an entry. Entries are listed in the order they're added.
+    pub fn entry(mut self, entry: LegendEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Adds multiple entries.
+    pub fn entries(mut self, entries: impl IntoIterator<Item = LegendEntry>) -> Self {
+        self.entries.extend(entries);
+        self
+    }
+
+    /// Sets the message that should be produced when the visibility of an entry is toggled. The
+    /// function receives the index of the entry and its new visibility. If unset, the toggles are
+    /// disabled.
+    pub fn on_toggle(mut self, func: impl Fn(usize, bool) -> Message + 'a) -> Self {
+        self.on_toggle = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the text size of the labels. If unset, the [`Renderer`]'s default text size is used.
+    ///
+    /// [`Renderer`]: iced_core::Renderer
+    pub fn text_size(mut self, size: impl Into<Pixels>) -> Self {
+        self.text_size = Some(size.into());
+        self
+    }
+
+    /// Sets the vertical spacing between the entries.
+    pub fn spacing(mut self, spacing: impl Into<Pixels>) -> Self {
+        self.spacing = spacing.into().0;
+        self
+    }
+}
+
+impl<Message> Default for Legend<'_, Message> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A single highlight category listed by a [`Legend`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegendEntry {
+    /// The name of the category.
+    pub label: String,
+    /// The colors the category is highlighted with.
+    pub color: HighlightColor,
+    /// Whether the category is currently shown.
+    pub visible: bool,
+}
+
+impl LegendEntry {
+    /// Creates a new visible entry.
+    pub fn new(label: impl Into<String>, color: HighlightColor) -> Self {
+        Self {
+            label: label.into(),
+            color,
+            visible: true,
+        }
+    }
+
+    /// Sets whether the category is currently shown.
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+}
+
+impl<'a, Message, Renderer> From<Legend<'a, Message>> for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Renderer: text::Renderer + 'a,
+{
+    fn from(legend: Legend<'a, Message>) -> Self {
+        let Legend { entries, on_toggle, text_size, spacing } = legend;
+
+        // Shared by all checkboxes, since each of them needs its own closure.
+        let on_toggle: Option<Rc<dyn Fn(usize, bool) -> Message + 'a>> = on_toggle.map(Rc::from);
+
+        let rows = entries.into_iter().enumerate().map(|(index, entry)| {
+            let color = entry.color;
+
+            let mut toggle = checkbox(entry.visible).on_toggle_maybe(
+                on_toggle.clone().map(|func| move |visible| func(index, visible)),
+            );
+
+            let mut label = label_text(entry.label);
+
+            if let Some(size) = text_size {
+                toggle = toggle.size(size);
+                label = label.size(size);
+            }
+
+            // The label is drawn the way highlighted bytes are, so it doubles as a swatch.
+            let swatch = container(label)
+                .padding([0, 4])
+                .style(move |_: &Theme| container::Style {
+                    text_color: Some(color.text),
+                    background: Some(color.background.into()),
+                    ..container::Style::default()
+                });
+
+            row![toggle, swatch]
+                .spacing(6)
+                .align_y(Vertical::Center)
+                .into()
+        });
+
+        column(rows)
+            .spacing(spacing)
+            .width(Length::Shrink)
+            .into()
+    }
+}
+
+/// Creates a new [`Legend`].
+pub fn legend<'a, Message>() -> Legend<'a, Message> {
+    Legend::new()
+}
@@ -0,0 +1,109 @@
+use crate::hex::viewer::Content;
+
+use iced_core::Color;
+use std::io;
+
+/// The maximum number of bytes that are read per bucket to estimate its entropy.
+const SAMPLE_SIZE: usize = 4096;
+
+/// A downsampled overview of the data, to be drawn in the track of the vertical scrollbar with
+/// [`HexViewer::minimap`]. The thumb then becomes a translucent window over it, so the scrollbar
+/// shows both where the viewport is and what the rest of the data looks like.
+///
+/// The data is divided into buckets of equal size, each with one color, from the start of the data
+/// to its end. [`Minimap::entropy`] colors the buckets by how random their bytes are, which makes
+/// compressed or encrypted parts, tables and padding stand out. Any other coloring can be passed to
+/// [`Minimap::from_colors`].
+///
+/// Building an entropy minimap reads from the [`Source`], so it's meant to be done once after
+/// opening it, or in the background, and not on every frame.
+///
+/// [`HexViewer::minimap`]: crate::hex::viewer::HexViewer::minimap
+/// [`Source`]: crate::hex::viewer::Source
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Minimap {
+    colors: Vec<Color>,
+}
+
+impl Minimap {
+    /// Creates a minimap from a color per bucket, from the start of the data to its end.
+    pub fn from_colors(colors: Vec<Color>) -> Self {
+        Self { colors }
+    }
+
+    /// Creates a minimap of `buckets` buckets, colored by the Shannon entropy of their bytes. The
+    /// color goes from `low` for bytes that are all the same to `high` for random bytes.
+    ///
+    /// Up to 4 KiB is read from the start of each bucket, so large sources are sampled rather than
+    /// read entirely. Buckets that can't be read get the `low` color. Returns an error only if the
+    /// [`Source`](crate::hex::viewer::Source) fails with anything other than
+    /// [`io::ErrorKind::WouldBlock`].
+    pub fn entropy(content: &mut Content, buckets: usize, low: Color, high: Color) -> io::Result<Self> {
+        let size = content.size();
+        let buckets = (buckets as u64).min(size) as usize;
+        let mut colors = Vec::with_capacity(buckets);
+        let mut sample = vec![0; SAMPLE_SIZE];
+
+        for bucket in 0..buckets as u64 {
+            let start = bucket * size / buckets as u64;
+            let end = (bucket + 1) * size / buckets as u64;
+            let length = ((end - start) as usize).min(SAMPLE_SIZE);
+
+            let read = match content.read_source(start, &mut sample[..length]) {
+                Ok(read) => read,
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => 0,
+                Err(error) => return Err(error),
+            };
+
+            colors.push(mix(low, high, entropy(&sample[..read]) / 8.0));
+        }
+
+        Ok(Self { colors })
+    }
+
+    /// The color of every bucket, from the start of the data to its end.
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// Whether the minimap has no buckets.
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+}
+
+/// The Shannon entropy of `data` in bits per byte, from 0 to 8.
+pub(crate) fn entropy(data: &[u8]) -> f32 {
+    if data.is_empty() {
+        return 0.0;
+    }
+
+    let mut counts = [0u32; 256];
+
+    for &byte in data {
+        counts[byte as usize] += 1;
+    }
+
+    let total = data.len() as f32;
+
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let probability = count as f32 / total;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
+/// Interpolates between two colors, with `t` from 0 for `from` to 1 for `to`.
+pub(crate) fn mix(from: Color, to: Color, t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+
+    Color {
+        r: from.r + (to.r - from.r) * t,
+        g: from.g + (to.g - from.g) * t,
+        b: from.b + (to.b - from.b) * t,
+        a: from.a + (to.a - from.a) * t,
+    }
+}
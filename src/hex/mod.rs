@@ -1,2 +1,31 @@
 pub mod viewer;
-
+/// Provides the [`RuleSet`](rules::RuleSet), a declarative way to style and label bytes.
+pub mod rules;
+/// Provides the [`AnnotationSet`](annotations::AnnotationSet), an exchange format for bookmarks,
+/// annotations and regions.
+pub mod annotations;
+/// Provides the [`NavigationTrail`](trail::NavigationTrail), a record of visited offsets that can be
+/// exported as a report.
+pub mod trail;
+/// Provides helpers that format offsets and sizes consistently with the
+/// [`HexViewer`](viewer::HexViewer).
+pub mod format;
+/// Provides the [`HighlightPalette`](palette::HighlightPalette), theme-derived highlight colors.
+pub mod palette;
+/// Provides the [`Legend`](legend::Legend), which lists highlight categories with visibility toggles.
+pub mod legend;
+/// Provides the [`Minimap`](minimap::Minimap), a data overview drawn in the vertical scrollbar.
+pub mod minimap;
+/// Provides the [`Heatmap`](heatmap::Heatmap), which colors bytes by a value such as their
+/// entropy.
+pub mod heatmap;
+/// Provides the [`GotoBar`](goto_bar::GotoBar), an input for an offset to go to.
+pub mod goto_bar;
+/// Provides ready-made [`Source`](viewer::Source)s, such as the [`FileSource`](source::FileSource).
+/// `Source` is also implemented for in-memory bytes: `Vec<u8>`, `&[u8]`, `Arc<[u8]>` and, with the
+/// `bytes` feature, `bytes::Bytes`.
+pub mod source;
+/// Provides a [`Subscription`](iced_futures::Subscription) that reports changes to a file, such as
+/// the one of a [`FileSource`](source::FileSource).
+#[cfg(feature = "watch")]
+pub mod watch;
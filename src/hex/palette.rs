@@ -0,0 +1,137 @@
+use iced_core::{Color, Theme};
+
+/// The golden angle in degrees. Stepping around the color wheel by this angle spreads any number of
+/// hues about as far apart from each other as possible.
+pub(crate) const GOLDEN_ANGLE: f32 = 137.507_77;
+
+/// A set of visually distinct highlight colors derived from a [`Theme`].
+///
+/// Use this instead of hard-coded or random colors for regions, bookmarks, matches and the like, so
+/// highlights look consistent and remain readable in both light and dark themes. The first color is
+/// based on the theme's primary color; subsequent colors step around the color wheel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighlightPalette {
+    colors: Vec<HighlightColor>,
+}
+
+impl HighlightPalette {
+    /// Derives `count` distinct highlight colors from the `theme`. At least one color is always
+    /// created.
+    pub fn new(theme: &Theme, count: usize) -> Self {
+        let colors = (0..count.max(1))
+            .map(|index| Self::color_of(theme, index))
+            .collect();
+
+        Self { colors }
+    }
+
+    /// The highlight color at `index` of every palette derived from the `theme` with more than
+    /// `index` colors, without creating one.
+    pub fn color_of(theme: &Theme, index: usize) -> HighlightColor {
+        let palette = theme.extended_palette();
+        let (hue, _, _) = to_hsl(palette.primary.base.color);
+        let hue = (hue + index as f32 * GOLDEN_ANGLE) % 360.0;
+
+        HighlightColor {
+            text: palette.background.base.text,
+            background: highlight_background(hue, palette.is_dark),
+        }
+    }
+
+    /// The highlight color at `index`. Indices beyond the number of colors wrap around.
+    pub fn get(&self, index: usize) -> HighlightColor {
+        self.colors[index % self.colors.len()]
+    }
+
+    /// The number of distinct colors.
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Always false, since a palette has at least one color.
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// Iterates over all colors.
+    pub fn iter(&self) -> impl Iterator<Item = HighlightColor> + '_ {
+        self.colors.iter().copied()
+    }
+}
+
+/// A text color and a background color that belong together.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HighlightColor {
+    /// The text color.
+    pub text: Color,
+    /// The background color.
+    pub background: Color,
+}
+
+impl HighlightColor {
+    /// A highlight of the `hue` (in degrees) behind `text` of the given color. Light text is taken
+    /// to be on a dark background.
+    pub(crate) fn behind(text: Color, hue: f32) -> Self {
+        let (_, _, lightness) = to_hsl(text);
+
+        Self {
+            text,
+            background: highlight_background(hue, lightness > 0.5),
+        }
+    }
+}
+
+/// The background of a highlight of the `hue` (in degrees). Highlights are backgrounds for text, so
+/// they need to stay close to the lightness of the theme's own background to keep the text
+/// readable.
+fn highlight_background(hue: f32, is_dark: bool) -> Color {
+    let (saturation, lightness) = if is_dark {
+        (0.45, 0.32)
+    } else {
+        (0.65, 0.82)
+    };
+
+    from_hsl(hue, saturation, lightness)
+}
+
+/// Converts the color to hue (in degrees), saturation and lightness.
+fn to_hsl(color: Color) -> (f32, f32, f32) {
+    let max = color.r.max(color.g).max(color.b);
+    let min = color.r.min(color.g).min(color.b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+
+    if delta == 0.0 {
+        return (0.0, 0.0, lightness);
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+
+    let hue = if max == color.r {
+        60.0 * ((color.g - color.b) / delta).rem_euclid(6.0)
+    } else if max == color.g {
+        60.0 * ((color.b - color.r) / delta + 2.0)
+    } else {
+        60.0 * ((color.r - color.g) / delta + 4.0)
+    };
+
+    (hue, saturation, lightness)
+}
+
+/// Converts hue (in degrees), saturation and lightness to an opaque color.
+fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Color {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let m = lightness - chroma / 2.0;
+
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    Color::from_rgb(r + m, g + m, b + m)
+}
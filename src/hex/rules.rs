@@ -0,0 +1,376 @@
+use crate::hex::viewer::{Content, OffsetStyler};
+
+use iced_core::Color;
+use std::collections::BTreeSet;
+use std::io;
+use std::ops::Range;
+
+/// An ordered collection of [`Rule`]s that style and label the bytes of a [`Content`].
+///
+/// Rules are meant to describe a (proprietary) format declaratively, so that highlighting profiles
+/// can be shared. With the `serde` feature enabled a `RuleSet` can be loaded from and saved to
+/// TOML or JSON. In TOML a rule set looks like this:
+///
+/// ```toml
+/// [[rule]]
+/// label = "Signature"
+/// match = "pattern"
+/// pattern = "89 50 4E 47"
+/// background = "#7a3b3b"
+///
+/// [[rule]]
+/// label = "Record type"
+/// match = "modulo"
+/// modulus = 16
+/// remainder = 0
+/// text = "#e0c060"
+///
+/// [[rule]]
+/// label = "Header"
+/// match = "range"
+/// start = 0
+/// end = 64
+/// background = "#2b3a55"
+/// ```
+///
+/// When rules style the same byte, rules that come later take precedence.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RuleSet {
+    /// The rules, in order of increasing precedence.
+    #[cfg_attr(feature = "serde", serde(default, rename = "rule"))]
+    pub rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    /// Creates an empty [`RuleSet`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a [`Rule`], which takes precedence over all rules added before it.
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Parses a [`RuleSet`] from TOML.
+    #[cfg(feature = "serde")]
+    pub fn from_toml(toml: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(toml)
+    }
+
+    /// Serializes the [`RuleSet`] to TOML.
+    #[cfg(feature = "serde")]
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
+    /// Parses a [`RuleSet`] from JSON.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Serializes the [`RuleSet`] to JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Finds everything the rules match within the current viewport of the [`Content`]. Patterns
+    /// that only partially overlap the viewport are found as well, which is why the [`Source`]
+    /// may be read around the visible rows.
+    ///
+    /// The matches are ordered by rule. An error is returned if the [`Source`] fails to read.
+    ///
+    /// [`Source`]: crate::hex::viewer::Source
+    pub fn matches(&self, content: &mut Content) -> io::Result<Vec<RuleMatch>> {
+        let rows = visible_rows(content);
+        let mut matches = Vec::new();
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            for range in rule.matcher.find(content, &rows)? {
+                matches.push(RuleMatch { rule: index, range });
+            }
+        }
+
+        Ok(matches)
+    }
+
+    /// Styles the bytes in the current viewport of the [`Content`] that are matched by the rules.
+    /// The [`OffsetStyler`] isn't cleared first, so rules can be combined with other styling.
+    /// Nothing is styled if the source fails to read.
+    ///
+    /// The styling is keyed by absolute offset, so it stays with the bytes when the viewport moves,
+    /// and applying the rules again after scrolling adds the matches that came into view. Matches
+    /// are only ever added, never removed. When the data changes, such as after
+    /// [`Content::refresh`], clear the styler before applying the rules again, or matches that no
+    /// longer match stay styled. Clearing it on every apply keeps just the matches in view.
+    pub fn apply(&self, content: &mut Content, styler: &mut OffsetStyler) -> io::Result<()> {
+        for RuleMatch { rule, range } in self.matches(content)? {
+            let style = self.rules[rule].style;
+
+            if let Some(color) = style.text {
+                styler.set_text_range(range.clone(), color);
+            }
+
+            if let Some(color) = style.background {
+                styler.set_background_range(range, color);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The label of the [`Rule`] that produced the [`RuleMatch`], if it has one.
+    pub fn label(&self, rule_match: &RuleMatch) -> Option<&str> {
+        self.rules.get(rule_match.rule)?.label.as_deref()
+    }
+}
+
+/// A single rule of a [`RuleSet`]: what it matches, how matched bytes look, and what they mean.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rule {
+    /// Describes what the matched bytes mean, for instance a field name.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub label: Option<String>,
+    /// What the rule matches.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub matcher: Matcher,
+    /// How the matched bytes are styled.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub style: RuleStyle,
+}
+
+impl Rule {
+    /// Creates a [`Rule`] with the given [`Matcher`] that doesn't style anything yet.
+    pub fn new(matcher: Matcher) -> Self {
+        Self {
+            label: None,
+            matcher,
+            style: RuleStyle::default(),
+        }
+    }
+
+    /// Creates a [`Rule`] that matches every occurrence of `pattern`.
+    pub fn pattern(pattern: impl Into<Vec<u8>>) -> Self {
+        Self::new(Matcher::Pattern { pattern: pattern.into() })
+    }
+
+    /// Creates a [`Rule`] that matches every offset for which `offset % modulus == remainder`.
+    pub fn modulo(modulus: u64, remainder: u64) -> Self {
+        Self::new(Matcher::Modulo { modulus, remainder })
+    }
+
+    /// Creates a [`Rule`] that matches a range of absolute offsets.
+    pub fn range(range: Range<u64>) -> Self {
+        Self::new(Matcher::Range { start: range.start, end: range.end })
+    }
+
+    /// Sets the label.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Sets the text color of matched bytes.
+    pub fn text(mut self, color: Color) -> Self {
+        self.style.text = Some(color);
+        self
+    }
+
+    /// Sets the background color of matched bytes.
+    pub fn background(mut self, color: Color) -> Self {
+        self.style.background = Some(color);
+        self
+    }
+}
+
+/// What a [`Rule`] matches. All offsets are absolute offsets into the [`Source`].
+///
+/// [`Source`]: crate::hex::viewer::Source
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "match", rename_all = "snake_case"))]
+pub enum Matcher {
+    /// Every occurrence of a byte sequence. Serialized as a string of hex digits, for instance
+    /// `"89 50 4E 47"`.
+    Pattern {
+        /// The byte sequence to look for.
+        #[cfg_attr(feature = "serde", serde(with = "hex_pattern"))]
+        pattern: Vec<u8>,
+    },
+    /// Every offset for which `offset % modulus == remainder`.
+    Modulo {
+        /// The modulus. A modulus of 0 matches nothing.
+        modulus: u64,
+        /// The remainder.
+        remainder: u64,
+    },
+    /// The offsets from `start` up to, but not including, `end`.
+    Range {
+        /// The first matched offset.
+        start: u64,
+        /// The offset after the last matched offset.
+        end: u64,
+    },
+}
+
+impl Matcher {
+    /// Finds the matched ranges that overlap `rows`, which are the visible parts of the rows in the
+    /// viewport.
+    fn find(&self, content: &mut Content, rows: &[Range<u64>]) -> io::Result<Vec<Range<u64>>> {
+        match self {
+            Matcher::Pattern { pattern } => {
+                if pattern.is_empty() {
+                    return Ok(Vec::new());
+                }
+
+                let len = pattern.len() as u64;
+                let size = content.size();
+
+                // Rows may overlap once they're widened, so collect unique occurrences.
+                let mut occurrences = BTreeSet::new();
+
+                for row in rows {
+                    let start = row.start.saturating_sub(len - 1);
+                    let end = (row.end + len - 1).min(size);
+
+                    let mut buf = vec![0; (end - start) as usize];
+                    let read = content.read_source(start, &mut buf)?;
+                    buf.truncate(read);
+
+                    for (i, window) in buf.windows(pattern.len()).enumerate() {
+                        if window == pattern.as_slice() {
+                            occurrences.insert(start + i as u64);
+                        }
+                    }
+                }
+
+                Ok(occurrences.into_iter()
+                    .map(|offset| offset..offset + len)
+                    .collect())
+            }
+            Matcher::Modulo { modulus, remainder } => {
+                if *modulus == 0 {
+                    return Ok(Vec::new());
+                }
+
+                let remainder = remainder % modulus;
+                let mut ranges = Vec::new();
+
+                for row in rows {
+                    let mut offset = row.start
+                        + (remainder + modulus - row.start % modulus) % modulus;
+
+                    while offset < row.end {
+                        ranges.push(offset..offset + 1);
+                        offset += modulus;
+                    }
+                }
+
+                Ok(ranges)
+            }
+            Matcher::Range { start, end } => {
+                Ok(rows.iter()
+                    .map(|row| row.start.max(*start)..row.end.min(*end))
+                    .filter(|range| range.start < range.end)
+                    .collect())
+            }
+        }
+    }
+}
+
+/// How the bytes matched by a [`Rule`] are styled. Colors are serialized as hex strings such as
+/// `"#ff8800"` or `"#ff880080"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RuleStyle {
+    /// The text color.
+    #[cfg_attr(feature = "serde", serde(
+        default, with = "hex_color", skip_serializing_if = "Option::is_none"))]
+    pub text: Option<Color>,
+    /// The background color.
+    #[cfg_attr(feature = "serde", serde(
+        default, with = "hex_color", skip_serializing_if = "Option::is_none"))]
+    pub background: Option<Color>,
+}
+
+/// A range of bytes matched by a [`Rule`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleMatch {
+    /// The index of the rule in its [`RuleSet`].
+    pub rule: usize,
+    /// The absolute range of matched bytes.
+    pub range: Range<u64>,
+}
+
+/// The parts of the rows in the viewport that contain data.
+fn visible_rows(content: &Content) -> Vec<Range<u64>> {
+    let size = content.size();
+
+    content.viewport()
+        .iter_rows()
+        .map(|row| row.start..row.end.min(size))
+        .filter(|row| row.start < row.end)
+        .collect()
+}
+
+#[cfg(feature = "serde")]
+mod hex_pattern {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::de::Error;
+
+    pub fn serialize<S: Serializer>(pattern: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        let hex: Vec<String> = pattern.iter().map(|byte| format!("{byte:02X}")).collect();
+        serializer.serialize_str(&hex.join(" "))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        let digits: Vec<char> = hex.chars().filter(|c| !c.is_whitespace()).collect();
+
+        if !digits.len().is_multiple_of(2) {
+            return Err(D::Error::custom("pattern has an odd number of hex digits"));
+        }
+
+        digits.chunks(2)
+            .map(|pair| {
+                let pair: String = pair.iter().collect();
+                u8::from_str_radix(&pair, 16)
+                    .map_err(|_| D::Error::custom(format!("invalid hex byte \"{pair}\"")))
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "serde")]
+pub(crate) mod hex_color {
+    use iced_core::Color;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::de::Error;
+
+    pub fn serialize<S: Serializer>(color: &Option<Color>, serializer: S) -> Result<S::Ok, S::Error> {
+        match color {
+            Some(color) => {
+                let [r, g, b, a] = color.into_rgba8();
+                if a == u8::MAX {
+                    serializer.serialize_str(&format!("#{r:02x}{g:02x}{b:02x}"))
+                } else {
+                    serializer.serialize_str(&format!("#{r:02x}{g:02x}{b:02x}{a:02x}"))
+                }
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Color>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+
+        hex.parse::<Color>()
+            .map(Some)
+            .map_err(|_| D::Error::custom(format!("invalid color \"{hex}\"")))
+    }
+}
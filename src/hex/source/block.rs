@@ -0,0 +1,217 @@
+use crate::hex::viewer::{Source, SourceInfo};
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+
+/// The sector size that is assumed if it can't be determined.
+const DEFAULT_SECTOR_SIZE: u64 = 512;
+
+/// The default number of sectors a [`BlockDeviceSource`] reads at once.
+const DEFAULT_SECTORS_PER_READ: u64 = 128;
+
+/// A [`Source`] that reads from a raw block device, such as `/dev/sda` on Linux.
+///
+/// Block devices may only be read in whole sectors, at offsets that are a multiple of the sector
+/// size. `BlockDeviceSource` turns every read into such a read, and serves the requested bytes
+/// from it. The sectors of the last read are kept, so the following rows don't need another one.
+///
+/// The size of a block device isn't available from its metadata. On Linux, the size and the sector
+/// size of a device opened with [`BlockDeviceSource::open`] are taken from `/sys/class/block`.
+/// Otherwise the size is determined by seeking to its end, and the sector size is assumed to be
+/// 512 bytes, unless set with [`BlockDeviceSource::sector_size`]. Seeking to the end doesn't work
+/// for the raw devices of Windows, such as `\\.\PhysicalDrive0`, which then appear empty. Reading
+/// a block device usually requires elevated privileges.
+#[derive(Debug)]
+pub struct BlockDeviceSource {
+    file: File,
+    name: Option<String>,
+    /// The size of the device, if it was looked up when it was opened.
+    size: Option<u64>,
+    sector_size: u64,
+    sectors_per_read: u64,
+    /// Whole sectors read, starting at `buffer_offset`.
+    buffer: Vec<u8>,
+    buffer_offset: u64,
+    /// Whether the buffer ends at the end of the device.
+    buffer_at_end: bool,
+}
+
+impl BlockDeviceSource {
+    /// Creates a new `BlockDeviceSource` that reads from the block device opened as `file`, with a
+    /// sector size of 512 bytes.
+    pub fn new(file: File) -> Self {
+        Self {
+            file,
+            name: None,
+            size: None,
+            sector_size: DEFAULT_SECTOR_SIZE,
+            sectors_per_read: DEFAULT_SECTORS_PER_READ,
+            buffer: Vec::new(),
+            buffer_offset: 0,
+            buffer_at_end: false,
+        }
+    }
+
+    /// Opens the block device at `path` in read-only mode. Its file name is used as the
+    /// [`SourceInfo::name`], and on Linux its size and sector size are looked up.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut source = Self::new(File::open(path)?);
+        source.name = path.file_name().map(|name| name.to_string_lossy().into_owned());
+
+        if let Some(size) = sector_size(path) {
+            source.sector_size = size;
+        }
+
+        source.size = device_size(path);
+
+        Ok(source)
+    }
+
+    /// Sets the [`SourceInfo::name`].
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the sector size, at least 1 byte. All reads start at a multiple of it and span a
+    /// multiple of it.
+    pub fn sector_size(mut self, bytes: u64) -> Self {
+        self.sector_size = bytes.max(1);
+        self.buffer.clear();
+        self.buffer_at_end = false;
+        self
+    }
+
+    /// Sets the number of sectors that are read at once, at least 1. Larger reads than that are
+    /// still possible.
+    pub fn sectors_per_read(mut self, sectors: u64) -> Self {
+        self.sectors_per_read = sectors.max(1);
+        self
+    }
+
+    /// Copies the part of the buffer at `offset` into `buf`, if the buffer contains `offset`.
+    fn read_buffered(&self, offset: u64, buf: &mut [u8]) -> Option<usize> {
+        let start = offset.checked_sub(self.buffer_offset)? as usize;
+        let available = self.buffer.get(start..)?;
+
+        // Only serve from the buffer if it has all requested bytes, or if it ends at the end of
+        // the device.
+        if available.len() < buf.len() && !self.buffer_at_end {
+            return None;
+        }
+
+        let length = available.len().min(buf.len());
+        buf[..length].copy_from_slice(&available[..length]);
+
+        Some(length)
+    }
+
+    /// Reads the whole sectors that span `length` bytes from `offset` into the buffer.
+    fn fill_buffer(&mut self, offset: u64, length: u64) -> io::Result<()> {
+        let start = offset - offset % self.sector_size;
+        let end = (offset + length).max(start + self.sectors_per_read * self.sector_size);
+        let end = end.div_ceil(self.sector_size) * self.sector_size;
+
+        // Forget the buffer first, so it isn't trusted if the read fails halfway.
+        self.buffer.clear();
+        self.buffer_at_end = false;
+        self.buffer.resize((end - start) as usize, 0);
+
+        self.file.seek(SeekFrom::Start(start))?;
+
+        let mut total = 0;
+
+        while total < self.buffer.len() {
+            match self.file.read(&mut self.buffer[total..]) {
+                Ok(0) => break,
+                Ok(read) => total += read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => {
+                    self.buffer.clear();
+                    return Err(error);
+                }
+            }
+        }
+
+        self.buffer_at_end = total < self.buffer.len();
+        self.buffer.truncate(total);
+        self.buffer_offset = start;
+
+        Ok(())
+    }
+}
+
+impl From<File> for BlockDeviceSource {
+    fn from(file: File) -> Self {
+        Self::new(file)
+    }
+}
+
+impl Source for BlockDeviceSource {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(read) = self.read_buffered(offset, buf) {
+            return Ok(read);
+        }
+
+        self.fill_buffer(offset, buf.len() as u64)?;
+
+        Ok(self.read_buffered(offset, buf).unwrap_or(0))
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        match self.size {
+            Some(size) => Ok(size),
+            None => self.file.seek(SeekFrom::End(0)),
+        }
+    }
+
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: self.name.clone(),
+            block_size: Some(self.sector_size),
+            ..SourceInfo::default()
+        }
+    }
+}
+
+/// The directory of the block device at `path` in `/sys/class/block`.
+#[cfg(target_os = "linux")]
+fn sysfs_device(path: &Path) -> Option<PathBuf> {
+    let path = path.canonicalize().ok()?;
+    Some(Path::new("/sys/class/block").join(path.file_name()?))
+}
+
+/// Looks up the size of the block device at `path`. The kernel reports it in units of 512 bytes,
+/// regardless of the sector size.
+#[cfg(target_os = "linux")]
+fn device_size(path: &Path) -> Option<u64> {
+    let size = std::fs::read_to_string(sysfs_device(path)?.join("size")).ok()?;
+    size.trim().parse::<u64>().ok()?.checked_mul(512)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn device_size(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Looks up the logical sector size of the block device at `path`. Partitions don't have their own
+/// queue, so the one of the disk they're on is used.
+#[cfg(target_os = "linux")]
+fn sector_size(path: &Path) -> Option<u64> {
+    let device = sysfs_device(path)?;
+
+    [device.join("queue"), device.join("../queue")]
+        .iter()
+        .find_map(|queue| std::fs::read_to_string(queue.join("logical_block_size")).ok())
+        .and_then(|size| size.trim().parse().ok())
+        .filter(|&size| size > 0)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn sector_size(_path: &Path) -> Option<u64> {
+    None
+}
@@ -0,0 +1,183 @@
+use crate::hex::viewer::{Source, SourceInfo};
+
+use std::collections::HashMap;
+use std::io;
+
+/// The default size of the chunks cached by a [`CachedSource`].
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The default number of chunks cached by a [`CachedSource`].
+const DEFAULT_CAPACITY: usize = 16;
+
+/// A [`Source`] that wraps another one and caches the data in fixed-size chunks.
+///
+/// [`Content`] issues many small reads. `CachedSource` turns those into a read of a whole
+/// chunk the first time one of its bytes is needed, and serves the following rows from memory.
+/// When the cache is full, the least recently used chunk is dropped. Use this for slow sources,
+/// such as network resources, spinning disks or archives.
+///
+/// [`Content`]: crate::hex::viewer::Content
+#[derive(Debug)]
+pub struct CachedSource<S> {
+    source: S,
+    chunk_size: usize,
+    capacity: usize,
+    chunks: HashMap<u64, Chunk>,
+    /// Incremented on every access, to find the least recently used chunk.
+    clock: u64,
+    /// The size of the source when the cached chunks were read.
+    size: Option<u64>,
+}
+
+#[derive(Debug)]
+struct Chunk {
+    data: Vec<u8>,
+    last_used: u64,
+}
+
+impl<S: Source> CachedSource<S> {
+    /// Creates a new `CachedSource` that caches 16 chunks of 64 KiB.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            capacity: DEFAULT_CAPACITY,
+            chunks: HashMap::new(),
+            clock: 0,
+            size: None,
+        }
+    }
+
+    /// Sets the size of the chunks, at least 1 byte. Clears the cache.
+    pub fn chunk_size(mut self, size: usize) -> Self {
+        self.chunk_size = size.max(1);
+        self.clear();
+        self
+    }
+
+    /// Sets the maximum number of cached chunks, at least 1.
+    pub fn capacity(mut self, chunks: usize) -> Self {
+        self.capacity = chunks.max(1);
+        self
+    }
+
+    /// Drops all cached chunks, so the data is read from the wrapped [`Source`] again.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    /// Gets a reference to the wrapped [`Source`].
+    pub fn get_ref(&self) -> &S {
+        &self.source
+    }
+
+    /// Gets a mutable reference to the wrapped [`Source`]. Call [`CachedSource::clear`] if its data
+    /// is changed through it.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Unwraps the [`Source`], dropping the cache.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+
+    /// Gets the chunk with the given index, reading it if it isn't cached.
+    fn chunk(&mut self, index: u64) -> io::Result<&[u8]> {
+        self.clock += 1;
+
+        if !self.chunks.contains_key(&index) {
+            if self.chunks.len() >= self.capacity
+                && let Some(&oldest) = self.chunks.iter()
+                    .min_by_key(|(_, chunk)| chunk.last_used)
+                    .map(|(index, _)| index)
+            {
+                self.chunks.remove(&oldest);
+            }
+
+            let data = self.read_chunk(index)?;
+            self.chunks.insert(index, Chunk { data, last_used: 0 });
+        }
+
+        let chunk = self.chunks.get_mut(&index).expect("chunk is cached");
+        chunk.last_used = self.clock;
+
+        Ok(&chunk.data)
+    }
+
+    /// Reads a whole chunk from the wrapped [`Source`]. It's shorter than the chunk size only at
+    /// the end of the source.
+    fn read_chunk(&mut self, index: u64) -> io::Result<Vec<u8>> {
+        let offset = index * self.chunk_size as u64;
+        let mut data = vec![0; self.chunk_size];
+        let mut total = 0;
+
+        while total < data.len() {
+            let read = self.source.read(offset + total as u64, &mut data[total..])?;
+
+            if read == 0 {
+                break;
+            }
+
+            total += read;
+        }
+
+        data.truncate(total);
+
+        Ok(data)
+    }
+}
+
+impl<S: Source> Source for CachedSource<S> {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let chunk_size = self.chunk_size as u64;
+        let mut total = 0;
+
+        while total < buf.len() {
+            let position = offset + total as u64;
+            let start = (position % chunk_size) as usize;
+            let chunk = self.chunk(position / chunk_size)?;
+
+            let Some(available) = chunk.get(start..) else {
+                break;
+            };
+
+            let length = available.len().min(buf.len() - total);
+            buf[total..total + length].copy_from_slice(&available[..length]);
+            total += length;
+
+            // A partial chunk marks the end of the source.
+            if chunk.len() < self.chunk_size {
+                break;
+            }
+        }
+
+        Ok(total)
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        let size = self.source.size()?;
+
+        // If the size has changed, so may have the data.
+        if self.size != Some(size) {
+            self.clear();
+            self.size = Some(size);
+        }
+
+        Ok(size)
+    }
+
+    fn reload(&mut self) -> io::Result<()> {
+        self.clear();
+        self.size = None;
+        self.source.reload()
+    }
+
+    /// Describes the wrapped [`Source`], but with the chunk size as the block size.
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            block_size: Some(self.chunk_size as u64),
+            ..self.source.info()
+        }
+    }
+}
@@ -0,0 +1,503 @@
+use crate::hex::viewer::{Source, SourceInfo};
+
+use miniz_oxide::inflate::stream::{inflate, InflateState};
+use miniz_oxide::{DataFormat, MZError, MZFlush, MZStatus};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// The default number of decompressed bytes between two checkpoints.
+const DEFAULT_CHECKPOINT_INTERVAL: u64 = 4 * 1024 * 1024;
+
+/// The size of the buffer compressed data is read into.
+const INPUT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// The flags in the header of a gzip member.
+const GZIP_FHCRC: u8 = 0x02;
+const GZIP_FEXTRA: u8 = 0x04;
+const GZIP_FNAME: u8 = 0x08;
+const GZIP_FCOMMENT: u8 = 0x10;
+
+/// The container format around the deflate data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// Plain deflate data, as found in zip entries.
+    #[cfg_attr(not(feature = "zip"), allow(dead_code))]
+    Raw,
+    /// One or more gzip members.
+    #[cfg_attr(not(feature = "gzip"), allow(dead_code))]
+    Gzip,
+}
+
+/// Decompresses deflate data with random access.
+///
+/// Deflate data can only be decompressed from the start. To avoid starting over for every read
+/// before the current position, the decompressor state is stored at an interval. A read then
+/// continues from the closest checkpoint before it.
+struct Inflater {
+    file: File,
+    format: Format,
+    /// The offset of the compressed data in the file.
+    start: u64,
+    /// The offset in the file where the compressed data ends.
+    end: u64,
+    state: Box<InflateState>,
+    /// Compressed data read from the file, and how much of it has been consumed.
+    input: Vec<u8>,
+    input_position: usize,
+    /// The offset in the file right after `input`.
+    file_position: u64,
+    /// The number of decompressed bytes produced so far.
+    position: u64,
+    /// Whether the end of the decompressed data has been reached.
+    finished: bool,
+    checkpoints: Vec<Checkpoint>,
+    checkpoint_interval: u64,
+}
+
+/// The decompressor state at a decompressed position.
+#[derive(Clone)]
+struct Checkpoint {
+    position: u64,
+    /// The offset in the file of the first compressed byte that wasn't consumed yet.
+    file_position: u64,
+    state: Box<InflateState>,
+    finished: bool,
+}
+
+impl Inflater {
+    fn new(file: File, format: Format, start: u64, end: u64) -> io::Result<Self> {
+        let mut inflater = Self {
+            file,
+            format,
+            start,
+            end,
+            state: InflateState::new_boxed(DataFormat::Raw),
+            input: Vec::new(),
+            input_position: 0,
+            file_position: start,
+            position: 0,
+            finished: false,
+            checkpoints: Vec::new(),
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+        };
+
+        inflater.rewind()?;
+
+        Ok(inflater)
+    }
+
+    /// Starts decompressing from the start again.
+    fn rewind(&mut self) -> io::Result<()> {
+        self.state = InflateState::new_boxed(DataFormat::Raw);
+        self.input.clear();
+        self.input_position = 0;
+        self.file_position = self.start;
+        self.position = 0;
+        self.finished = false;
+
+        if self.format == Format::Gzip && !self.gzip_header()? {
+            self.finished = true;
+        }
+
+        Ok(())
+    }
+
+    /// Continues from the last checkpoint at or before `offset`, unless continuing from the current
+    /// position is quicker.
+    fn seek(&mut self, offset: u64) -> io::Result<()> {
+        let index = self.checkpoints.partition_point(|checkpoint| checkpoint.position <= offset);
+        let checkpoint = index.checked_sub(1).map(|index| &self.checkpoints[index]);
+
+        match checkpoint {
+            Some(checkpoint) if offset < self.position || checkpoint.position > self.position => {
+                let checkpoint = checkpoint.clone();
+                self.state = checkpoint.state;
+                self.input.clear();
+                self.input_position = 0;
+                self.file_position = checkpoint.file_position;
+                self.position = checkpoint.position;
+                self.finished = checkpoint.finished;
+            }
+            None if offset < self.position => self.rewind()?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Decompresses the data at `offset` into `buf`.
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        self.seek(offset)?;
+
+        let mut skip = vec![0; INPUT_BUFFER_SIZE];
+
+        while self.position < offset {
+            let length = ((offset - self.position) as usize).min(skip.len());
+
+            if self.inflate(&mut skip[..length])? == 0 {
+                return Ok(0);
+            }
+        }
+
+        let mut total = 0;
+
+        while total < buf.len() {
+            let written = self.inflate(&mut buf[total..])?;
+
+            if written == 0 {
+                break;
+            }
+
+            total += written;
+        }
+
+        Ok(total)
+    }
+
+    /// Decompresses the next bytes into `out`. Returns 0 at the end of the data.
+    fn inflate(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.finished || out.is_empty() {
+                return Ok(0);
+            }
+
+            // Without input left, the decompressor may still have buffered output.
+            if self.input_position == self.input.len() {
+                self.fill_input()?;
+            }
+
+            let result = inflate(
+                &mut self.state, &self.input[self.input_position..], out, MZFlush::None);
+
+            self.input_position += result.bytes_consumed;
+            self.position += result.bytes_written as u64;
+
+            match result.status {
+                Ok(MZStatus::StreamEnd) => self.end_of_stream()?,
+                Ok(_) => {}
+                Err(MZError::Buf) if result.bytes_written == 0 => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof, "deflate data ends unexpectedly"));
+                }
+                Err(MZError::Buf) => {}
+                Err(_) => {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, "corrupt deflate data"));
+                }
+            }
+
+            if result.bytes_written > 0 {
+                self.add_checkpoint();
+                return Ok(result.bytes_written);
+            }
+        }
+    }
+
+    /// Handles the end of a deflate stream. A gzip file may consist of multiple members.
+    fn end_of_stream(&mut self) -> io::Result<()> {
+        if self.format == Format::Gzip {
+            // Skip the CRC32 and size of the member.
+            self.input_bytes(8)?;
+
+            if self.gzip_header()? {
+                self.state = InflateState::new_boxed(DataFormat::Raw);
+                return Ok(());
+            }
+        }
+
+        self.finished = true;
+
+        Ok(())
+    }
+
+    /// Stores the state if the last checkpoint is at least an interval ago.
+    fn add_checkpoint(&mut self) {
+        let last = self.checkpoints.last().map_or(0, |checkpoint| checkpoint.position);
+
+        if self.position >= last + self.checkpoint_interval {
+            self.checkpoints.push(Checkpoint {
+                position: self.position,
+                file_position: self.file_position - (self.input.len() - self.input_position) as u64,
+                state: self.state.clone(),
+                finished: self.finished,
+            });
+        }
+    }
+
+    /// Reads more compressed data. Returns false if there's none left.
+    fn fill_input(&mut self) -> io::Result<bool> {
+        let length = (self.end.saturating_sub(self.file_position) as usize).min(INPUT_BUFFER_SIZE);
+
+        if length == 0 {
+            return Ok(false);
+        }
+
+        self.input.resize(length, 0);
+        self.file.seek(SeekFrom::Start(self.file_position))?;
+
+        let read = self.file.read(&mut self.input)?;
+        self.input.truncate(read);
+        self.input_position = 0;
+        self.file_position += read as u64;
+
+        Ok(read > 0)
+    }
+
+    /// Consumes `count` compressed bytes that aren't part of the deflate data.
+    fn input_bytes(&mut self, count: usize) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(count);
+
+        while bytes.len() < count {
+            if self.input_position == self.input.len() && !self.fill_input()? {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+
+            let length = (count - bytes.len()).min(self.input.len() - self.input_position);
+            bytes.extend_from_slice(&self.input[self.input_position..self.input_position + length]);
+            self.input_position += length;
+        }
+
+        Ok(bytes)
+    }
+
+    /// Consumes bytes up to and including a zero byte.
+    fn skip_zero_terminated(&mut self) -> io::Result<()> {
+        while self.input_bytes(1)?[0] != 0 {}
+        Ok(())
+    }
+
+    /// Consumes the header of a gzip member. Returns false if there's no member left.
+    fn gzip_header(&mut self) -> io::Result<bool> {
+        if self.input_position == self.input.len() && !self.fill_input()? {
+            return Ok(false);
+        }
+
+        let header = self.input_bytes(10)?;
+
+        if header[0] != 0x1f || header[1] != 0x8b || header[2] != 8 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a gzip file"));
+        }
+
+        let flags = header[3];
+
+        if flags & GZIP_FEXTRA != 0 {
+            let length = self.input_bytes(2)?;
+            self.input_bytes(u16::from_le_bytes([length[0], length[1]]) as usize)?;
+        }
+
+        if flags & GZIP_FNAME != 0 {
+            self.skip_zero_terminated()?;
+        }
+
+        if flags & GZIP_FCOMMENT != 0 {
+            self.skip_zero_terminated()?;
+        }
+
+        if flags & GZIP_FHCRC != 0 {
+            self.input_bytes(2)?;
+        }
+
+        Ok(true)
+    }
+}
+
+impl fmt::Debug for Inflater {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Inflater")
+            .field("format", &self.format)
+            .field("position", &self.position)
+            .field("checkpoints", &self.checkpoints.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`Source`] that shows the decompressed contents of a gzip file, without extracting it first.
+/// Requires the `gzip` feature.
+///
+/// Reads before the current position continue from a checkpoint of the decompressor, which is
+/// stored every few MiB. The size of the decompressed data isn't stored reliably in a gzip file,
+/// so the first call to [`Source::size`] decompresses the whole file, creating all checkpoints on
+/// the way. Wrap it in a [`ThreadedSource`] to do that on a background thread.
+///
+/// [`ThreadedSource`]: crate::hex::source::ThreadedSource
+#[cfg(feature = "gzip")]
+#[derive(Debug)]
+pub struct GzipSource {
+    inflater: Inflater,
+    size: Option<u64>,
+    name: Option<String>,
+}
+
+#[cfg(feature = "gzip")]
+impl GzipSource {
+    /// Creates a new `GzipSource` that decompresses `file`.
+    pub fn new(file: File) -> io::Result<Self> {
+        let end = file.metadata()?.len();
+
+        Ok(Self {
+            inflater: Inflater::new(file, Format::Gzip, 0, end)?,
+            size: None,
+            name: None,
+        })
+    }
+
+    /// Opens the gzip file at `path`. Its file name is used as the [`SourceInfo::name`].
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut source = Self::new(File::open(path)?)?;
+        source.name = path.file_name().map(|name| name.to_string_lossy().into_owned());
+
+        Ok(source)
+    }
+
+    /// Sets the number of decompressed bytes between two checkpoints, 4 MiB by default. Every
+    /// checkpoint takes about 40 KiB of memory.
+    pub fn checkpoint_interval(mut self, bytes: u64) -> Self {
+        self.inflater.checkpoint_interval = bytes.max(1);
+        self
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl Source for GzipSource {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        self.inflater.read_at(offset, buf)
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        if let Some(size) = self.size {
+            return Ok(size);
+        }
+
+        // Decompress everything from the last checkpoint on.
+        let start = self.inflater.checkpoints.last().map_or(0, |checkpoint| checkpoint.position);
+        self.inflater.seek(start)?;
+
+        let mut buf = vec![0; INPUT_BUFFER_SIZE];
+        while self.inflater.inflate(&mut buf)? > 0 {}
+
+        self.size = Some(self.inflater.position);
+
+        Ok(self.inflater.position)
+    }
+
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: self.name.clone(),
+            ..SourceInfo::default()
+        }
+    }
+}
+
+/// A [`Source`] that shows the contents of an entry in a zip file, without extracting it first.
+/// Stored and deflated entries are supported. Requires the `zip` feature.
+///
+/// Stored entries are read directly. Reads in deflated entries before the current position
+/// continue from a checkpoint of the decompressor, which is stored every few MiB.
+#[cfg(feature = "zip")]
+#[derive(Debug)]
+pub struct ZipEntrySource {
+    entry: ZipEntry,
+    size: u64,
+    name: String,
+}
+
+#[cfg(feature = "zip")]
+#[derive(Debug)]
+enum ZipEntry {
+    Stored {
+        file: File,
+        start: u64,
+    },
+    Deflated(Inflater),
+}
+
+#[cfg(feature = "zip")]
+impl ZipEntrySource {
+    /// Opens the entry called `name` in the zip file at `path`.
+    pub fn open(path: impl AsRef<Path>, name: &str) -> io::Result<Self> {
+        Self::new(File::open(path)?, name)
+    }
+
+    /// Opens the entry called `name` in the zip `file`.
+    pub fn new(file: File, name: &str) -> io::Result<Self> {
+        let mut archive = zip::ZipArchive::new(file)?;
+        let index = archive.index_for_name(name)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such entry in zip file"))?;
+
+        let (compression, start, compressed_size, size) = {
+            let entry = archive.by_index_raw(index)?;
+
+            if entry.encrypted() {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported, "encrypted zip entries aren't supported"));
+            }
+
+            (entry.compression(), entry.data_start(), entry.compressed_size(), entry.size())
+        };
+
+        let file = archive.into_inner();
+
+        let entry = if compression == zip::CompressionMethod::STORE {
+            ZipEntry::Stored { file, start }
+        } else if compression == zip::CompressionMethod::DEFLATE {
+            ZipEntry::Deflated(Inflater::new(file, Format::Raw, start, start + compressed_size)?)
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported, "only stored and deflated zip entries are supported"));
+        };
+
+        Ok(Self {
+            entry,
+            size,
+            name: name.to_string(),
+        })
+    }
+
+    /// Sets the number of decompressed bytes between two checkpoints, 4 MiB by default. Every
+    /// checkpoint takes about 40 KiB of memory. Has no effect on stored entries.
+    pub fn checkpoint_interval(mut self, bytes: u64) -> Self {
+        if let ZipEntry::Deflated(inflater) = &mut self.entry {
+            inflater.checkpoint_interval = bytes.max(1);
+        }
+        self
+    }
+}
+
+#[cfg(feature = "zip")]
+impl Source for ZipEntrySource {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let length = (self.size.saturating_sub(offset) as usize).min(buf.len());
+        let buf = &mut buf[..length];
+
+        match &mut self.entry {
+            ZipEntry::Stored { file, start } => {
+                file.seek(SeekFrom::Start(*start + offset))?;
+
+                let mut total = 0;
+
+                while total < buf.len() {
+                    match file.read(&mut buf[total..])? {
+                        0 => break,
+                        read => total += read,
+                    }
+                }
+
+                Ok(total)
+            }
+            ZipEntry::Deflated(inflater) => inflater.read_at(offset, buf),
+        }
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        Ok(self.size)
+    }
+
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: Some(self.name.clone()),
+            ..SourceInfo::default()
+        }
+    }
+}
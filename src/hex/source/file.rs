@@ -0,0 +1,196 @@
+use crate::hex::viewer::{Source, SourceInfo};
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// The default size of the read-ahead buffer of a [`FileSource`].
+const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// A [`Source`] that reads from a [`File`].
+///
+/// [`Content`] issues many small reads, so `FileSource` reads ahead into a buffer and serves
+/// the following rows from it. It only seeks when a read doesn't continue where the previous one
+/// left off, which keeps scrolling through a file down to a few large reads.
+///
+/// The buffer is dropped when the size of the file changes. If the file is written to in place or
+/// replaced, call [`Content::refresh`], which reopens the file if it was opened with
+/// [`FileSource::open`].
+///
+/// [`Content`]: crate::hex::viewer::Content
+/// [`Content::refresh`]: crate::hex::viewer::Content::refresh
+#[derive(Debug)]
+pub struct FileSource {
+    file: File,
+    name: Option<String>,
+    path: Option<PathBuf>,
+    /// The position of the file cursor, if known.
+    position: Option<u64>,
+    /// Data read ahead, starting at `buffer_offset`.
+    buffer: Vec<u8>,
+    buffer_offset: u64,
+    /// Whether the buffer ends at the end of the file.
+    buffer_at_end: bool,
+    buffer_size: usize,
+}
+
+impl FileSource {
+    /// Creates a new `FileSource` that reads from `file`.
+    pub fn new(file: File) -> Self {
+        Self {
+            file,
+            name: None,
+            path: None,
+            position: None,
+            buffer: Vec::new(),
+            buffer_offset: 0,
+            buffer_at_end: false,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+        }
+    }
+
+    /// Opens the file at `path` in read-only mode. Its file name is used as the
+    /// [`SourceInfo::name`].
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut source = Self::new(File::open(path)?);
+        source.name = path.file_name().map(|name| name.to_string_lossy().into_owned());
+        source.path = Some(path.to_path_buf());
+
+        Ok(source)
+    }
+
+    /// The path the file was opened from with [`FileSource::open`]. With the `watch` feature, it
+    /// can be passed to `hex::watch::file` to be notified of changes.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Sets the [`SourceInfo::name`].
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the size of the read-ahead buffer. Reads larger than the buffer bypass it. A size of 0
+    /// disables buffering altogether.
+    pub fn buffer_size(mut self, size: usize) -> Self {
+        self.buffer_size = size;
+        self.clear_buffer();
+        self
+    }
+
+    /// Reads as many bytes as possible into `buf` at `offset`, seeking only if necessary.
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position != Some(offset) {
+            // Forget the position first, so it isn't trusted if the seek fails halfway.
+            self.position = None;
+            self.file.seek(SeekFrom::Start(offset))?;
+            self.position = Some(offset);
+        }
+
+        let mut total = 0;
+
+        while total < buf.len() {
+            match self.file.read(&mut buf[total..]) {
+                Ok(0) => break,
+                Ok(read) => total += read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => {
+                    self.position = None;
+                    return Err(error);
+                }
+            }
+        }
+
+        self.position = Some(offset + total as u64);
+
+        Ok(total)
+    }
+
+    /// Copies the part of the buffer at `offset` into `buf`, if the buffer contains `offset`.
+    fn read_buffered(&self, offset: u64, buf: &mut [u8]) -> Option<usize> {
+        let start = offset.checked_sub(self.buffer_offset)? as usize;
+        let available = self.buffer.get(start..)?;
+
+        // Only serve from the buffer if it has all requested bytes, or if it ends at the end of
+        // the file.
+        if available.len() < buf.len() && !self.buffer_at_end {
+            return None;
+        }
+
+        let length = available.len().min(buf.len());
+        buf[..length].copy_from_slice(&available[..length]);
+
+        Some(length)
+    }
+
+    fn clear_buffer(&mut self) {
+        self.buffer = Vec::new();
+        self.buffer_at_end = false;
+    }
+}
+
+impl From<File> for FileSource {
+    fn from(file: File) -> Self {
+        Self::new(file)
+    }
+}
+
+impl Source for FileSource {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.len() >= self.buffer_size {
+            return self.read_at(offset, buf);
+        }
+
+        if let Some(read) = self.read_buffered(offset, buf) {
+            return Ok(read);
+        }
+
+        let mut buffer = std::mem::take(&mut self.buffer);
+        self.buffer_at_end = false;
+        buffer.resize(self.buffer_size, 0);
+
+        let read = self.read_at(offset, &mut buffer)?;
+        buffer.truncate(read);
+
+        self.buffer = buffer;
+        self.buffer_offset = offset;
+        self.buffer_at_end = read < self.buffer_size;
+
+        Ok(self.read_buffered(offset, buf).unwrap_or(0))
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        let size = self.file.metadata()?.len();
+
+        // The file may have been truncated or appended to since the buffer was filled.
+        let buffer_end = self.buffer_offset + self.buffer.len() as u64;
+
+        if buffer_end > size || (self.buffer_at_end && buffer_end < size) {
+            self.clear_buffer();
+        }
+
+        Ok(size)
+    }
+
+    /// Drops the read-ahead buffer and opens the file at the [`FileSource::path`] again, so a file
+    /// that was replaced, as many editors do when saving, is read instead of the old one.
+    fn reload(&mut self) -> io::Result<()> {
+        self.clear_buffer();
+
+        if let Some(path) = &self.path {
+            self.file = File::open(path)?;
+            self.position = None;
+        }
+
+        Ok(())
+    }
+
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: self.name.clone(),
+            ..SourceInfo::default()
+        }
+    }
+}
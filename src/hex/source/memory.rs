@@ -0,0 +1,57 @@
+use crate::hex::viewer::Source;
+
+use std::io;
+use std::sync::Arc;
+
+/// Copies as many bytes as possible from `data` at `offset` into `buf`.
+pub(crate) fn read_slice(data: &[u8], offset: u64, buf: &mut [u8]) -> usize {
+    let Some(available) = usize::try_from(offset).ok().and_then(|offset| data.get(offset..)) else {
+        return 0;
+    };
+
+    let length = available.len().min(buf.len());
+    buf[..length].copy_from_slice(&available[..length]);
+
+    length
+}
+
+impl Source for Vec<u8> {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(read_slice(self, offset, buf))
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        Ok(self.len() as u64)
+    }
+}
+
+impl Source for &[u8] {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(read_slice(self, offset, buf))
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        Ok(self.len() as u64)
+    }
+}
+
+impl Source for Arc<[u8]> {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(read_slice(self, offset, buf))
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        Ok(self.len() as u64)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Source for bytes::Bytes {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(read_slice(self, offset, buf))
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        Ok(self.len() as u64)
+    }
+}
@@ -0,0 +1,58 @@
+use crate::hex::source::memory::read_slice;
+use crate::hex::viewer::Source;
+
+use memmap2::Mmap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/// A [`Source`] that memory-maps a [`File`], so reading a row is a plain copy instead of a seek
+/// and read. Suited for multi-gigabyte files. Requires the `mmap` feature.
+///
+/// The file is mapped once, so its size is fixed at the size it had when it was mapped.
+#[derive(Debug)]
+pub struct MmapSource {
+    map: Mmap,
+}
+
+impl MmapSource {
+    /// Maps `file` into memory.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified, truncated or resized, by this or any other process, as long
+    /// as the `MmapSource` exists. See [`Mmap::map`].
+    pub unsafe fn new(file: &File) -> io::Result<Self> {
+        // SAFETY: upheld by the caller.
+        let map = unsafe { Mmap::map(file)? };
+
+        Ok(Self { map })
+    }
+
+    /// Opens the file at `path` in read-only mode and maps it into memory.
+    ///
+    /// # Safety
+    ///
+    /// See [`MmapSource::new`].
+    pub unsafe fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+
+        // SAFETY: upheld by the caller.
+        unsafe { Self::new(&file) }
+    }
+
+    /// The mapped bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.map
+    }
+}
+
+impl Source for MmapSource {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(read_slice(&self.map, offset, buf))
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        Ok(self.map.len() as u64)
+    }
+}
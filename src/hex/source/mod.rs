@@ -0,0 +1,36 @@
+mod block;
+mod cached;
+#[cfg(any(feature = "gzip", feature = "zip"))]
+mod compressed;
+mod file;
+mod memory;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod prefetch;
+mod shared;
+mod stats;
+mod threaded;
+mod transform;
+
+pub use block::BlockDeviceSource;
+pub use cached::CachedSource;
+#[cfg(feature = "gzip")]
+pub use compressed::GzipSource;
+#[cfg(feature = "zip")]
+pub use compressed::ZipEntrySource;
+pub use file::FileSource;
+#[cfg(feature = "mmap")]
+pub use mmap::MmapSource;
+pub use prefetch::PrefetchSource;
+pub use shared::SharedSource;
+pub use stats::{SourceStats, StatsHandle, StatsSource};
+pub use threaded::ThreadedSource;
+pub use transform::{AddSource, Base64DecodedSource, XorSource};
+
+use std::sync::{Mutex, MutexGuard};
+
+/// Locks state that's shared with another thread or handle. The sources only change it in ways that
+/// a panic doesn't leave halfway done, so a poisoned lock is used as is.
+pub(crate) fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|error| error.into_inner())
+}
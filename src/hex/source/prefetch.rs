@@ -0,0 +1,328 @@
+use crate::hex::source::lock;
+use crate::hex::viewer::{Source, SourceInfo};
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// The default size of the chunks read by a [`PrefetchSource`].
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The default number of chunks a [`PrefetchSource`] reads ahead.
+const DEFAULT_AHEAD: usize = 4;
+
+/// The default number of chunks cached by a [`PrefetchSource`].
+const DEFAULT_CAPACITY: usize = 32;
+
+/// A [`Source`] that wraps another one, and reads the data just outside of the viewport ahead of
+/// time on a background thread.
+///
+/// Like the [`CachedSource`], it reads and caches the data in fixed-size chunks. Every time a chunk
+/// is used for the first time, the next chunks in the direction the user is scrolling in are
+/// requested from the background thread. When scrolling at a steady pace, the rows that scroll
+/// into view have then already been read, so a slow source doesn't make the scrolling stutter.
+///
+/// Unlike the [`ThreadedSource`], reads of data that hasn't been prefetched block until it has been
+/// read. If the background thread is reading a chunk at that moment, that read is finished first.
+///
+/// [`CachedSource`]: crate::hex::source::CachedSource
+/// [`ThreadedSource`]: crate::hex::source::ThreadedSource
+#[derive(Debug)]
+pub struct PrefetchSource<S> {
+    source: Arc<Mutex<S>>,
+    cache: Arc<Mutex<Cache>>,
+    requests: Sender<u64>,
+    ahead: usize,
+    /// The chunk that was used for the first time most recently.
+    last_chunk: Option<u64>,
+    /// Whether the user is scrolling towards the end of the source.
+    forward: bool,
+    /// The info of the wrapped source, taken before it was shared with the background thread.
+    info: SourceInfo,
+}
+
+/// The chunks shared with the background thread.
+#[derive(Debug)]
+struct Cache {
+    chunk_size: usize,
+    capacity: usize,
+    chunks: HashMap<u64, Chunk>,
+    /// Chunks requested from the background thread that haven't been read yet.
+    requested: HashSet<u64>,
+    /// Incremented on every access, to find the least recently used chunk.
+    clock: u64,
+    /// The size of the source when the cached chunks were read.
+    size: Option<u64>,
+}
+
+#[derive(Debug)]
+struct Chunk {
+    data: Vec<u8>,
+    last_used: u64,
+    /// Whether the chunk has been read from since it was prefetched.
+    used: bool,
+}
+
+impl<S: Source + Send + 'static> PrefetchSource<S> {
+    /// Creates a new `PrefetchSource` that reads 4 chunks of 64 KiB ahead, and caches 32 chunks.
+    /// Starts a background thread, which stops when the `PrefetchSource` is dropped.
+    pub fn new(source: S) -> Self {
+        let info = source.info();
+        let source = Arc::new(Mutex::new(source));
+        let cache = Arc::new(Mutex::new(Cache {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            capacity: DEFAULT_CAPACITY,
+            chunks: HashMap::new(),
+            requested: HashSet::new(),
+            clock: 0,
+            size: None,
+        }));
+
+        let (requests, receiver) = mpsc::channel::<u64>();
+        let thread_source = Arc::clone(&source);
+        let thread_cache = Arc::clone(&cache);
+
+        thread::spawn(move || {
+            for index in receiver {
+                let chunk_size = lock(&thread_cache).chunk_size;
+
+                // Errors are ignored: the read that needs the chunk reports them.
+                let data = read_chunk(&mut *lock(&thread_source), index, chunk_size);
+
+                let mut cache = lock(&thread_cache);
+
+                // The chunk may have been read by a blocking read or the cache may have been
+                // cleared in the meantime.
+                if !cache.requested.remove(&index) || cache.chunk_size != chunk_size {
+                    continue;
+                }
+
+                if let Ok(data) = data {
+                    cache.insert(index, data);
+                }
+            }
+        });
+
+        Self {
+            source,
+            cache,
+            requests,
+            ahead: DEFAULT_AHEAD,
+            last_chunk: None,
+            forward: true,
+            info,
+        }
+    }
+}
+
+impl<S> PrefetchSource<S> {
+    /// Sets the size of the chunks, at least 1 byte. Clears the cache.
+    pub fn chunk_size(self, size: usize) -> Self {
+        {
+            let mut cache = lock(&self.cache);
+            cache.chunk_size = size.max(1);
+            cache.clear();
+        }
+        self
+    }
+
+    /// Sets the number of chunks that are read ahead in the scrolling direction.
+    pub fn ahead(mut self, chunks: usize) -> Self {
+        self.ahead = chunks;
+        self
+    }
+
+    /// Sets the maximum number of cached chunks, at least 1. It should be well over the number of
+    /// chunks read ahead, or prefetched chunks are dropped before they're used.
+    pub fn capacity(self, chunks: usize) -> Self {
+        lock(&self.cache).capacity = chunks.max(1);
+        self
+    }
+
+    /// Drops all cached chunks, so the data is read from the wrapped [`Source`] again.
+    pub fn clear(&mut self) {
+        lock(&self.cache).clear();
+    }
+
+    /// Requests the chunks after or before `index`, depending on the scrolling direction.
+    fn prefetch(&mut self, index: u64) {
+        if let Some(last) = self.last_chunk
+            && last != index
+        {
+            self.forward = index > last;
+        }
+
+        self.last_chunk = Some(index);
+
+        let mut cache = lock(&self.cache);
+        let chunk_size = cache.chunk_size as u64;
+
+        for distance in 1..=self.ahead as u64 {
+            let next = if self.forward {
+                index.checked_add(distance)
+            } else {
+                index.checked_sub(distance)
+            };
+
+            let Some(next) = next else {
+                break;
+            };
+
+            // Don't read past the end of the source, if it's known.
+            if let Some(size) = cache.size
+                && next.saturating_mul(chunk_size) >= size
+            {
+                break;
+            }
+
+            if !cache.chunks.contains_key(&next) && cache.requested.insert(next) {
+                let _ = self.requests.send(next);
+            }
+        }
+    }
+}
+
+impl Cache {
+    fn clear(&mut self) {
+        self.chunks.clear();
+        self.requested.clear();
+    }
+
+    fn insert(&mut self, index: u64, data: Vec<u8>) {
+        if self.chunks.len() >= self.capacity
+            && let Some(&oldest) = self.chunks.iter()
+                .min_by_key(|(_, chunk)| chunk.last_used)
+                .map(|(index, _)| index)
+        {
+            self.chunks.remove(&oldest);
+        }
+
+        self.clock += 1;
+        self.chunks.insert(index, Chunk { data, last_used: self.clock, used: false });
+    }
+
+    /// Copies the data of a cached chunk from `start` on into `buf`. Returns the number of bytes
+    /// copied, the size of the chunk, and whether it was used for the first time.
+    fn copy(&mut self, index: u64, start: usize, buf: &mut [u8]) -> Option<(usize, usize, bool)> {
+        self.clock += 1;
+
+        let chunk = self.chunks.get_mut(&index)?;
+        chunk.last_used = self.clock;
+
+        let first_use = !chunk.used;
+        chunk.used = true;
+
+        let available = chunk.data.get(start..).unwrap_or_default();
+        let length = available.len().min(buf.len());
+        buf[..length].copy_from_slice(&available[..length]);
+
+        Some((length, chunk.data.len(), first_use))
+    }
+}
+
+impl<S: Source> Source for PrefetchSource<S> {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let mut total = 0;
+
+        while total < buf.len() {
+            let position = offset + total as u64;
+            let chunk_size = lock(&self.cache).chunk_size;
+            let index = position / chunk_size as u64;
+            let start = (position % chunk_size as u64) as usize;
+
+            let cached = lock(&self.cache).copy(index, start, &mut buf[total..]);
+
+            let (length, size, first_use) = match cached {
+                Some(copied) => copied,
+                None => {
+                    let mut source = lock(&self.source);
+
+                    // The background thread may have just read it.
+                    let cached = lock(&self.cache).copy(index, start, &mut buf[total..]);
+
+                    match cached {
+                        Some(copied) => copied,
+                        None => {
+                            let data = read_chunk(&mut *source, index, chunk_size)?;
+
+                            let mut cache = lock(&self.cache);
+                            cache.requested.remove(&index);
+                            cache.insert(index, data);
+                            cache.copy(index, start, &mut buf[total..]).expect("chunk is cached")
+                        }
+                    }
+                }
+            };
+
+            if first_use {
+                self.prefetch(index);
+            }
+
+            total += length;
+
+            // A partial chunk marks the end of the source.
+            if size < chunk_size || length == 0 {
+                break;
+            }
+        }
+
+        Ok(total)
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        let size = lock(&self.source).size()?;
+        let mut cache = lock(&self.cache);
+
+        // If the size has changed, so may have the data.
+        if cache.size != Some(size) {
+            cache.clear();
+            cache.size = Some(size);
+        }
+
+        Ok(size)
+    }
+
+    fn reload(&mut self) -> io::Result<()> {
+        let mut source = lock(&self.source);
+
+        // The source is locked first, so a chunk the background thread is reading isn't cached.
+        let mut cache = lock(&self.cache);
+        cache.clear();
+        cache.size = None;
+        drop(cache);
+
+        source.reload()
+    }
+
+    /// Describes the wrapped [`Source`], but with the chunk size as the block size.
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            block_size: Some(lock(&self.cache).chunk_size as u64),
+            ..self.info.clone()
+        }
+    }
+}
+
+/// Reads a whole chunk from `source`. It's shorter than the chunk size only at the end of the
+/// source.
+fn read_chunk<S: Source + ?Sized>(source: &mut S, index: u64, chunk_size: usize) -> io::Result<Vec<u8>> {
+    let offset = index * chunk_size as u64;
+    let mut data = vec![0; chunk_size];
+    let mut total = 0;
+
+    while total < data.len() {
+        let read = source.read(offset + total as u64, &mut data[total..])?;
+
+        if read == 0 {
+            break;
+        }
+
+        total += read;
+    }
+
+    data.truncate(total);
+
+    Ok(data)
+}
@@ -0,0 +1,41 @@
+use crate::hex::source::lock;
+use crate::hex::viewer::{Source, SourceInfo};
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+/// A [`Source`] that can be shared between multiple [`Content`]s, for instance for split views, a
+/// diff baseline or a minimap. Clones read from the same underlying source, one at a time.
+///
+/// [`Content`]: crate::hex::viewer::Content
+#[derive(Debug, Clone)]
+pub struct SharedSource {
+    source: Arc<Mutex<dyn Source + Send>>,
+}
+
+impl SharedSource {
+    /// Creates a new `SharedSource`. Clone it to share it.
+    pub fn new<S: Source + Send + 'static>(source: S) -> Self {
+        Self {
+            source: Arc::new(Mutex::new(source)),
+        }
+    }
+}
+
+impl Source for SharedSource {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        lock(&self.source).read(offset, buf)
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        lock(&self.source).size()
+    }
+
+    fn reload(&mut self) -> io::Result<()> {
+        lock(&self.source).reload()
+    }
+
+    fn info(&self) -> SourceInfo {
+        lock(&self.source).info()
+    }
+}
@@ -0,0 +1,150 @@
+use crate::hex::source::lock;
+use crate::hex::viewer::{Source, SourceInfo};
+
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A [`Source`] that wraps another one and keeps statistics about the reads that are passed to
+/// it, to tune block sizes, caching and prefetching for slow or remote sources.
+///
+/// The statistics are shared with a [`StatsHandle`], so they can be queried while the source is
+/// owned by a [`Content`]. Wrap a caching source, such as a [`CachedSource`], on both sides to find
+/// out how many reads its cache served:
+///
+/// ```ignore
+/// let backing = StatsSource::new(remote);
+/// let backing_stats = backing.handle();
+/// let cached = StatsSource::new(CachedSource::new(backing));
+/// let cached_stats = cached.handle();
+///
+/// // Later:
+/// let hit_ratio = cached_stats.get().hit_ratio(&backing_stats.get());
+/// ```
+///
+/// [`Content`]: crate::hex::viewer::Content
+/// [`CachedSource`]: crate::hex::source::CachedSource
+#[derive(Debug)]
+pub struct StatsSource<S> {
+    source: S,
+    stats: StatsHandle,
+}
+
+/// Statistics about the reads of a [`StatsSource`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SourceStats {
+    /// The number of reads, including failed and pending ones.
+    pub requests: u64,
+    /// The number of bytes read.
+    pub bytes: u64,
+    /// The number of reads that returned an error other than [`io::ErrorKind::WouldBlock`].
+    pub errors: u64,
+    /// The number of reads that returned [`io::ErrorKind::WouldBlock`].
+    pub pending: u64,
+    /// The time spent in all reads together.
+    pub total_latency: Duration,
+    /// The time spent in the slowest read.
+    pub max_latency: Duration,
+}
+
+impl SourceStats {
+    /// The average time spent in a read, if there were any.
+    pub fn average_latency(&self) -> Option<Duration> {
+        (self.requests > 0).then(|| self.total_latency.div_f64(self.requests as f64))
+    }
+
+    /// The average number of bytes per read, if there were any.
+    pub fn average_size(&self) -> Option<f64> {
+        (self.requests > 0).then(|| self.bytes as f64 / self.requests as f64)
+    }
+
+    /// The part of these reads that didn't reach the `backing` source, from 0 to 1, if there were
+    /// any. Use it with the statistics of the sources on both sides of a cache.
+    pub fn hit_ratio(&self, backing: &SourceStats) -> Option<f64> {
+        (self.requests > 0).then(|| {
+            1.0 - (backing.requests as f64 / self.requests as f64).min(1.0)
+        })
+    }
+}
+
+/// A shared handle to the [`SourceStats`] of a [`StatsSource`].
+#[derive(Debug, Clone, Default)]
+pub struct StatsHandle {
+    stats: Arc<Mutex<SourceStats>>,
+}
+
+impl StatsHandle {
+    /// The statistics so far.
+    pub fn get(&self) -> SourceStats {
+        *lock(&self.stats)
+    }
+
+    /// Starts over with empty statistics.
+    pub fn reset(&self) {
+        *lock(&self.stats) = SourceStats::default();
+    }
+}
+
+impl<S: Source> StatsSource<S> {
+    /// Creates a new `StatsSource` with empty statistics.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            stats: StatsHandle::default(),
+        }
+    }
+
+    /// A handle to the statistics, which stays valid after the `StatsSource` is moved into a
+    /// [`Content`](crate::hex::viewer::Content).
+    pub fn handle(&self) -> StatsHandle {
+        self.stats.clone()
+    }
+
+    /// Gets a reference to the wrapped [`Source`].
+    pub fn get_ref(&self) -> &S {
+        &self.source
+    }
+
+    /// Gets a mutable reference to the wrapped [`Source`].
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Unwraps the [`Source`].
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: Source> Source for StatsSource<S> {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let start = Instant::now();
+        let result = self.source.read(offset, buf);
+        let latency = start.elapsed();
+
+        let mut stats = lock(&self.stats.stats);
+        stats.requests += 1;
+        stats.total_latency += latency;
+        stats.max_latency = stats.max_latency.max(latency);
+
+        match &result {
+            Ok(read) => stats.bytes += *read as u64,
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => stats.pending += 1,
+            Err(_) => stats.errors += 1,
+        }
+
+        result
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        self.source.size()
+    }
+
+    fn reload(&mut self) -> io::Result<()> {
+        self.source.reload()
+    }
+
+    fn info(&self) -> SourceInfo {
+        self.source.info()
+    }
+}
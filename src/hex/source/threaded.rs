@@ -0,0 +1,171 @@
+use crate::hex::source::lock;
+use crate::hex::viewer::{Source, SourceInfo};
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// The number of finished reads a [`ThreadedSource`] keeps.
+const FINISHED_CAPACITY: usize = 1024;
+
+/// A [`Source`] that reads from another one on a background thread, so a slow source doesn't
+/// freeze the UI.
+///
+/// Reads that haven't finished return [`io::ErrorKind::WouldBlock`], and the [`HexViewer`]
+/// displays a placeholder for them. Set [`HexViewer::on_pending`] and call [`Content::poll`] in
+/// response to pick up the data once it has arrived. Finished reads are kept for a while, so the
+/// rows that are already shown don't have to be read again.
+///
+/// The size of the wrapped source is determined when the `ThreadedSource` is created, and again
+/// after [`Source::reload`]. Reloading happens on the background thread as well, and drops the
+/// finished reads.
+///
+/// [`HexViewer`]: crate::hex::viewer::HexViewer
+/// [`HexViewer::on_pending`]: crate::hex::viewer::HexViewer::on_pending
+/// [`Content::poll`]: crate::hex::viewer::Content::poll
+#[derive(Debug)]
+pub struct ThreadedSource {
+    requests: Sender<Request>,
+    /// The info of the wrapped source, taken before it moved to the background thread.
+    info: SourceInfo,
+    shared: Arc<Mutex<Shared>>,
+}
+
+/// A request to the background thread.
+#[derive(Debug)]
+enum Request {
+    /// Read the given number of bytes at the offset, for the given reload generation.
+    Read(u64, usize, u64),
+    Reload,
+}
+
+/// The result of an operation on the background thread. The error is taken apart, so it can be
+/// reconstructed every time the result is read.
+type Stored<T> = Result<T, (io::ErrorKind, String)>;
+
+/// The state shared with the background thread.
+#[derive(Debug, Default)]
+struct Shared {
+    size: Option<Stored<u64>>,
+    /// Requested reads that haven't finished.
+    requested: HashSet<(u64, usize)>,
+    finished: HashMap<(u64, usize), Stored<Vec<u8>>>,
+    /// The order the reads finished in, oldest first.
+    order: VecDeque<(u64, usize)>,
+    /// The number of reloads, so reads that started before a reload aren't kept.
+    generation: u64,
+}
+
+impl ThreadedSource {
+    /// Moves `source` to a new background thread. The thread stops when the `ThreadedSource` is
+    /// dropped.
+    pub fn new<S: Source + Send + 'static>(mut source: S) -> Self {
+        let info = source.info();
+        let (requests, receiver) = mpsc::channel::<Request>();
+        let shared = Arc::new(Mutex::new(Shared::default()));
+
+        let thread_shared = Arc::clone(&shared);
+
+        thread::spawn(move || {
+            let size = source.size().map_err(|error| (error.kind(), error.to_string()));
+            lock(&thread_shared).size = Some(size);
+
+            for request in receiver {
+                let (offset, length, generation) = match request {
+                    Request::Read(offset, length, generation) => (offset, length, generation),
+                    Request::Reload => {
+                        let size = source.reload()
+                            .and_then(|()| source.size())
+                            .map_err(|error| (error.kind(), error.to_string()));
+
+                        lock(&thread_shared).size = Some(size);
+                        continue;
+                    }
+                };
+
+                let mut buf = vec![0; length];
+
+                let result = source.read(offset, &mut buf)
+                    .map(|read| {
+                        buf.truncate(read);
+                        buf
+                    })
+                    .map_err(|error| (error.kind(), error.to_string()));
+
+                let mut shared = lock(&thread_shared);
+
+                if shared.generation != generation {
+                    continue;
+                }
+
+                shared.requested.remove(&(offset, length));
+
+                if shared.order.len() >= FINISHED_CAPACITY
+                    && let Some(oldest) = shared.order.pop_front()
+                {
+                    shared.finished.remove(&oldest);
+                }
+
+                shared.finished.insert((offset, length), result);
+                shared.order.push_back((offset, length));
+            }
+        });
+
+        Self {
+            requests,
+            info,
+            shared,
+        }
+    }
+}
+
+impl Source for ThreadedSource {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let key = (offset, buf.len());
+        let mut shared = lock(&self.shared);
+
+        match shared.finished.get(&key) {
+            Some(Ok(data)) => {
+                buf[..data.len()].copy_from_slice(data);
+                Ok(data.len())
+            }
+            Some(Err((kind, message))) => Err(io::Error::new(*kind, message.clone())),
+            None => {
+                let request = Request::Read(offset, buf.len(), shared.generation);
+
+                if shared.requested.insert(key) && self.requests.send(request).is_err() {
+                    shared.requested.remove(&key);
+                    return Err(io::Error::other("the reading thread has stopped"));
+                }
+
+                Err(io::ErrorKind::WouldBlock.into())
+            }
+        }
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        match &lock(&self.shared).size {
+            Some(Ok(size)) => Ok(*size),
+            Some(Err((kind, message))) => Err(io::Error::new(*kind, message.clone())),
+            None => Err(io::ErrorKind::WouldBlock.into()),
+        }
+    }
+
+    fn reload(&mut self) -> io::Result<()> {
+        let mut shared = lock(&self.shared);
+        shared.generation += 1;
+        shared.size = None;
+        shared.requested.clear();
+        shared.finished.clear();
+        shared.order.clear();
+
+        self.requests.send(Request::Reload)
+            .map_err(|_| io::Error::other("the reading thread has stopped"))
+    }
+
+    fn info(&self) -> SourceInfo {
+        self.info.clone()
+    }
+}
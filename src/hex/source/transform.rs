@@ -0,0 +1,386 @@
+use crate::hex::viewer::{Source, SourceInfo};
+
+use std::io;
+
+/// The number of decoded bytes between the positions a [`Base64DecodedSource`] remembers. A
+/// multiple of 3, so every position is at the start of a group of 4 characters.
+const CHECKPOINT_INTERVAL: u64 = 3 * 16 * 1024;
+
+/// The number of characters a [`Base64DecodedSource`] reads from the wrapped source at once.
+const ENCODED_CHUNK_SIZE: usize = 8 * 1024;
+
+/// A [`Source`] that wraps another one and XORs its bytes with a repeating key.
+///
+/// The key is aligned to the start of the data: the byte at offset `n` is XORed with the key byte
+/// at `n % key.len()`, no matter where a read starts. An empty key leaves the data as is.
+#[derive(Debug)]
+pub struct XorSource<S> {
+    source: S,
+    key: Vec<u8>,
+}
+
+impl<S: Source> XorSource<S> {
+    /// Creates a new `XorSource` that XORs the data of `source` with `key`.
+    pub fn new(source: S, key: impl Into<Vec<u8>>) -> Self {
+        Self { source, key: key.into() }
+    }
+
+    /// Gets a reference to the wrapped [`Source`].
+    pub fn get_ref(&self) -> &S {
+        &self.source
+    }
+
+    /// Gets a mutable reference to the wrapped [`Source`].
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Unwraps the [`Source`].
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: Source> Source for XorSource<S> {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.source.read(offset, buf)?;
+
+        if !self.key.is_empty() {
+            let length = self.key.len() as u64;
+
+            for (i, byte) in buf[..read].iter_mut().enumerate() {
+                *byte ^= self.key[((offset + i as u64) % length) as usize];
+            }
+        }
+
+        Ok(read)
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        self.source.size()
+    }
+
+    fn reload(&mut self) -> io::Result<()> {
+        self.source.reload()
+    }
+
+    fn info(&self) -> SourceInfo {
+        self.source.info()
+    }
+}
+
+/// A [`Source`] that wraps another one and adds a constant to each of its bytes, wrapping around
+/// at 256.
+///
+/// This undoes byte-wise rotation ciphers: data that had 3 added to every byte is shown as is
+/// with an amount of `3u8.wrapping_neg()`, which is 253.
+#[derive(Debug)]
+pub struct AddSource<S> {
+    source: S,
+    amount: u8,
+}
+
+impl<S: Source> AddSource<S> {
+    /// Creates a new `AddSource` that adds `amount` to every byte of `source`.
+    pub fn new(source: S, amount: u8) -> Self {
+        Self { source, amount }
+    }
+
+    /// Gets a reference to the wrapped [`Source`].
+    pub fn get_ref(&self) -> &S {
+        &self.source
+    }
+
+    /// Gets a mutable reference to the wrapped [`Source`].
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Unwraps the [`Source`].
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: Source> Source for AddSource<S> {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.source.read(offset, buf)?;
+
+        for byte in &mut buf[..read] {
+            *byte = byte.wrapping_add(self.amount);
+        }
+
+        Ok(read)
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        self.source.size()
+    }
+
+    fn reload(&mut self) -> io::Result<()> {
+        self.source.reload()
+    }
+
+    fn info(&self) -> SourceInfo {
+        self.source.info()
+    }
+}
+
+/// A [`Source`] that wraps another one holding base64 text, and shows the data it decodes to.
+///
+/// Both the standard and the URL-safe alphabet are accepted, and whitespace such as line breaks is
+/// skipped. Decoding stops at the first `=`. Other characters make reads fail with
+/// [`io::ErrorKind::InvalidData`].
+///
+/// Because of the skipped whitespace, an offset in the decoded data can't be converted to one in
+/// the text directly. The text is decoded from the start, and the position in the text of every
+/// 48 KiB of decoded data is remembered, so later reads only decode from the nearest one. The
+/// decoded size is only known once all text has been decoded, so the first call to
+/// [`Source::size`] decodes all of it, without keeping the result. These positions are forgotten
+/// when the size of the wrapped source changes.
+#[derive(Debug)]
+pub struct Base64DecodedSource<S> {
+    source: S,
+    /// The offset in the text of every [`CHECKPOINT_INTERVAL`] decoded bytes.
+    checkpoints: Vec<u64>,
+    /// The decoded and text offset where the last read stopped, to continue sequential reads.
+    resume: Option<(u64, u64)>,
+    /// The size of the wrapped source and the decoded size, once known.
+    size: Option<(u64, u64)>,
+}
+
+impl<S: Source> Base64DecodedSource<S> {
+    /// Creates a new `Base64DecodedSource` that decodes the text of `source`.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            checkpoints: vec![0],
+            resume: None,
+            size: None,
+        }
+    }
+
+    /// Gets a reference to the wrapped [`Source`].
+    pub fn get_ref(&self) -> &S {
+        &self.source
+    }
+
+    /// Gets a mutable reference to the wrapped [`Source`].
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Unwraps the [`Source`].
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+
+    /// Decodes from the closest known position before `offset` until `buf` is full or the text
+    /// ends, copying the decoded bytes from `offset` on into `buf`. Returns the number of bytes
+    /// copied and the decoded size if the end of the text was reached.
+    fn decode(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<(usize, Option<u64>)> {
+        let index = ((offset / CHECKPOINT_INTERVAL) as usize).min(self.checkpoints.len() - 1);
+        let mut decoded = index as u64 * CHECKPOINT_INTERVAL;
+        let mut text = Text::new(self.checkpoints[index]);
+
+        if let Some((resume_decoded, resume_text)) = self.resume
+            && resume_decoded <= offset
+            && resume_decoded > decoded
+        {
+            decoded = resume_decoded;
+            text = Text::new(resume_text);
+        }
+
+        let end = offset + buf.len() as u64;
+        let mut copied = 0;
+
+        while decoded < end {
+            if decoded.is_multiple_of(CHECKPOINT_INTERVAL)
+                && decoded / CHECKPOINT_INTERVAL == self.checkpoints.len() as u64
+            {
+                self.checkpoints.push(text.offset());
+            }
+
+            let Some((bytes, length)) = text.next_group(&mut self.source)? else {
+                self.resume = None;
+                return Ok((copied, Some(decoded)));
+            };
+
+            for (i, &byte) in bytes[..length].iter().enumerate() {
+                let position = decoded + i as u64;
+
+                if position >= offset && position < end {
+                    buf[(position - offset) as usize] = byte;
+                    copied += 1;
+                }
+            }
+
+            decoded += length as u64;
+
+            // Only full groups are followed by more data.
+            if length < 3 {
+                self.resume = None;
+                return Ok((copied, Some(decoded)));
+            }
+        }
+
+        self.resume = Some((decoded, text.offset()));
+
+        Ok((copied, None))
+    }
+}
+
+impl<S: Source> Source for Base64DecodedSource<S> {
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some((_, size)) = self.size
+            && offset >= size
+        {
+            return Ok(0);
+        }
+
+        let (copied, _) = self.decode(offset, buf)?;
+
+        Ok(copied)
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        let inner_size = self.source.size()?;
+
+        if let Some((size, decoded_size)) = self.size
+            && size == inner_size
+        {
+            return Ok(decoded_size);
+        }
+
+        // If the size has changed, so may have the text.
+        if self.size.is_some() {
+            self.checkpoints.truncate(1);
+            self.resume = None;
+        }
+
+        // Decode everything past the last known position, in pieces.
+        let mut scratch = vec![0; ENCODED_CHUNK_SIZE];
+        let mut offset = (self.checkpoints.len() as u64 - 1) * CHECKPOINT_INTERVAL;
+
+        let decoded_size = loop {
+            if let (_, Some(decoded_size)) = self.decode(offset, &mut scratch)? {
+                break decoded_size;
+            }
+
+            offset += scratch.len() as u64;
+        };
+
+        self.size = Some((inner_size, decoded_size));
+
+        Ok(decoded_size)
+    }
+
+    fn reload(&mut self) -> io::Result<()> {
+        self.checkpoints.truncate(1);
+        self.resume = None;
+        self.size = None;
+        self.source.reload()
+    }
+
+    /// Describes the wrapped [`Source`], but without a block size, since the text isn't read in
+    /// blocks of decoded data.
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            block_size: None,
+            ..self.source.info()
+        }
+    }
+}
+
+/// Reads the base64 text of a source in chunks, a group of 4 characters at a time.
+struct Text {
+    /// The offset of the first character in `buffer`.
+    buffer_offset: u64,
+    buffer: Vec<u8>,
+    /// The position of the next character in `buffer`.
+    position: usize,
+}
+
+impl Text {
+    fn new(offset: u64) -> Self {
+        Self {
+            buffer_offset: offset,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// The offset of the next character in the text.
+    fn offset(&self) -> u64 {
+        self.buffer_offset + self.position as u64
+    }
+
+    /// The value of the next character that isn't whitespace, or `None` at the end of the data.
+    fn next_value<S: Source + ?Sized>(&mut self, source: &mut S) -> io::Result<Option<u8>> {
+        loop {
+            if self.position == self.buffer.len() {
+                self.buffer_offset += self.buffer.len() as u64;
+                self.position = 0;
+                self.buffer.resize(ENCODED_CHUNK_SIZE, 0);
+
+                let read = source.read(self.buffer_offset, &mut self.buffer)?;
+                self.buffer.truncate(read);
+
+                if read == 0 {
+                    return Ok(None);
+                }
+            }
+
+            let character = self.buffer[self.position];
+
+            let value = match character {
+                b'A'..=b'Z' => character - b'A',
+                b'a'..=b'z' => character - b'a' + 26,
+                b'0'..=b'9' => character - b'0' + 52,
+                b'+' | b'-' => 62,
+                b'/' | b'_' => 63,
+                b'=' => return Ok(None),
+                b' ' | b'\t' | b'\r' | b'\n' => {
+                    self.position += 1;
+                    continue;
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid base64 character at offset {}", self.offset()),
+                    ));
+                }
+            };
+
+            self.position += 1;
+
+            return Ok(Some(value));
+        }
+    }
+
+    /// Decodes the next group of 4 characters into up to 3 bytes, and returns them with the number
+    /// of bytes. There are fewer at the end of the data, and `None` if there's no data left.
+    fn next_group<S: Source + ?Sized>(&mut self, source: &mut S) -> io::Result<Option<([u8; 3], usize)>> {
+        let mut values = [0u8; 4];
+        let mut count = 0;
+
+        while count < 4 {
+            match self.next_value(source)? {
+                Some(value) => {
+                    values[count] = value;
+                    count += 1;
+                }
+                None => break,
+            }
+        }
+
+        let bits = values.iter().fold(0u32, |bits, &value| bits << 6 | value as u32);
+        let bytes = [(bits >> 16) as u8, (bits >> 8) as u8, bits as u8];
+
+        // A single character doesn't make up a byte.
+        match count {
+            0 | 1 => Ok(None),
+            _ => Ok(Some((bytes, count - 1))),
+        }
+    }
+}
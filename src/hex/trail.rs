@@ -0,0 +1,158 @@
+use crate::hex::format::format_utc;
+
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// An ordered record of the offsets visited during an examination, with the time of each visit and
+/// optional notes, to document the steps that were taken.
+///
+/// The application decides what counts as a visit, for instance a jump to a search result or a
+/// bookmark, and calls [`NavigationTrail::visit`]. Steps can be undone and redone. The trail can be
+/// exported as a readable report with [`NavigationTrail::to_report`], and with the `serde` feature
+/// enabled it can be loaded from and saved to TOML or JSON:
+///
+/// ```toml
+/// [[step]]
+/// offset = 4096
+/// timestamp = 1760616000
+/// note = "Start of the image data"
+/// ```
+///
+/// Timestamps are seconds since the Unix epoch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NavigationTrail {
+    /// The visits, oldest first.
+    #[cfg_attr(feature = "serde", serde(default, rename = "step"))]
+    pub steps: Vec<TrailStep>,
+    /// Undone steps, most recently undone last.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    undone: Vec<TrailStep>,
+}
+
+/// A single visit of a [`NavigationTrail`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrailStep {
+    /// The visited offset.
+    pub offset: u64,
+    /// When the offset was visited, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// A note about the visit.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub note: Option<String>,
+}
+
+impl NavigationTrail {
+    /// Creates an empty [`NavigationTrail`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a visit of `offset` now. A visit of the offset of the last step isn't recorded
+    /// again. Steps that were undone can't be redone anymore.
+    pub fn visit(&mut self, offset: u64) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+
+        self.visit_at(offset, timestamp);
+    }
+
+    /// Records a visit of `offset` at `timestamp`, in seconds since the Unix epoch. Like
+    /// [`NavigationTrail::visit`], it's skipped if it's at the offset of the last step.
+    pub fn visit_at(&mut self, offset: u64, timestamp: u64) {
+        if self.steps.last().is_some_and(|step| step.offset == offset) {
+            return;
+        }
+
+        self.undone.clear();
+        self.steps.push(TrailStep { offset, timestamp, note: None });
+    }
+
+    /// Sets the note of the last step, if there is one.
+    pub fn note(&mut self, note: impl Into<String>) {
+        if let Some(step) = self.steps.last_mut() {
+            step.note = Some(note.into());
+        }
+    }
+
+    /// Removes the last step and returns the offset of the step before it, to go back to.
+    pub fn undo(&mut self) -> Option<u64> {
+        let step = self.steps.pop()?;
+        self.undone.push(step);
+
+        self.steps.last().map(|step| step.offset)
+    }
+
+    /// Restores the most recently undone step and returns its offset.
+    pub fn redo(&mut self) -> Option<u64> {
+        let step = self.undone.pop()?;
+        let offset = step.offset;
+        self.steps.push(step);
+
+        Some(offset)
+    }
+
+    /// Whether there are steps to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.steps.is_empty()
+    }
+
+    /// Whether there are undone steps to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Removes all steps, including undone ones.
+    pub fn clear(&mut self) {
+        self.steps.clear();
+        self.undone.clear();
+    }
+
+    /// Formats the steps as a Markdown table, with the times in UTC and the offsets in hex.
+    pub fn to_report(&self) -> String {
+        let mut report = String::from("| # | Time (UTC) | Offset | Note |\n|---|---|---|---|\n");
+
+        for (index, step) in self.steps.iter().enumerate() {
+            let note = step.note.as_deref().unwrap_or_default()
+                .replace('|', "\\|")
+                .replace('\n', " ");
+
+            let _ = writeln!(
+                report,
+                "| {} | {} | 0x{:08X} | {} |",
+                index + 1,
+                format_utc(step.timestamp),
+                step.offset,
+                note,
+            );
+        }
+
+        report
+    }
+
+    /// Parses a [`NavigationTrail`] from TOML.
+    #[cfg(feature = "serde")]
+    pub fn from_toml(toml: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(toml)
+    }
+
+    /// Serializes the [`NavigationTrail`] to TOML. Undone steps aren't included.
+    #[cfg(feature = "serde")]
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
+    /// Parses a [`NavigationTrail`] from JSON.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Serializes the [`NavigationTrail`] to JSON. Undone steps aren't included.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
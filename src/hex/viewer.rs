@@ -3,9 +3,11 @@ use crate::core::scroll_area::{
     ScrollAreaResult, ScrollResult, Viewport as ScrollViewport, State as ScrollAreaState
 };
 use crate::core::util::Timer;
+use crate::hex::format::{format_offset, format_utc, Radix};
+use crate::hex::minimap::Minimap;
+use crate::hex::palette::{GOLDEN_ANGLE, HighlightColor, HighlightPalette};
 
 use bitflags::bitflags;
-use encoding_rs;
 use iced_core::alignment;
 use iced_core::keyboard;
 use iced_core::layout::{self, Limits};
@@ -14,19 +16,46 @@ use iced_core::renderer::{self, Quad};
 use iced_core::text;
 use iced_core::keyboard::key;
 use iced_core::widget::tree::{self, Tree};
+use iced_core::widget::operation::Outcome;
+use iced_core::widget::{Id, Operation};
+use iced_core::window;
 use iced_core::{
     Background, Border, Clipboard, Color, Element, Event, Font, Length, Padding, Pixels, Point,
-    Rectangle, Renderer, Shell, Size, Text, Theme, Widget
+    Rectangle, Renderer, Shadow, Shell, Size, Text, Theme, Vector, Widget
 };
 use iced_widget::text::Wrapping;
-use std::fmt::Debug;
+use std::any::Any;
+use std::cell::{Ref, RefCell};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::{self, Debug};
+use std::io;
 use std::cmp::{PartialEq, Ordering};
-use std::time::{Instant};
-use std::ops::Range;
+use std::time::{Duration, Instant};
+use std::ops::{Range, RangeInclusive};
+use std::path::PathBuf;
 use std::sync::atomic;
 
 static CONTENT_COUNTER: atomic::AtomicU64 = atomic::AtomicU64::new(0);
 
+/// The largest number of bytes [`Content`] reads from its [`Source`] at once when it reads several
+/// rows at a time.
+const MAX_BATCH_SIZE: i64 = 64 * 1024;
+
+/// The largest number of bytes [`Content`] keeps for the rows around the viewport, see
+/// [`Content::margin_rows`].
+const MAX_MARGIN_SIZE: i64 = 4 * 1024 * 1024;
+
+/// The number of rows a new [`Content`] reads before it's given a viewport, which is enough to
+/// fill most windows.
+const INITIAL_ROWS: i64 = 64;
+/// The number of columns per row a new [`Content`] reads before it's given a viewport, the default
+/// of [`HexViewer::virtual_columns`].
+const INITIAL_COLUMNS: i64 = 32;
+
+/// The largest distance [`Content::run_jump`] scans from the cursor. A longer run is jumped over up
+/// to this distance, and the next jump continues from there.
+const MAX_RUN_SCAN: i64 = 64 * 1024 * 1024;
+
 /// A widget for viewing and interacting with binary data of virtually any size.
 pub struct HexViewer<'a, Message, Theme>
 where
@@ -34,20 +63,66 @@ where
 {
     content: &'a Content,
     cursor: i64,
+    ghost_cursor: Option<i64>,
+    crosshair: bool,
+    zebra_stripes: bool,
+    column_group: i64,
+    follow_tail: bool,
     width: Length,
     height: Length,
+    min_rows: Option<i64>,
+    max_rows: Option<i64>,
     font: Option<Font>,
     font_size: Option<Pixels>,
+    byte_display: ByteDisplay,
+    lowercase_hex: bool,
+    encoding: Encoding,
+    control_pictures: bool,
+    non_printable_char: char,
+    char_map: Option<&'a CharMap>,
+    row_value: Option<RowValueColumn<'a>>,
     virtual_columns: i64,
+    fit_columns: bool,
+    snap_columns: bool,
+    word_size: i64,
+    word_grouping: i64,
+    endianness: Endianness,
+    row_group: i64,
     horizontal_step: Step,
+    horizontal_scroll_strategy: HorizontalScrollStrategy,
+    char_header: CharHeader,
+    address_digits: Option<usize>,
+    address_format: AddressFormat,
+    base_address: u64,
     layout_settings: PaddingSettings,
     horizontal_navigation: Navigation,
     vertical_navigation: Navigation,
-    content_styler: Option<&'a ContentStyler>,
+    offset_styler: Option<&'a OffsetStyler>,
+    style_provider: Option<Box<dyn StyleProvider + 'a>>,
+    style_layers: Option<&'a StyleLayers>,
+    style_runs: Option<&'a StyleRuns>,
+    byte_class_coloring: bool,
+    /// Set if the application passed the current selection.
+    selection: Option<Option<Selection>>,
+    id: Option<Id>,
     on_cursor_moved: Option<Box<dyn Fn(u64) -> Message + 'a>>,
     on_scrolled: Option<Box<dyn Fn(Viewport) -> Message + 'a>>,
     on_logical_viewport_size_changed: Option<Box<dyn Fn(Viewport) -> Message + 'a>>,
     on_selection: Option<Box<dyn Fn(Option<Selection>) -> Message + 'a>>,
+    on_drag_started: Option<Box<dyn Fn(Selection) -> Message + 'a>>,
+    on_hover: Option<Box<dyn Fn(Option<HoveredByte>) -> Message + 'a>>,
+    on_right_click: Option<Box<dyn Fn(RightClick) -> Message + 'a>>,
+    on_double_click: Option<Box<dyn Fn(u64) -> Message + 'a>>,
+    on_layout: Option<Box<dyn Fn(LayoutSnapshot) -> Message + 'a>>,
+    on_read_error: Option<Box<dyn Fn(ReadError) -> Message + 'a>>,
+    on_content_changed: Option<Box<dyn Fn(u64) -> Message + 'a>>,
+    on_source_shrunk: Option<Box<dyn Fn(u64) -> Message + 'a>>,
+    on_cursor_history: Option<Box<dyn Fn(CursorHistory) -> Message + 'a>>,
+    on_file_dropped: Option<Box<dyn Fn(PathBuf) -> Message + 'a>>,
+    placeholder: Placeholder,
+    change_flash: Option<Duration>,
+    on_pending: Option<Box<dyn Fn() -> Message + 'a>>,
+    on_run_jump: Option<Box<dyn Fn(RunJump) -> Message + 'a>>,
     class: Theme::Class<'a>,
     scroll_area: ScrollArea<'a, Theme>,
 }
@@ -63,20 +138,65 @@ where
         Self {
             content,
             cursor: 0,
+            ghost_cursor: None,
+            crosshair: false,
+            zebra_stripes: false,
+            column_group: 0,
+            follow_tail: false,
             width: Length::Shrink,
             height: Length::Fill,
+            min_rows: None,
+            max_rows: None,
             font: None,
             font_size: None,
+            byte_display: ByteDisplay::default(),
+            lowercase_hex: false,
+            encoding: Encoding::default(),
+            control_pictures: false,
+            non_printable_char: '.',
+            char_map: None,
+            row_value: None,
             virtual_columns: 32,
+            fit_columns: false,
+            snap_columns: false,
+            word_size: 4,
+            word_grouping: 1,
+            endianness: Endianness::default(),
+            row_group: 0,
             horizontal_step: Step::default(),
+            horizontal_scroll_strategy: HorizontalScrollStrategy::default(),
+            char_header: CharHeader::default(),
+            address_digits: None,
+            address_format: AddressFormat::default(),
+            base_address: 0,
             layout_settings: PaddingSettings::default(),
             horizontal_navigation: Navigation::Lazy,
             vertical_navigation: Navigation::Lazy,
-            content_styler: None,
+            offset_styler: None,
+            style_provider: None,
+            style_layers: None,
+            style_runs: None,
+            byte_class_coloring: false,
+            selection: None,
+            id: None,
             on_cursor_moved: None,
             on_scrolled: None,
             on_logical_viewport_size_changed: None,
             on_selection: None,
+            on_drag_started: None,
+            on_hover: None,
+            on_right_click: None,
+            on_double_click: None,
+            on_layout: None,
+            on_read_error: None,
+            on_content_changed: None,
+            on_source_shrunk: None,
+            on_cursor_history: None,
+            on_file_dropped: None,
+            placeholder: Placeholder::default(),
+            change_flash: None,
+            on_pending: None,
+            on_run_jump: None,
             class: Theme::default(),
             scroll_area: ScrollArea::default()
                 .horizontal_scrollbar(HorizontalScrollbar::new())
@@ -84,9 +204,12 @@ where
         }
     }
 
-    /// Sets the width.
-    pub fn width(mut self, width: impl Into<Pixels>) -> Self {
-        self.width = Length::from(width.into());
+    /// Sets the width. With [`Length::Shrink`], the default, the [`HexViewer`] is as wide as its
+    /// columns require. Any other width, such as [`Length::Fill`], scrolls horizontally if the
+    /// columns don't fit, unless [`HexViewer::fit_columns`] is set. If it's wider than the columns
+    /// require, the space is left empty right of the char area.
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.width = width.into();
         self
     }
 
@@ -96,9 +219,46 @@ where
         self
     }
 
-    /// Sets the cursor, as an absolute offset into the [`Content`].
+    /// Sets the minimum number of rows the [`HexViewer`] makes room for, regardless of its height
+    /// and the size of the [`Content`].
+    pub fn min_rows(mut self, rows: u64) -> Self {
+        self.min_rows = Some(rows as i64);
+        self
+    }
+
+    /// Sets the maximum number of rows the [`HexViewer`] makes room for. Combined with
+    /// [`HexViewer::min_rows`] and a [`Length::Shrink`] height, this shows an exact number of rows,
+    /// for instance in a preview pane.
+    pub fn max_rows(mut self, rows: u64) -> Self {
+        self.max_rows = Some(rows as i64);
+        self
+    }
+
+    /// Sets the cursor, as an absolute offset into the [`Content`]. A cursor beyond the end of the
+    /// [`Content`] is displayed on its last byte, see [`HexViewer::on_source_shrunk`].
     pub fn cursor(mut self, cursor: u64) -> Self {
-        self.cursor = cursor as i64;
+        self.cursor = cursor.min(self.content.source_size.max(1) as u64 - 1) as i64;
+        self
+    }
+
+    /// Sets a second cursor that's only displayed, as an absolute offset into the [`Content`]. Use
+    /// this to show the cursor of a synchronized viewer, such as the other side of a diff.
+    pub fn ghost_cursor(mut self, cursor: Option<u64>) -> Self {
+        self.ghost_cursor = cursor.map(|cursor| cursor as i64);
+        self
+    }
+
+    /// Sets whether the row and the column of the cursor are shaded with [`Style::crosshair`] in
+    /// the byte and char areas, to keep track of the cursor in wide layouts.
+    pub fn crosshair(mut self, crosshair: bool) -> Self {
+        self.crosshair = crosshair;
+        self
+    }
+
+    /// Sets whether every other row of the byte and char areas has the [`Style::zebra_stripe`]
+    /// background, to follow rows across wide layouts. It's drawn below any other styling.
+    pub fn zebra_stripes(mut self, zebra_stripes: bool) -> Self {
+        self.zebra_stripes = zebra_stripes;
         self
     }
 
@@ -128,6 +288,15 @@ where
         self
     }
 
+    /// Sets whether the viewport follows the end of a growing [`Source`]. While the last row is
+    /// shown, the [`HexViewer`] scrolls down with [`HexViewer::on_scrolled`] when the size grows.
+    /// Scrolling up stops following until the last row is shown again. See
+    /// [`Content::check_size`].
+    pub fn follow_tail(mut self, follow: bool) -> Self {
+        self.follow_tail = follow;
+        self
+    }
+
     /// Sets the virtual number of columns. If this makes the content too wide horizontal scrollbars
     /// are displayed to scroll through the content.
     pub fn virtual_columns(mut self, columns: u64) -> Self {
@@ -135,6 +304,124 @@ where
         self
     }
 
+    /// Sets how the byte area displays the values of the bytes, in hex by default. The cells are as
+    /// wide as the digits need, so [`ByteDisplay::Binary`] fits fewer columns.
+    pub fn byte_display(mut self, display: ByteDisplay) -> Self {
+        self.byte_display = display;
+        self
+    }
+
+    /// Sets whether hex digits are lowercase, such as `7f` instead of `7F`, in the byte area, the
+    /// column headers and the address area. Uppercase by default.
+    pub fn lowercase_hex(mut self, lowercase: bool) -> Self {
+        self.lowercase_hex = lowercase;
+        self
+    }
+
+    /// Sets the [`Encoding`] that the char area decodes the bytes with, printable ASCII by default.
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Sets whether the char area shows ASCII control chars as their Unicode control pictures, such
+    /// as `␀` for NUL and `␊` for a line feed, instead of as the
+    /// [`HexViewer::non_printable_char`]. Combine it with [`HexViewer::byte_class_coloring`] to
+    /// color them as well.
+    pub fn control_pictures(mut self, control_pictures: bool) -> Self {
+        self.control_pictures = control_pictures;
+        self
+    }
+
+    /// Sets the char that the char area shows bytes without a printable char as, `.` by default.
+    /// Its color is [`Style::non_printable_text`].
+    pub fn non_printable_char(mut self, char: char) -> Self {
+        self.non_printable_char = char;
+        self
+    }
+
+    /// Sets the [`CharMap`] that the char area shows the bytes with, such as the text table of a
+    /// game ROM. It takes precedence over the [`HexViewer::encoding`].
+    pub fn char_map(mut self, char_map: &'a CharMap) -> Self {
+        self.char_map = Some(char_map);
+        self
+    }
+
+    /// Shows a column right of the char area with the [`RowValue`] that the bytes at the start of
+    /// every row decode to, such as a length field or a timestamp of fixed-size records.
+    pub fn row_value(self, value: RowValue) -> Self {
+        self.row_value_with(value.chars(), move |_, bytes| value.format(bytes))
+    }
+
+    /// Shows a column right of the char area with the text that `func` produces for every row, from
+    /// the offset of its first byte and its bytes. Only bytes that are loaded are passed, so there
+    /// may be fewer than a row. The column is `chars` chars wide; longer texts are cut off.
+    pub fn row_value_with(
+        mut self,
+        chars: usize,
+        func: impl Fn(u64, &[u8]) -> Option<String> + 'a,
+    ) -> Self {
+        self.row_value = Some(RowValueColumn { chars, format: Box::new(func) });
+        self
+    }
+
+    /// Sets whether the number of columns is derived from the width of the [`HexViewer`], instead of
+    /// set with [`HexViewer::virtual_columns`]. As many columns as fit are shown, with a minimum of
+    /// one. Only has an effect if the width isn't [`Length::Shrink`].
+    ///
+    /// The number of columns is reported through [`HexViewer::on_logical_viewport_resized`] as
+    /// [`Viewport::virtual_columns`], which should be passed on to [`Content::update`].
+    pub fn fit_columns(mut self, fit: bool) -> Self {
+        self.fit_columns = fit;
+        self
+    }
+
+    /// Sets whether the columns derived with [`HexViewer::fit_columns`] are rounded down to a power
+    /// of two, from 1 to 64, so that rows start at round offsets. Use [`power_of_two_columns`] to
+    /// snap a number of columns that the user picks in the same way.
+    pub fn snap_columns(mut self, snap: bool) -> Self {
+        self.snap_columns = snap;
+        self
+    }
+
+    /// Sets the size of the aligned group of bytes that is selected when a byte is double clicked,
+    /// similar to selecting a word in a text editor. Typical values are 2, 4 and 8. Triple clicking
+    /// selects the whole row.
+    pub fn word_size(mut self, size: u64) -> Self {
+        self.word_size = size.max(1) as i64;
+        self
+    }
+
+    /// Groups every aligned `size` bytes, 2, 4 or 8, into a word, to view tables of `u16`, `u32` or
+    /// `u64`. The byte area shows the bytes of a word most significant first, so in hex or binary
+    /// it reads as the value, in the given `endianness`. The cursor covers and moves by a word, and
+    /// selections span whole words. The char area still shows the bytes in their order.
+    ///
+    /// Grouping only applies if [`HexViewer::virtual_columns`] is a multiple of `size`, so no
+    /// word spans two rows. Other sizes turn it off, which is the default.
+    pub fn word_grouping(mut self, size: u64, endianness: Endianness) -> Self {
+        self.word_grouping = if matches!(size, 2 | 4 | 8) { size as i64 } else { 1 };
+        self.endianness = endianness;
+        self
+    }
+
+    /// Draws a separator line above every `rows`'th row, counted from the start of the data, to
+    /// make it easier to keep track of where you are while scrolling. With 16 columns, 16 rows
+    /// marks every 0x100 bytes. 0, the default, draws no separators. The line has the
+    /// [`Style::row_separator`] color.
+    pub fn row_groups(mut self, rows: u64) -> Self {
+        self.row_group = rows as i64;
+        self
+    }
+
+    /// Shades every other group of `columns` columns in the byte and char areas, starting with the
+    /// second group, so that groups of 4 or 8 columns are easy to tell apart. 0, the default,
+    /// shades nothing. The shade is the [`Style::column_group_shade`] background.
+    pub fn column_groups(mut self, columns: u64) -> Self {
+        self.column_group = columns as i64;
+        self
+    }
+
     /// Sets the horizontal [`Step`] that controls whether a horizontal scroll movement moves per
     /// column or per pixel.
     pub fn horizontal_step(mut self, step: Step) -> Self {
@@ -142,9 +429,65 @@ where
         self
     }
 
-    /// Sets the padding settings.
+    /// Sets whether the char area scrolls horizontally along with the byte area, or on its own.
+    /// See [`HorizontalScrollStrategy`].
+    pub fn horizontal_scroll_strategy(mut self, strategy: HorizontalScrollStrategy) -> Self {
+        self.horizontal_scroll_strategy = strategy;
+        self
+    }
+
+    /// Draws a [`Minimap`] in the track of the vertical scrollbar, which is widened to fit it. The
+    /// thumb becomes a translucent window over the minimap.
+    pub fn minimap(mut self, minimap: &'a Minimap) -> Self {
+        self.scroll_area = std::mem::take(&mut self.scroll_area).vertical_scrollbar(
+            VerticalScrollbar::new()
+                .track_width(MINIMAP_WIDTH)
+                .thumb_width(MINIMAP_WIDTH)
+                .minimap(minimap.colors())
+        );
+        self
+    }
+
+    /// Sets how the column offsets in the char area header are displayed.
+    pub fn char_header(mut self, char_header: CharHeader) -> Self {
+        self.char_header = char_header;
+        self
+    }
+
+    /// Fixes the number of digits in the address area, instead of deriving it from the size of the
+    /// [`Source`]. Shorter addresses are padded, longer ones are truncated to their least
+    /// significant digits behind an ellipsis. The prefix of [`AddressFormat::prefix`] isn't
+    /// counted. Use this to keep the address areas of multiple stacked [`HexViewer`]s aligned.
+    pub fn address_digits(mut self, digits: usize) -> Self {
+        self.address_digits = Some(digits.max(1));
+        self
+    }
+
+    /// Sets how the addresses in the address area are written, in hex padded with zeros by
+    /// default.
+    pub fn address_format(mut self, format: AddressFormat) -> Self {
+        self.address_format = format;
+        self
+    }
+
+    /// Sets the address of the first byte, which is added to the offsets in the address area,
+    /// such as the image base `0x140000000` of an executable. 0 by default.
+    ///
+    /// Only the address area shows addresses. The cursor, selections and all offsets passed to and
+    /// from the [`HexViewer`] stay offsets into the [`Source`] from 0. Use [`to_address`] and
+    /// [`from_address`] to convert between the two, [`Selection::address_ranges`] to show a
+    /// selection as addresses, and [`GotoBar::base_address`] to go to addresses.
+    ///
+    /// [`GotoBar::base_address`]: crate::hex::goto_bar::GotoBar::base_address
+    pub fn base_address(mut self, base: u64) -> Self {
+        self.base_address = base;
+        self
+    }
+
+    /// Sets the padding settings. Values outside of [`PaddingSettings::VALID_RANGE`] are clamped,
+    /// see [`PaddingSettings::clamped`].
     pub fn padding_settings(mut self, settings: PaddingSettings) -> Self {
-        self.layout_settings = settings;
+        self.layout_settings = settings.clamped();
         self
     }
 
@@ -180,9 +523,64 @@ where
         self
     }
 
-    /// Sets the [`ContentStyler`], which is used to color of the bytes/chars.
-    pub fn content_styler(mut self, content_style: &'a ContentStyler) -> Self {
-        self.content_styler = Some(content_style);
+    /// Sets the [`OffsetStyler`], which is used to color the bytes/chars.
+    #[deprecated(note = "use `HexViewer::offset_styler`")]
+    pub fn content_styler(self, styler: &'a OffsetStyler) -> Self {
+        self.offset_styler(styler)
+    }
+
+    /// Sets the [`OffsetStyler`], which colors the bytes/chars by their absolute offset.
+    pub fn offset_styler(mut self, styler: &'a OffsetStyler) -> Self {
+        self.offset_styler = Some(styler);
+        self
+    }
+
+    /// Sets the [`StyleProvider`], which computes the style of the bytes/chars from their offset and
+    /// value while they're drawn, such as a coloring by byte class. A closure works as well, its
+    /// parameters have to be annotated: `|offset: u64, value: u8| ...`. The [`StyleLayers`] and
+    /// [`OffsetStyler`] take precedence over it.
+    pub fn style_provider(mut self, provider: impl StyleProvider + 'a) -> Self {
+        self.style_provider = Some(Box::new(provider));
+        self
+    }
+
+    /// Sets the [`StyleLayers`], which color the bytes/chars per category. Enabled layers take
+    /// precedence over the [`OffsetStyler`] set by [`HexViewer::offset_styler`].
+    pub fn style_layers(mut self, layers: &'a StyleLayers) -> Self {
+        self.style_layers = Some(layers);
+        self
+    }
+
+    /// Sets the [`StyleRuns`], which color the bytes/chars by absolute ranges. Use this for
+    /// colorings that span the whole source, such as by entropy or byte class. The
+    /// [`StyleLayers`], [`OffsetStyler`] and [`StyleProvider`] take precedence over it.
+    pub fn style_runs(mut self, runs: &'a StyleRuns) -> Self {
+        self.style_runs = Some(runs);
+        self
+    }
+
+    /// Colors the text of the bytes/chars by their [`ByteClass`], such as null bytes and printable
+    /// ASCII, with the [`Style::byte_classes`] colors. All other styling takes precedence over it.
+    pub fn byte_class_coloring(mut self, enabled: bool) -> Self {
+        self.byte_class_coloring = enabled;
+        self
+    }
+
+    /// Sets the current selection. The [`HexViewer`] doesn't need to know about the selection to
+    /// function, but passing it lets the user continue a selection that was made by the
+    /// application, such as a search hit, with SHIFT. To change the selection from outside of the
+    /// view, see [`select`] and [`extend_selection`]. The selection is cut off at the end of the
+    /// [`Content`], see [`Selection::clamp`].
+    pub fn selection(mut self, selection: Option<Selection>) -> Self {
+        let size = self.content.source_size as u64;
+        self.selection = Some(selection.and_then(|selection| selection.clamp(size)));
+        self
+    }
+
+    /// Sets the [`Id`] of the [`HexViewer`], which is needed to target it with operations such as
+    /// [`select`].
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = Some(id.into());
         self
     }
 
@@ -217,6 +615,123 @@ where
         self
     }
 
+    /// Sets the message that should be produced when the user presses the mouse on the current
+    /// selection and drags it. Use this to start a drag and drop operation with the selected bytes,
+    /// for instance as hex text or a temporary file, since the payload and the way to hand it to the
+    /// platform are up to the application. If unset, dragging on the selection starts a new
+    /// selection instead.
+    ///
+    /// The current selection is only known if it was made by the user or passed with
+    /// [`HexViewer::selection`].
+    pub fn on_drag_started(mut self, func: impl Fn(Selection) -> Message + 'a) -> Self {
+        self.on_drag_started = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the message that should be produced when the mouse starts hovering another byte, or
+    /// `None` when it stops hovering bytes. Use it for status bars, tooltips or inspector panels
+    /// that follow the mouse. Only changes are reported, including ones because the bytes scrolled
+    /// under a stationary mouse.
+    pub fn on_hover(mut self, func: impl Fn(Option<HoveredByte>) -> Message + 'a) -> Self {
+        self.on_hover = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the message that should be produced when the user right-clicks the address, byte or
+    /// char area or one of the headers, to open a context menu. The [`RightClick`] tells what was
+    /// clicked and whether it's in the current selection. The cursor and selection don't change.
+    pub fn on_right_click(mut self, func: impl Fn(RightClick) -> Message + 'a) -> Self {
+        self.on_right_click = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the message that should be produced when the user double-clicks a byte or char, with
+    /// the absolute offset of the byte, for instance to follow a pointer or open the structure at
+    /// it. The double-click still selects the word under the mouse, and the first click of it
+    /// already moved the cursor.
+    pub fn on_double_click(mut self, func: impl Fn(u64) -> Message + 'a) -> Self {
+        self.on_double_click = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the message that should be produced when the layout has changed, for instance because
+    /// the [`HexViewer`] was resized or scrolled. See [`LayoutSnapshot`].
+    pub fn on_layout(mut self, func: impl Fn(LayoutSnapshot) -> Message + 'a) -> Self {
+        self.on_layout = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the message that should be produced for each error the [`Source`] returned during the
+    /// last [`Content::update`]. Unreadable bytes are displayed as a placeholder either way.
+    pub fn on_read_error(mut self, func: impl Fn(ReadError) -> Message + 'a) -> Self {
+        self.on_read_error = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the message that should be produced when the [`Content::generation`] changes, or a
+    /// different [`Content`] is passed. The function receives the new generation.
+    pub fn on_content_changed(mut self, func: impl Fn(u64) -> Message + 'a) -> Self {
+        self.on_content_changed = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the message that should be produced when the [`Source`] of the [`Content`] has become
+    /// smaller, for instance because a file that's being watched was truncated. The function
+    /// receives the new size. The cursor and selection are displayed within the new size, but
+    /// the application should clamp its own copies in response, see [`Selection::clamp`].
+    pub fn on_source_shrunk(mut self, func: impl Fn(u64) -> Message + 'a) -> Self {
+        self.on_source_shrunk = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the message that should be produced when the [`CursorHistory`] changes, because the
+    /// cursor jumped or the user went back or forward with ALT+LEFT and ALT+RIGHT. Use it to show
+    /// the history, for instance as breadcrumbs.
+    pub fn on_cursor_history(mut self, func: impl Fn(CursorHistory) -> Message + 'a) -> Self {
+        self.on_cursor_history = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the message that should be produced when a file is dropped on the window, with the
+    /// path of the file. While a file is dragged over the window, the data area is highlighted as a
+    /// drop target, and the [`Placeholder::drop_hint`] is displayed. Since the window doesn't
+    /// report where a file is dropped, set this on a single [`HexViewer`] per window.
+    pub fn on_file_dropped(mut self, func: impl Fn(PathBuf) -> Message + 'a) -> Self {
+        self.on_file_dropped = Some(Box::new(func));
+        self
+    }
+
+    /// Sets what's displayed instead of data when the [`Content`] is empty, such as a default
+    /// [`Content`] before a file has been opened.
+    pub fn placeholder(mut self, placeholder: Placeholder) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    /// Briefly highlights the bytes that changed when the [`Content`] was read again, such as after
+    /// a [`Content::refresh`] of a live source, like the memory view of a debugger does. The
+    /// highlight fades out over `duration`. See [`Content::changes`] and [`Style::change_flash`].
+    pub fn change_flash(mut self, duration: Duration) -> Self {
+        self.change_flash = Some(duration);
+        self
+    }
+
+    /// Sets the message that should be produced periodically while the [`Content`] is waiting for
+    /// data from its [`Source`]. Call [`Content::poll`] in response.
+    pub fn on_pending(mut self, func: impl Fn() -> Message + 'a) -> Self {
+        self.on_pending = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the message that should be produced when CTRL and an arrow key jump over a run of
+    /// identical bytes that continues beyond the data that was read. Without it, the cursor stops
+    /// at the edge of the data that was read. Find the end of the jump with [`Content::run_jump`]
+    /// and move the cursor there, for instance with [`scroll_to`] and [`extend_selection`].
+    pub fn on_run_jump(mut self, func: impl Fn(RunJump) -> Message + 'a) -> Self {
+        self.on_run_jump = Some(Box::new(func));
+        self
+    }
+
     /// Sets the style of the [`HexViewer`].
     pub fn style(mut self, style: impl Fn(&Theme, Status) -> Style + 'a) -> Self
     where
@@ -226,10 +741,114 @@ where
         self
     }
 
-    /// Calculates the number of chars needed to address the highest offset.
+    /// The settings that the cached texts depend on.
+    fn text_settings(&self) -> TextSettings {
+        TextSettings {
+            font: self.font,
+            font_size: self.font_size,
+            byte_display: self.byte_display,
+            lowercase: self.lowercase_hex,
+            encoding: self.encoding,
+            control_pictures: self.control_pictures,
+            non_printable_char: self.non_printable_char,
+        }
+    }
+
+    /// Measures the [`CellMetrics`] for the font and font size this [`HexViewer`] is configured
+    /// with. This needs a renderer, which application code usually doesn't have. The metrics the
+    /// [`HexViewer`] measured itself are the [`LayoutSnapshot::metrics`], published with
+    /// [`HexViewer::on_layout`] and retrieved with the [`layout_snapshot`] operation.
+    pub fn cell_metrics<R>(&self, renderer: &R) -> CellMetrics
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone + Default,
+    {
+        let mut text_cache = TextCache::<R>::new();
+        text_cache.set(self.text_settings(), renderer);
+        text_cache.metrics().into()
+    }
+
+    /// Creates the [`CellLayout`] this [`HexViewer`] has when given `bounds`, for the current
+    /// viewport of its [`Content`]. Use this to position overlays over specific cells, or to find
+    /// out which cell is under a point, without duplicating the [`HexViewer`]'s layout math. The
+    /// `metrics` can be taken from the last [`LayoutSnapshot::metrics`], or measured with
+    /// [`HexViewer::cell_metrics`].
+    pub fn layout_for(&self, bounds: Rectangle, metrics: CellMetrics) -> CellLayout {
+        CellLayout {
+            layout: self.create_layout(metrics.into(), bounds, self.content.viewport.percentage_x),
+            viewport: self.content.viewport,
+            source_size: self.content.source_size,
+        }
+    }
+
+    fn layout_snapshot(&self, bounds: Rectangle, layout: &Layout) -> LayoutSnapshot {
+        LayoutSnapshot {
+            bounds,
+            address_area: layout.address_area,
+            byte_header: layout.byte_area_header,
+            char_header: layout.char_area_header,
+            byte_area: layout.byte_area,
+            char_area: layout.char_area,
+            row_height: layout.row_height(),
+            metrics: layout.metrics.into(),
+            viewport: self.content.viewport,
+            cells: CellLayout {
+                layout: layout.clone(),
+                viewport: self.content.viewport,
+                source_size: self.content.source_size,
+            },
+        }
+    }
+
+    /// Calculates the number of chars needed to address the highest offset in the
+    /// [`AddressFormat`], unless the digits are fixed with [`HexViewer::address_digits`].
     fn address_area_horizontal_char_count(&self) -> usize {
-        let highest_address = format!("{}", self.content.source_size);
-        highest_address.chars().count()
+        let format = self.address_format;
+        let digits = self.address_digits.unwrap_or_else(|| {
+            let highest = (self.content.source_size - 1).max(0) as u64;
+            format.radix.digits(self.base_address.saturating_add(highest))
+        });
+
+        format.prefix().len() + digits
+    }
+
+    /// Writes `address` in the [`AddressFormat`], padded to `digits` digits.
+    fn format_address(&self, address: u64, digits: usize) -> String {
+        let format = self.address_format;
+        let number = format_offset(address, format.radix, 0, self.lowercase_hex);
+
+        let padding = digits.saturating_sub(number.len());
+
+        if format.zero_padding {
+            format!("{}{}{number}", format.prefix(), "0".repeat(padding))
+        } else {
+            format!("{}{}{number}", " ".repeat(padding), format.prefix())
+        }
+    }
+
+    /// The number of bytes per word of [`HexViewer::word_grouping`], 1 if it doesn't apply.
+    fn grouping(&self) -> i64 {
+        if self.virtual_columns % self.word_grouping == 0 { self.word_grouping } else { 1 }
+    }
+
+    /// The offset of the word that contains `offset`, which is `offset` itself without grouping.
+    fn word_start(&self, offset: i64) -> i64 {
+        offset - offset % self.grouping()
+    }
+
+    /// The offset of the byte that's displayed in the byte area at `offset`, which differs from
+    /// `offset` for little endian words.
+    fn displayed_offset(&self, offset: i64) -> i64 {
+        let grouping = self.grouping();
+        let start = self.word_start(offset);
+
+        match self.endianness {
+            // A word at the end of the data that isn't complete is shown as is.
+            Endianness::Little if start + grouping <= self.content.source_size => {
+                start + grouping - 1 - (offset - start)
+            }
+            _ => offset,
+        }
     }
 
     fn cursor_can_decrease(&self) -> bool {
@@ -242,12 +861,92 @@ where
 
     /// Finds the new cursor position if the move is possible and None otherwise.
     fn move_cursor_left(&self) -> Option<i64> {
-        self.cursor_can_decrease().then(|| self.cursor - 1)
+        self.cursor_can_decrease().then(|| (self.word_start(self.cursor) - self.grouping()).max(0))
     }
 
     /// Finds the new cursor position if the move is possible and None otherwise.
     fn move_cursor_right(&self) -> Option<i64> {
-        self.cursor_can_increase().then(|| self.cursor + 1)
+        let next = self.word_start(self.cursor) + self.grouping();
+        (next < self.content.source_size).then_some(next)
+    }
+
+    /// Finds the new cursor position after jumping over a run of identical bytes, with CTRL and
+    /// the arrow keys, or None if the move isn't possible. Moving forward goes to the start of the
+    /// next run and moving back to the start of the run, or of the previous one if the cursor is
+    /// already at it. The bytes are compared `step` bytes apart, so a step of a row follows a
+    /// column.
+    ///
+    /// Only the data that was read is scanned, which is the viewport and the
+    /// [`Content::margin_rows`]. A run that continues beyond it is jumped over up to its edge, so
+    /// the next jump continues once the data there is read. See [`HexViewer::jump_over_run`] for
+    /// scanning the [`Source`].
+    fn move_cursor_over_run(&self, step: i64) -> Option<i64> {
+        let in_source = |offset: i64| (0..self.content.source_size).contains(&offset);
+        let value = |offset: i64| self.content.loaded_byte(offset);
+
+        let mut position = self.cursor + step;
+
+        if !in_source(position) {
+            return None;
+        }
+
+        if step > 0 {
+            position = self.cursor;
+
+            let Some(run) = value(position) else {
+                return Some(position + step);
+            };
+
+            while in_source(position + step) && value(position + step) == Some(run) {
+                position += step;
+            }
+
+            let next = position + step;
+
+            if in_source(next) {
+                Some(next)
+            } else {
+                (position != self.cursor).then_some(position)
+            }
+        } else {
+            let Some(run) = value(position) else {
+                return Some(position);
+            };
+
+            while in_source(position + step) && value(position + step) == Some(run) {
+                position += step;
+            }
+
+            Some(position)
+        }
+    }
+
+    /// Like [`HexViewer::move_cursor_over_run`], but if the run continues beyond the data that was
+    /// read, the application is asked to scan the [`Source`] with [`HexViewer::on_run_jump`], and
+    /// the cursor isn't moved.
+    fn jump_over_run(
+        &self, shell: &mut Shell<'_, Message>, step: i64, extend_selection: bool,
+    ) -> Option<i64> {
+        let target = self.move_cursor_over_run(step)?;
+        let loaded = |offset: i64| {
+            !(0..self.content.source_size).contains(&offset)
+                || self.content.loaded_byte(offset).is_some()
+        };
+
+        // Moving back stops at the start of the run, so the byte before it tells whether the run
+        // may continue.
+        let unloaded = !loaded(target) || (step < 0 && !loaded(target + step));
+
+        if unloaded && let Some(on_run_jump) = &self.on_run_jump {
+            let from = self.cursor as u64;
+
+            shell.publish((on_run_jump)(RunJump { from, step, extend_selection }));
+            shell.capture_event();
+
+            return None;
+        }
+
+        Some(target)
     }
 
     /// Finds the new cursor position if the move is possible and None otherwise.
@@ -275,6 +974,19 @@ where
         })
     }
 
+    /// Finds the new cursor position if the move is possible and None otherwise.
+    fn move_cursor_row_start(&self) -> Option<i64> {
+        let row_start = self.cursor - self.cursor % self.virtual_columns;
+        (self.cursor != row_start).then_some(row_start)
+    }
+
+    /// Finds the new cursor position if the move is possible and None otherwise.
+    fn move_cursor_row_end(&self) -> Option<i64> {
+        let row_end = (self.cursor - self.cursor % self.virtual_columns + self.virtual_columns - 1)
+            .min(self.content.source_size - 1);
+        (row_end > self.cursor).then_some(row_end)
+    }
+
     /// Finds the new cursor position if the move is possible and None otherwise.
     fn move_cursor_top(&self) -> Option<i64> {
         self.cursor_can_decrease().then_some(0)
@@ -367,13 +1079,20 @@ where
             }
         }.min(layout.max_viewport_y_offset()).max(0);
 
-        (column != self.content.viewport.x || percentage_x != self.content.viewport.percentage_x || row != self.content.viewport.y)
-            .then_some(self.create_viewport(layout, column, row, percentage_x))
+        let char_x = self.char_x_following(layout, target_column);
+        let viewport = self.create_viewport(layout, column, row, percentage_x);
+        let viewport = self.with_char_x(layout, viewport, char_x);
+
+        (column != self.content.viewport.x
+            || percentage_x != self.content.viewport.percentage_x
+            || row != self.content.viewport.y
+            || viewport.char_window() != self.content.viewport.char_window())
+            .then_some(viewport)
     }
 
     /// Determines what selection can be made between the two indices, if any. The order in which
     /// the indices are supplied doesn't matter.
-    fn selection(
+    fn linear_selection(
         &self,
         a: Index,
         b: Index,
@@ -386,59 +1105,316 @@ where
             + (left.side == Side::Left || left.side == Side::None) as i64
             + (right.side == Side::Right || right.side == Side::None) as i64;
 
-        (length > 0).then(|| Selection::new(start as u64, length as u64, current_cursor as u64))
+        if length <= 0 {
+            return None;
+        }
+
+        // Selections span whole words of the word grouping.
+        let grouping = self.grouping();
+        let end = self.word_start(start + length - 1) + grouping;
+        let start = self.word_start(start);
+        let length = end.min(self.content.source_size) - start;
+
+        Some(Selection::new(start as u64, length as u64, current_cursor as u64))
     }
 
-    fn create_layout(&self, metrics: HexMetrics, bounds: Rectangle, shift_x: f32) -> Layout {
-        let (dimensions, settings) =
-            self.create_layout_dimensions(metrics, bounds.size());
+    /// Determines the rectangular selection spanned by the two indices, if any. Unlike linear
+    /// selections, the sides of the cells are irrelevant: both corner cells are always included.
+    fn block_selection(&self, a: Index, b: Index) -> Option<Selection> {
+        (a.offset != b.offset).then(|| {
+            Selection::block(a.offset as u64, b.offset as u64, self.virtual_columns as u64)
+        })
+    }
 
-        Layout::new(
-            dimensions,
-            settings,
-            self.content.source_size,
-            self.virtual_columns,
-            metrics,
-            shift_x,
-            bounds,
-        )
+    /// Determines the selection of the aligned group of [`HexViewer::word_size`] bytes that
+    /// contains the offset.
+    fn word_selection(&self, offset: i64) -> Option<Selection> {
+        self.span_selection(offset - offset % self.word_size, self.word_size, offset)
     }
 
-    fn create_layout_dimensions(&self, metrics: HexMetrics, bounds_size: Size) -> (LayoutDimensions, HexPadding) {
-        let settings = HexPadding::new(&self.layout_settings, metrics);
+    /// Determines the selection of the row that contains the offset.
+    fn row_selection(&self, offset: i64) -> Option<Selection> {
+        self.span_selection(offset - offset % self.virtual_columns, self.virtual_columns, offset)
+    }
 
-        let dimensions = LayoutDimensions::new(
-            &settings,
-            self.virtual_columns,
-            metrics,
-            self.scroll_area.horizontal_scrollbar_height(),
-            self.scroll_area.vertical_scrollbar_width(),
-            self.content.source_size,
-            bounds_size,
-            self.height,
-        );
+    /// Determines the selection of the rows from the one that contains `anchor` up to and including
+    /// the one that contains `last`, in either order.
+    fn rows_selection(&self, anchor: i64, last: i64) -> Option<Selection> {
+        let (anchor_row, last_row) = (anchor / self.virtual_columns, last / self.virtual_columns);
+        let start = anchor_row.min(last_row) * self.virtual_columns;
+        let end = (anchor_row.max(last_row) + 1) * self.virtual_columns;
 
-        (dimensions, settings)
+        self.span_selection(start, end - start, last)
     }
 
-    /// Create the [`VirtualState`].
-    fn x_viewport(&self, layout: &Layout) -> ScrollViewport {
-        match self.horizontal_step {
-            Step::Cell => {
-                ScrollViewport::new(
-                    self.content.viewport.x,
-                    self.virtual_columns,
-                    layout.byte_cell_width,
-                    layout.byte_area_content().width.ceil(),
-                )
-            }
-            Step::Pixel => {
-                ScrollViewport::new(
-                    // Note that this automatically adjusts for sudden Step changes: going from Cell
-                    // to Pixel just assumes shift was 0, which is ok. Going from Pixel to Cell
-                    // silently drops the small shift and aligns the first (partially) visible byte
-                    // to the cell grid. Also, we round here instead of ceil since the percentage
-                    // should originate from the actual offset we're on.
+    /// The start of the row in the viewport at the absolute `point` in the address area, limited to
+    /// the rows with data.
+    fn address_area_row_start(&self, layout: &Layout, point: Point) -> Option<i64> {
+        if self.content.source_size == 0 {
+            return None;
+        }
+
+        let row = (self.content.viewport.y + layout.address_area_row(point).unwrap_or(0))
+            .min((self.content.source_size - 1) / self.virtual_columns);
+
+        Some(row * self.virtual_columns)
+    }
+
+    /// The column of the data that the byte or char header at the absolute `point` belongs to, if
+    /// `location` is one of them and the point is on a column.
+    fn header_column(&self, layout: &Layout, location: Location, point: Point) -> Option<i64> {
+        let (first, columns) = match location {
+            Location::ByteHeader => (self.content.viewport.x, self.content.viewport.columns),
+            Location::CharHeader => self.content.viewport.char_window(),
+            _ => return None,
+        };
+
+        let column = match location {
+            Location::ByteHeader => layout.byte_header_column(point)?,
+            _ => layout.char_header_column(point)?,
+        };
+
+        (column < columns && first + column < self.virtual_columns).then_some(first + column)
+    }
+
+    /// Determines the block selection of the `column`, widened to the word of the word grouping,
+    /// in the rows of the viewport, or in all rows if `whole` is set.
+    fn column_selection(&self, column: i64, whole: bool) -> Option<Selection> {
+        let columns = self.virtual_columns;
+        let left = self.word_start(column);
+        let right = (left + self.grouping()).min(columns) - 1;
+
+        let (top, bottom) = if whole {
+            (0, i64::MAX)
+        } else {
+            let viewport = &self.content.viewport;
+            (viewport.y, viewport.y + viewport.rows - 1)
+        };
+
+        // The last row may end before the column.
+        if self.content.source_size <= right {
+            return None;
+        }
+
+        let bottom = bottom.min((self.content.source_size - 1 - right) / columns);
+
+        (top <= bottom).then(|| Selection::block(
+            (top * columns + left) as u64,
+            (bottom * columns + right) as u64,
+            columns as u64,
+        ))
+    }
+
+    /// Whether the char area shows `value` as the [`HexViewer::non_printable_char`], with a
+    /// single-byte [`Encoding`].
+    fn is_non_printable(&self, value: u8) -> bool {
+        let picture = self.control_pictures && value.is_ascii_control();
+
+        !picture && self.encoding.decode(value).is_none()
+    }
+
+    /// How the cells of the `buffer` are shown in the char area with a multi-byte [`Encoding`],
+    /// in the order of its data. Each visible row is decoded once, along with the bytes around it
+    /// that its chars may span. Only loaded bytes are decoded.
+    fn glyphs(&self, buffer: Buffer) -> Vec<Glyph> {
+        let viewport = self.content.viewport;
+        let (x, columns) = self.content.window(buffer);
+        let code_unit = self.encoding.code_unit();
+        let mut glyphs = Vec::with_capacity((viewport.rows * columns).max(0) as usize);
+        let mut bytes = Vec::new();
+        let mut decoded = Vec::new();
+
+        for row in 0..viewport.rows {
+            let start = (viewport.y + row) * viewport.virtual_columns + x;
+            // Chars that end in the row may start before it, and chars that start in it may end
+            // after it.
+            let first = (start - (MAX_SEQUENCE_LENGTH - 1)).max(0);
+            let first = first - first % code_unit;
+            let end = start + columns + MAX_SEQUENCE_LENGTH - 1;
+
+            bytes.clear();
+            bytes.extend((first..end).map(|offset| self.content.loaded_byte(offset)));
+            decoded.clear();
+            decoded.resize(bytes.len(), Glyph::Invalid);
+
+            let mut index = 0;
+
+            while index < bytes.len() {
+                let mut sequence = [0; MAX_SEQUENCE_LENGTH as usize];
+                let mut length = 0;
+
+                for byte in bytes[index..].iter().map_while(|&byte| byte).take(sequence.len()) {
+                    sequence[length] = byte;
+                    length += 1;
+                }
+
+                match self.encoding.decode_sequence(&sequence[..length]) {
+                    Some((char, length)) => {
+                        decoded[index] = Glyph::Char(char);
+
+                        for glyph in decoded.iter_mut().skip(index + 1).take(length - 1) {
+                            *glyph = Glyph::Continuation;
+                        }
+
+                        index += length;
+                    }
+                    None => index += code_unit as usize,
+                }
+            }
+
+            let start = (start - first) as usize;
+            glyphs.extend_from_slice(&decoded[start..start + columns as usize]);
+        }
+
+        glyphs
+    }
+
+    /// Determines the selection of `length` bytes from `start`, cut off at the end of the source.
+    fn span_selection(&self, start: i64, length: i64, last: i64) -> Option<Selection> {
+        let length = length.min(self.content.source_size - start);
+        (length > 0).then(|| Selection::new(start as u64, length as u64, last as u64))
+    }
+
+    /// The text color of the byte/char in the `area`, if it's styled. [`Highlight`]s are turned
+    /// into colors with `resolve`.
+    fn styled_text_color(
+        &self, item: &ContentItem, area: Area, resolve: &Resolve,
+    ) -> Option<Color> {
+        let offset = item.offset as u64;
+
+        self.style_layers.and_then(|layers| layers.text_color(offset, area, resolve))
+            .or_else(|| self.offset_styler?.text_color(offset, area, resolve))
+            .or_else(|| {
+                self.style_provider.as_ref()?.style(offset, item.value)?.text_color(area, resolve)
+            })
+            .or_else(|| self.style_runs?.get(offset)?.text_color(area, resolve))
+    }
+
+    /// The background color of the byte/char in the `area`, if it's styled. [`Highlight`]s are
+    /// turned into colors with `resolve`.
+    fn styled_background_color(
+        &self, item: &ContentItem, area: Area, resolve: &Resolve,
+    ) -> Option<Color> {
+        let offset = item.offset as u64;
+
+        self.style_layers.and_then(|layers| layers.background_color(offset, area, resolve))
+            .or_else(|| self.offset_styler?.background_color(offset, area, resolve))
+            .or_else(|| {
+                self.style_provider.as_ref()?.style(offset, item.value)?
+                    .background_color(area, resolve)
+            })
+            .or_else(|| self.style_runs?.get(offset)?.background_color(area, resolve))
+    }
+
+    /// The border of the byte/char, if it's styled.
+    fn styled_border(&self, item: &ContentItem) -> Option<CellBorder> {
+        let offset = item.offset as u64;
+
+        self.style_layers.and_then(|layers| layers.border(offset))
+            .or_else(|| self.offset_styler?.border(offset))
+            .or_else(|| self.style_provider.as_ref()?.style(offset, item.value)?.border)
+            .or_else(|| self.style_runs?.get(offset)?.border)
+    }
+
+    /// The decorations of the byte/char, if it's styled.
+    fn styled_decorations(&self, item: &ContentItem) -> Option<Decorations> {
+        let offset = item.offset as u64;
+
+        self.style_layers.and_then(|layers| layers.decorations(offset))
+            .or_else(|| self.offset_styler?.decorations(offset))
+            .or_else(|| self.style_provider.as_ref()?.style(offset, item.value)?.decorations)
+            .or_else(|| self.style_runs?.get(offset)?.decorations)
+    }
+
+    /// The animation of the byte/char, if it's styled.
+    fn styled_animation(&self, item: &ContentItem) -> Option<CellAnimation> {
+        let offset = item.offset as u64;
+
+        self.style_layers.and_then(|layers| layers.animation(offset))
+            .or_else(|| self.offset_styler?.animation(offset))
+            .or_else(|| self.style_provider.as_ref()?.style(offset, item.value)?.animation)
+            .or_else(|| self.style_runs?.get(offset)?.animation)
+    }
+
+    fn create_layout(&self, metrics: HexMetrics, bounds: Rectangle, shift_x: f32) -> Layout {
+        let (dimensions, settings) =
+            self.create_layout_dimensions(metrics, bounds.size());
+
+        let mut layout = Layout::new(
+            dimensions,
+            settings,
+            self.content.source_size,
+            self.virtual_columns,
+            metrics,
+            shift_x,
+            bounds,
+        );
+
+        // The char area scrolls per cell on its own.
+        if self.horizontal_scroll_strategy == HorizontalScrollStrategy::Split {
+            layout.char_shift = 0.0;
+        }
+
+        layout
+    }
+
+    /// Calculates how many columns fit in the given width, at least one.
+    fn fitted_columns(
+        dim: &LayoutDimensions, settings: &HexPadding, metrics: HexMetrics, width: f32,
+    ) -> i64 {
+        let available = width
+            - dim.address_area_width
+            - dim.vertical_scrollbar_width
+            - settings.byte_area_padding().x()
+            - settings.char_area_padding().x();
+
+        let column_width = metrics.byte_width + 2.0 * settings.byte_horizontal
+            + metrics.char_width + 2.0 * settings.char_horizontal;
+
+        ((available / column_width).floor() as i64).max(1)
+    }
+
+    fn create_layout_dimensions(&self, metrics: HexMetrics, bounds_size: Size) -> (LayoutDimensions, HexPadding) {
+        let settings = HexPadding::new(
+            &self.layout_settings,
+            metrics,
+            self.char_header.rows(),
+            self.address_area_horizontal_char_count(),
+            self.row_value.as_ref().map_or(0, |row_value| row_value.chars),
+        );
+
+        let dimensions = LayoutDimensions::new(
+            &settings,
+            self.virtual_columns,
+            metrics,
+            self.scroll_area.horizontal_scrollbar_height(),
+            self.scroll_area.vertical_scrollbar_width(),
+            self.content.source_size,
+            bounds_size,
+            self.height,
+        );
+
+        (dimensions, settings)
+    }
+
+    /// Create the [`VirtualState`].
+    fn x_viewport(&self, layout: &Layout) -> ScrollViewport {
+        match self.horizontal_step {
+            Step::Cell => {
+                ScrollViewport::new(
+                    self.content.viewport.x,
+                    self.virtual_columns,
+                    layout.byte_cell_width,
+                    layout.byte_area_content().width.ceil(),
+                )
+            }
+            Step::Pixel => {
+                ScrollViewport::new(
+                    // Note that this automatically adjusts for sudden Step changes: going from Cell
+                    // to Pixel just assumes shift was 0, which is ok. Going from Pixel to Cell
+                    // silently drops the small shift and aligns the first (partially) visible byte
+                    // to the cell grid. Also, we round here instead of ceil since the percentage
+                    // should originate from the actual offset we're on.
                     (self.content.viewport.x as f64
                         * layout.byte_cell_width as f64
                         + layout.byte_shift as f64)
@@ -489,13 +1465,17 @@ where
             .min(layout.viewport_row_count_ceil())
             .max(0);
 
+        let (char_x, char_columns) = self.char_window(layout, self.content.viewport.char_x);
+
         Viewport {
             x,
             y: scroll_offset.y,
             columns,
             rows,
             percentage_x: shift_x,
-            virtual_columns: self.virtual_columns
+            virtual_columns: self.virtual_columns,
+            char_x,
+            char_columns,
         }
     }
 
@@ -509,13 +1489,57 @@ where
             .min(layout.viewport_row_count_ceil())
             .max(0);
 
+        let (char_x, char_columns) = self.char_window(layout, self.content.viewport.char_x);
+
         Viewport {
             x,
             y,
             columns,
             rows,
             percentage_x: shift_x,
-            virtual_columns: self.virtual_columns
+            virtual_columns: self.virtual_columns,
+            char_x,
+            char_columns,
+        }
+    }
+
+    /// Determines the first column and the number of columns of the char area, starting as close
+    /// to `char_x` as possible. Both are 0 unless the char area scrolls on its own.
+    fn char_window(&self, layout: &Layout, char_x: i64) -> (i64, i64) {
+        if self.horizontal_scroll_strategy != HorizontalScrollStrategy::Split {
+            return (0, 0);
+        }
+
+        let char_x = char_x.min(layout.max_char_x_offset()).max(0);
+        let columns = (self.virtual_columns - char_x)
+            .min(layout.char_column_count_ceil())
+            .max(1);
+
+        (char_x, columns)
+    }
+
+    /// Returns `viewport` with the char area starting as close to `char_x` as possible.
+    fn with_char_x(&self, layout: &Layout, viewport: Viewport, char_x: i64) -> Viewport {
+        let (char_x, char_columns) = self.char_window(layout, char_x);
+
+        Viewport {
+            char_x,
+            char_columns,
+            ..viewport
+        }
+    }
+
+    /// The char area's first column after scrolling it lazily to show `column`.
+    fn char_x_following(&self, layout: &Layout, column: i64) -> i64 {
+        let (char_x, _) = self.content.viewport.char_window();
+        let visible = layout.char_column_count_floor();
+
+        if column < char_x {
+            column
+        } else if column >= char_x + visible {
+            column - visible + 1
+        } else {
+            char_x
         }
     }
 
@@ -531,12 +1555,43 @@ where
     }
 
     fn index(&self, layout: &Layout, location: Location) -> Option<Index> {
+        if self.content.source_size == 0 {
+            return None;
+        }
+
         location.approximate_cell(self.virtual_columns, layout.viewport_row_count_ceil())
-            .map(|cell_location| {
+            .map(|mut cell_location| {
+                cell_location.col += self.column_shift(location);
                 self.cell_to_absolute(&cell_location)
             })
     }
 
+    /// The number of columns to add to a column in `location` to make it relative to the byte
+    /// area's columns. Only the char area can show other columns.
+    fn column_shift(&self, location: Location) -> i64 {
+        match location {
+            Location::CharArea(_) | Location::CharHeader => {
+                self.content.viewport.char_window().0 - self.content.viewport.x
+            }
+            _ => 0,
+        }
+    }
+
+    /// The rows in the viewport that start a [`HexViewer::row_groups`] group, except for the first
+    /// row of the data.
+    fn row_group_starts(&self) -> impl Iterator<Item = i64> + '_ {
+        let viewport = &self.content.viewport;
+
+        (0..viewport.rows).filter(move |&row| {
+            let absolute = viewport.y + row;
+
+            self.row_group > 0
+                && absolute > 0
+                && absolute % self.row_group == 0
+                && absolute * viewport.virtual_columns < self.content.source_size
+        })
+    }
+
     /// Determines if, and if so where, the offset is visible in the current viewport. The returned
     /// offset is the byte offset within the current viewport, so it starts at 0 at the top left
     /// cell. Note: may return Some if the offset is just outside the viewport, need to fix viewport
@@ -547,6 +1602,13 @@ where
         })
     }
 
+    /// Like [`HexViewer::offset_in_viewport`], but for the char area, which may show other columns.
+    fn char_offset_in_viewport(&self, offset: i64) -> Option<(i64, i64)> {
+        self.content.viewport.char_contains(offset as u64).map(|(col, row)| {
+            (col as i64, row as i64)
+        })
+    }
+
     fn row_fully_in_viewport(&self, row: i64, layout: &Layout) -> Option<i64> {
         // We ignore and percent stuff for now, just focusx on x, y col, and row.
 
@@ -740,6 +1802,20 @@ where
 
         let viewport = self.create_viewport_from_scroll_offset(&layout, scroll_offset);
 
+        // A new Content with the id of the previous one continues at the previous viewport. That's
+        // published once, instead of the viewport the new Content was created with.
+        if let Some(carried) = self.carried_over_viewport(state, &layout)
+            && let Some(on_scrolled) = &self.on_scrolled
+        {
+            if carried != self.content.viewport {
+                shell.publish((on_scrolled)(carried));
+                shell.request_redraw();
+                state.last_reported_viewport = Some((carried, self.content.id));
+            }
+
+            return layout;
+        }
+
         if viewport != self.content.viewport
             && Some((viewport, self.content.id)) != state.last_reported_viewport
             && let Some(func) = &self.on_logical_viewport_size_changed
@@ -773,6 +1849,34 @@ where
         };
     }
 
+    /// The viewport of the previous [`Content`], if the current one is a new one that was created
+    /// with its id, see [`Content::with_id`]. It's fitted to the `layout`, so it's clamped to the
+    /// size of the new source.
+    fn carried_over_viewport<R>(&self, state: &mut State<R>, layout: &Layout) -> Option<Viewport>
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        let current = (self.content.id, self.content.instance, self.content.viewport);
+
+        let previous = match state.content.replace(current) {
+            Some((id, instance, previous))
+                if id == self.content.id && instance != self.content.instance => previous,
+            _ => return None,
+        };
+
+        let x = previous.x.min(layout.max_viewport_x_offset()).max(0);
+        let y = previous.y.min(layout.max_viewport_y_offset()).max(0);
+
+        let shift_x = if self.horizontal_step == Step::Pixel {
+            previous.percentage_x
+        } else {
+            0.0
+        };
+
+        Some(self.create_viewport(layout, x, y, shift_x))
+    }
+
     fn publish_on_selection<R>(
         &self,
         state: &mut State<R>,
@@ -792,2100 +1896,7176 @@ where
         }
     }
 
-    fn publish_cursor_moved(
-        &self,
-        shell: &mut Shell<'_, Message>,
-        cursor: i64)
+    /// Makes the selection the one that is continued when the user selects with SHIFT.
+    fn anchor_selection<R>(&self, state: &mut State<R>, selection: Option<Selection>)
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
     {
-        if let Some(on_cursor_moved) = &self.on_cursor_moved {
-            let message = (on_cursor_moved)(cursor as u64);
-            shell.publish(message);
-            shell.capture_event();
-            shell.request_redraw();
-        }
+        state.start_index = selection.map(|selection| selection.anchor());
+        state.block_selection = selection.is_some_and(|selection| selection.block.is_some());
     }
-}
 
-impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer> for HexViewer<'a, Message, Theme>
-where
-    Renderer: text::Renderer<Font = Font> + 'static,
-    Renderer::Paragraph: Clone,
-    Theme: Catalog,
-{
-    fn size(&self) -> Size<Length> {
-        Size::new(self.width, self.height)
-    }
+    /// Publishes [`HexViewer::on_source_shrunk`] if the [`Content`] has become smaller since the
+    /// last update, and drops a selection that's being made if it started beyond the new end.
+    fn check_shrunk<R>(&self, state: &mut State<R>, shell: &mut Shell<'_, Message>)
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        let size = self.content.source_size;
 
-    fn layout(
-        &mut self,
-        tree: &mut Tree,
-        renderer: &Renderer,
-        limits: &Limits,
-    ) -> layout::Node {
-        let state = tree.state.downcast_mut::<State<Renderer>>();
+        if let Some((content_id, last_size)) = state.last_size
+            && content_id == self.content.id
+            && size < last_size
+        {
+            if state.start_index.is_some_and(|index| index.offset >= size) {
+                state.start_index = None;
+                state.dragging = false;
+                state.pending_drag = None;
+            }
 
-        state.text_cache.set(&self.font, self.font_size, renderer);
-        let metrics = state.text_cache.metrics();
-        let dim = self.create_layout_dimensions(metrics, Size::INFINITE).0;
+            if let Some(func) = &self.on_source_shrunk {
+                shell.publish((func)(size as u64));
+            }
+        }
 
-        layout::Node::new(limits.resolve(dim.width(), dim.height(), Size::ZERO))
+        state.last_size = Some((self.content.id, size));
     }
 
-    fn draw(
-        &self,
-        tree: &Tree,
-        renderer: &mut Renderer,
-        theme: &Theme,
-        _style: &renderer::Style,
-        layout: layout::Layout<'_>,
-        _cursor: Cursor,
-        _viewport: &Rectangle,
-    ) {
-        let state = tree.state.downcast_ref::<State<Renderer>>();
+    /// Starts highlighting the [`Content::changes`] when new ones are found, and keeps redrawing
+    /// while the highlight fades out.
+    fn update_flash<R>(&self, state: &mut State<R>, shell: &mut Shell<'_, Message>)
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        let change = (self.content.id, self.content.change_count);
 
-        let bounds = layout.bounds();
+        if state.last_change != Some(change) {
+            // Changes of another content aren't changes to what was displayed.
+            if self.change_flash.is_some()
+                && state.last_change.is_some_and(|(content_id, _)| content_id == change.0)
+            {
+                state.flash = Some((self.content.changes.clone(), Instant::now()));
+            }
 
-        let metrics = state.text_cache.metrics();
-        let layout = self.create_layout(metrics, bounds, self.content.viewport.percentage_x);
-        
-        let style = theme.style(&self.class, Status::Active);
+            state.last_change = Some(change);
+        }
 
-        let x_viewport = self.x_viewport(&layout);
-        let y_viewport = self.y_viewport(&layout);
+        if let Some((_, start)) = &state.flash {
+            if self.change_flash.is_none_or(|duration| start.elapsed() >= duration) {
+                state.flash = None;
+            }
 
-        // Draw background of all headers.
-        renderer.fill_quad(
-            Quad {
-                bounds: layout.headers_background(),
-                ..Quad::default()
-            },
-            style.header_background
-        );
+            shell.request_redraw();
+        }
+    }
 
-        // Draw the background of the address area.
-        renderer.fill_quad(
-            Quad {
-                bounds: layout.address_area,
-                ..Quad::default()
-            },
-            style.header_background
-        );
+    /// Requests a redraw for when the next visible [`CellAnimation`] changes.
+    fn request_animation_frame<R>(
+        &self, state: &State<R>, shell: &mut Shell<'_, Message>, now: Instant,
+    )
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        let elapsed = now.saturating_duration_since(state.animation_clock);
+        let char_buffer = self.content.char_buffer();
+        let items = self.content.iter(Buffer::Bytes)
+            .chain(self.content.iter(char_buffer).filter(|_| char_buffer != Buffer::Bytes))
+            .filter(|item| item.availability == Availability::Ready);
+
+        let mut next_change: Option<Duration> = None;
+
+        for item in items {
+            if let Some(animation) = self.styled_animation(&item) {
+                let Some(change) = animation.next_change(elapsed) else {
+                    shell.request_redraw();
+                    return;
+                };
 
-        // Draw the byte area headers.
-        renderer.with_layer(layout.byte_area_header, |renderer| {
-            if let Some(hovered_column) = state.hovered_column {
-                renderer.fill_quad(
-                    Quad {
-                        bounds: layout.byte_header_cell(hovered_column),
-                        ..Quad::default()
-                    },
-                    style.header_hover
-                );
+                next_change = Some(next_change.map_or(change, |next| next.min(change)));
             }
+        }
 
-            for col in 0 .. self.content.viewport.columns {
-                let col_val = (self.content.viewport.x + col) % 256;
+        if let Some(change) = next_change
+            && let Some(at) = state.animation_clock.checked_add(change)
+        {
+            shell.request_redraw_at(at);
+        }
+    }
 
-                let paragraph = if col_val < 0x10 {
-                    state.text_cache.hex_digit(col_val as u8).raw()
-                } else {
-                    state.text_cache.byte(col_val as u8).raw()
-                };
+    /// Carries out the commands that were sent by operations since the last update.
+    fn handle_commands<R>(
+        &mut self,
+        state: &mut State<R>,
+        shell: &mut Shell<'_, Message>,
+        layout: &Layout,
+    )
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        if let Some(command) = state.commands.history.take() {
+            self.navigate_history(state, shell, layout, command);
+        }
 
-                renderer.fill_paragraph(
-                    paragraph,
-                    layout.byte_header_text_position(col, col_val),
-                    style.header_text,
-                    layout.byte_area_header
-                );
-            }
-        });
+        if let Some(ScrollCommand { offset, alignment }) = state.commands.scroll.take() {
+            let offset = offset.map_or(self.cursor, |offset| offset.min(i64::MAX as u64) as i64);
+            self.align_viewport(state, shell, layout, offset, alignment);
+        }
 
-        // Draw the char area headers.
-        renderer.with_layer(layout.char_area_header, |renderer| {
-            if let Some(hovered_column) = state.hovered_column {
-                renderer.fill_quad(
-                    Quad {
-                        bounds: layout.char_header_cell(hovered_column),
-                        ..Quad::default()
-                    },
-                    style.header_hover
-                );
-            }
+        if let Some(amount) = state.commands.scroll_by.take() {
+            let y_viewport = self.y_viewport(layout);
+            let y = y_viewport.offset
+                .saturating_add(amount.rows(layout.viewport_row_count_floor()))
+                .clamp(0, y_viewport.virtual_max_offset());
 
-            for col in 0 .. self.content.viewport.columns {
-                // We only have space for one char, so we draw just the last hex digit.
-                let col_val = (self.content.viewport.x + col) % 16;
+            let scroll_offset = ScrollOffset::new(self.x_viewport(layout).offset, y);
+            self.publish_scrolled(
+                state, shell, self.create_viewport_from_scroll_offset(layout, scroll_offset));
+        }
 
-                renderer.fill_paragraph(
-                    state.text_cache.hex_digit(col_val as u8).raw(),
-                    layout.char_header_text_position(col),
-                    style.header_text,
-                    layout.char_area_header
-                );
+        match state.commands.selection.take() {
+            Some(SelectionCommand::Set(selection)) => {
+                self.anchor_selection(state, selection);
+                self.publish_on_selection(state, shell, selection);
             }
-        });
+            Some(SelectionCommand::Extend(offset)) => {
+                let start = state.start_index_or_set(self.cursor);
+                let offset = (offset as i64).min(self.content.source_size - 1).max(0);
+                let end = Index::new(offset, Side::None);
 
-        // Draw the address area.
-        renderer.with_layer(layout.address_area, |renderer| {
-            if let Some(hovered_row) = state.hovered_row
-                && y_viewport.offset + hovered_row < y_viewport.size
-            {
-                renderer.fill_quad(
-                    Quad {
-                        bounds: layout.address_area_cell(hovered_row),
-                        ..Quad::default()
-                    },
-                    style.header_hover
-                );
+                let selection = if state.block_selection {
+                    self.block_selection(start, end)
+                } else {
+                    self.linear_selection(start, end, offset)
+                };
+
+                self.publish_on_selection(state, shell, selection);
             }
-            let first_address = self.content.viewport.y * self.virtual_columns;
-            let fill = self.address_area_horizontal_char_count();
-            let content_bounds = layout.address_area_content();
+            None => {}
+        }
+    }
 
-            for row in 0..self.content.viewport.rows {
-                let address = first_address + row * self.virtual_columns;
-                let address_str = format!("{:0fill$X}", address, fill = fill);
+    /// Scrolls the viewport so the row of the absolute `offset` is at the `alignment`, and
+    /// publishes it. Offsets beyond the end are clamped to the last byte.
+    fn align_viewport<R>(
+        &mut self,
+        state: &mut State<R>,
+        shell: &mut Shell<'_, Message>,
+        layout: &Layout,
+        offset: i64,
+        alignment: Alignment,
+    )
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        let offset = offset.min(self.content.source_size - 1).max(0);
 
-                for (char_num, char_value) in address_str.chars().enumerate() {
-                    renderer.fill_paragraph(
-                        state.text_cache.char(char_value as u8).raw(),
-                        layout.address_area_digit_position(char_num as i64, row),
-                        style.header_text,
-                        content_bounds
-                    );
+        if let Some(viewport) = self.scroll_viewport(
+            offset,
+            layout,
+            Scroll::Lazy(LazyAlignment::Start),
+            Scroll::Aligned(alignment),
+        ) {
+            self.publish_scrolled(state, shell, viewport);
+        }
+    }
+
+    /// Records a jump of the cursor in the [`CursorHistory`]. The cursor is passed by the
+    /// application, so jumps are found by comparing it with the cursor of the previous update.
+    fn track_cursor_jumps<R>(&self, state: &mut State<R>, shell: &mut Shell<'_, Message>)
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        match state.last_cursor {
+            // The offsets of the history don't apply to other content.
+            Some((content_id, _)) if content_id != self.content.id => {
+                state.cursor_history.clear();
+                state.history_target = None;
+            }
+            Some((_, previous)) if previous != self.cursor => {
+                if state.history_target == Some(self.cursor) {
+                    state.history_target = None;
+                } else if (self.cursor - previous).unsigned_abs()
+                    > self.content.viewport.size() as u64
+                {
+                    state.cursor_history.record(previous as u64, self.cursor as u64);
                 }
             }
-        });
-
-        // Closure to draw the byte area and char area
-        let mut draw_content = |
-            bounds: Rectangle,
-            content_bounds: Rectangle,
-            cell: fn(&Layout, col: i64, row: i64) -> Rectangle,
-            text_position: fn(&Layout, col: i64, row: i64) -> Point,
-            paragraph: fn(&TextCache<Renderer>, u8) -> &text::paragraph::Plain<Renderer::Paragraph>|{
+            _ => {}
+        }
 
-            // Draw background of the content area.
-            renderer.fill_quad(
-                Quad {
-                    bounds,
-                    ..Quad::default()
-                },
-                style.background
-            );
+        state.last_cursor = Some((self.content.id, self.cursor));
 
-            renderer.start_layer(content_bounds);
+        if let Some(func) = &self.on_cursor_history
+            && state.last_reported_history != state.cursor_history
+        {
+            shell.publish((func)(state.cursor_history.clone()));
+            state.last_reported_history = state.cursor_history.clone();
+        }
+    }
 
-            // Draw the bytes/chars.
-            for item in self.content.iter() {
-                if let Some(styler) = self.content_styler
-                    && let Some(color) = styler.background_color(item.viewport_offset as usize)
-                {
-                    renderer.fill_quad(
-                        Quad {
-                            bounds: cell(&layout, item.column, item.row),
-                            ..Quad::default()
-                        },
-                        color,
-                    )
-                }
+    /// Moves the cursor back or forward in the [`CursorHistory`], and scrolls to it. Returns false
+    /// if there's no position to move to.
+    fn navigate_history<R>(
+        &mut self,
+        state: &mut State<R>,
+        shell: &mut Shell<'_, Message>,
+        layout: &Layout,
+        command: HistoryCommand,
+    ) -> bool
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        let target = match command {
+            HistoryCommand::Back => state.cursor_history.back(self.cursor as u64),
+            HistoryCommand::Forward => state.cursor_history.forward(self.cursor as u64),
+        };
 
-                let color = if let Some(styler) = self.content_styler {
+        let Some(target) = target else {
+            return false;
+        };
 
-                    styler.text_color(item.viewport_offset as usize).unwrap_or(style.text)
-                } else {
-                    style.text
-                };
+        let target = (target as i64).min(self.content.source_size - 1).max(0);
 
-                renderer.fill_paragraph(
-                    paragraph(&state.text_cache, item.value).raw(),
-                    text_position(&layout, item.column, item.row),
-                    color,
-                    content_bounds
-                );
-            };
+        state.history_target = Some(target);
+        state.start_index = None;
+        self.publish_cursor_moved(shell, target);
+        self.cursor = target;
 
-            // Draw the cursor
-            if let Some((col, row)) = self.offset_in_viewport( self.cursor) {
-                let quad = Quad {
-                    bounds: cell(&layout, col, row),
-                    border: Border {
-                        color: style.text,
-                        width: 1.0,
-                        ..Border::default()
-                    },
-                    ..Quad::default()
-                };
+        if let Some(viewport) = self.scroll_viewport(
+            target,
+            layout,
+            Scroll::Lazy(LazyAlignment::Start),
+            Scroll::Aligned(Alignment::Center),
+        ) {
+            self.publish_scrolled(state, shell, viewport);
+        }
 
-                renderer.fill_quad(
-                    quad,
-                    Color::TRANSPARENT,
-                )
-            }
+        true
+    }
 
-            renderer.end_layer();
-        };
+    /// Updates the hovered column and row to match what's under the mouse, if anything.
+    fn update_hover<R>(
+        &self,
+        state: &mut State<R>,
+        shell: &mut Shell<'_, Message>,
+        layout: &Layout,
+        cursor_over_abs: Option<Point>,
+    )
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        let location = cursor_over_abs.map(|mouse_pos| layout.pointer_location(mouse_pos));
 
-        if self.content.viewport.virtual_columns != 0 {
-            // Draw the entire byte area.
-            draw_content(
-                layout.byte_area,
-                layout.byte_area_content(),
-                Layout::byte_cell,
-                Layout::byte_text_position,
-                TextCache::<Renderer>::byte,
-            );
+        let column = location.and_then(|location| {
+            Some(location.column()? + self.column_shift(location))
+        });
+        if column != state.hovered_column {
+            state.hovered_column = column;
+            shell.request_redraw();
+        }
 
-            // Draw the entire char area.
-            draw_content(
-                layout.char_area,
-                layout.char_area_content(),
-                Layout::char_cell,
-                Layout::char_text_position,
-                TextCache::<Renderer>::char,
-            );
+        let row = location.and_then(|location| location.row());
+        if row != state.hovered_row {
+            state.hovered_row = row;
+            shell.request_redraw();
         }
 
-        // The scrollbars are drawn next to the content as opposed to hovering over it (and
-        // therefore obstructing it), but this might become configurable in the future. Either way
-        // it makes most sense draw the scrollbars last.
-        self.scroll_area.draw(
-            renderer,
-            theme,
-            layout.scroll_area_bounds(),
-            Some(x_viewport),
-            Some(y_viewport),
-        );
+        if let Some(on_hover) = &self.on_hover {
+            let hovered = location.and_then(|location| self.hovered_byte(location));
 
-        // Draw a border around the widget.
-        renderer.fill_quad(
-            Quad {
-                bounds,
-                border: style.border,
-                ..Quad::default()
-            },
-            Color::TRANSPARENT,
-        );
+            if hovered != state.last_reported_hover {
+                shell.publish((on_hover)(hovered));
+                state.last_reported_hover = hovered;
+            }
+        }
     }
 
-    fn tag(&self) -> tree::Tag {
-        tree::Tag::of::<State<Renderer>>()
+    /// Describes a right-click at `position`, unless it's outside of the areas and headers.
+    fn right_click<R>(
+        &self,
+        state: &State<R>,
+        layout: &Layout,
+        position: Point,
+    ) -> Option<RightClick>
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        let location = layout.pointer_location(position);
+
+        let (area, offset) = match location {
+            Location::ByteArea(_) | Location::CharArea(_) => {
+                let area = if matches!(location, Location::ByteArea(_)) {
+                    ClickArea::Bytes
+                } else {
+                    ClickArea::Chars
+                };
+
+                (area, self.hovered_byte(location).map(|byte| byte.offset))
+            }
+            Location::AddressArea => {
+                let offset = layout.address_area_row(position)
+                    .map(|row| (self.content.viewport.y + row) * self.virtual_columns)
+                    .filter(|offset| *offset < self.content.source_size)
+                    .map(|offset| offset as u64);
+
+                (ClickArea::Address, offset)
+            }
+            Location::ByteHeader => (ClickArea::ByteHeader, None),
+            Location::CharHeader => (ClickArea::CharHeader, None),
+            Location::Other => return None,
+        };
+
+        let in_selection = offset.zip(state.last_reported_selection)
+            .is_some_and(|(offset, selection)| selection.contains(offset));
+
+        Some(RightClick { offset, area, in_selection, position })
     }
 
-    fn state(&self) -> tree::State {
-        tree::State::new(State::<Renderer>::new())
+    /// The byte at `location`, if it's exactly on one.
+    fn hovered_byte(&self, location: Location) -> Option<HoveredByte> {
+        let (area, cell) = match location {
+            Location::ByteArea(DataLocation::Cell(cell)) => (Area::Bytes, cell),
+            Location::CharArea(DataLocation::Cell(cell)) => (Area::Chars, cell),
+            _ => return None,
+        };
+
+        let column = self.content.viewport.x + cell.col + self.column_shift(location);
+        let row = self.content.viewport.y + cell.row;
+        let offset = row * self.virtual_columns + column;
+
+        (cell.row >= 0
+            && (0..self.virtual_columns).contains(&column)
+            && (0..self.content.source_size).contains(&offset))
+            .then_some(HoveredByte { offset: offset as u64, area })
     }
 
-    // We assume this may get called multiple times in between two HexViewer::update() calls
-    fn update(
+    /// Scrolls the viewport while a selection is dragged beyond the edges of the area it was started
+    /// in, and extends the selection to the cell closest to the pointer. The further the pointer is
+    /// beyond the edge, the faster the viewport scrolls.
+    fn auto_scroll<R>(
         &mut self,
-        tree: &mut Tree,
-        event: &Event,
-        layout: layout::Layout<'_>,
-        cursor: Cursor,
-        _renderer: &Renderer,
-        _clipboard: &mut dyn Clipboard,
+        state: &mut State<R>,
         shell: &mut Shell<'_, Message>,
-        _viewport: &Rectangle,
-    ) {
-        let state = tree.state.downcast_mut::<State<Renderer>>();
-
-        let bounds = layout.bounds();
-        let cursor_over_abs = cursor.position_over(bounds);
-        let metrics = state.text_cache.metrics();
+        layout: &Layout,
+        x_viewport: ScrollViewport,
+        y_viewport: ScrollViewport,
+        cursor: Cursor,
+    )
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        let (true, Some(position), Some(start)) =
+            (state.dragging, cursor.position(), state.start_index)
+        else {
+            state.auto_scroll_timer = None;
+            return;
+        };
 
-        let layout = self.check_state(state, shell, metrics, bounds);
-        let x_viewport = self.x_viewport(&layout);
-        let y_viewport = self.y_viewport(&layout);
+        let (area, cell_width) = if state.drag_in_chars {
+            (layout.char_area_content(), layout.char_cell_width)
+        } else {
+            (layout.byte_area_content(), layout.byte_cell_width)
+        };
 
-        let result = self.scroll_area.update(
-            &mut state.scroll_area_state,
-            event,
-            layout.scroll_area_bounds(),
-            Some(x_viewport),
-            Some(y_viewport),
-            cursor,
-        );
+        // The number of steps to scroll at once grows quadratically with the distance past the
+        // edge, measured in cells. Scrolling only starts once the pointer is past the edge, so
+        // pressing and holding on the first row or column doesn't scroll.
+        let steps = |before: f32, after: f32, cell_size: f32| -> i64 {
+            let speed = |distance: f32| ((distance / cell_size) / 2.0 + 1.0).powi(2) as i64;
 
-        if let Some(scroll_offset) = self.handle_scroll_result(
-            state, shell, result, &layout, x_viewport, y_viewport)
-        {
-            self.publish_scrolled(
-                state, shell, self.create_viewport_from_scroll_offset(&layout, scroll_offset));
+            if before < 0.0 {
+                -speed(-before)
+            } else if after < 0.0 {
+                speed(-after)
+            } else {
+                0
+            }
+        };
+
+        let columns = steps(
+            position.x - area.x, area.x + area.width - position.x, cell_width);
+        let rows = steps(
+            position.y - area.y, area.y + area.height - position.y, layout.row_height());
+
+        if columns == 0 && rows == 0 {
+            state.auto_scroll_timer = None;
             return;
         }
 
-        // The event wasn't handled by ScrollArea; do our own processing.
-        match event {
-            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
-                if let Some(mouse_pos) = cursor_over_abs {
-                    state.focussed = true;
+        let now = Instant::now();
+        let timer = state.auto_scroll_timer.get_or_insert_with(|| Timer::new(now, 50));
+        let (finished, _) = timer.test(&now);
 
-                    let location = layout.pointer_location(mouse_pos);
+        if finished {
+            timer.set_at_interval(&now);
 
-                    // Handle a cell being clicked, or close to it.
-                    if let Some(index) = self.index(&layout, location) {
+            // Extend the selection to the pointer, clamped to the area. This uses the viewport as
+            // it was before the scroll below, so the selection trails the scroll by one step.
+            let clamped = Point::new(
+                position.x.clamp(area.x, area.x + area.width - 1.0),
+                position.y.clamp(area.y, area.y + area.height - 1.0),
+            );
 
-                        // If shift is held we try to continue a previously created selection, from
-                        // its starting point.
-                        if state.keyboard_modifiers.shift() {
-                            let start = state.start_index_or_set(self.cursor);
+            if let Some(index) = self.index(layout, layout.pointer_location(clamped)) {
+                let selection = if state.block_selection {
+                    self.block_selection(start, index)
+                } else {
+                    self.linear_selection(start, index, index.offset)
+                };
 
-                            self.publish_on_selection(
-                                state,
-                                shell,
-                                self.selection(start, index, index.offset)
-                            );
-                        } else {
-                            if index.offset != self.cursor {
-                                self.publish_cursor_moved(shell, index.offset);
-                            }
+                self.publish_on_selection(state, shell, selection);
+            }
 
-                            self.cursor = index.offset;
+            // A selection in a char area that scrolls on its own only scrolls that area.
+            let (char_steps, columns) = if state.drag_in_chars && self.content.viewport.is_split() {
+                (columns, 0)
+            } else {
+                (0, columns)
+            };
 
-                            // Start a drag interaction, even though the user may not intend to
-                            // drag. We'll cancel the drag later in that case.
-                            state.start_index = Some(index);
-                        }
+            let x_steps = match self.horizontal_step {
+                Step::Cell => columns,
+                Step::Pixel => (columns as f32 * layout.byte_cell_width) as i64,
+            };
 
-                        state.dragging = true;
-                    }
-                } else {
-                    // We lose focus if the button is pressed anywhere outside our widget, but
-                    // within the bounds of the drawable area of the main window.
-                    if cursor.position_over(*_viewport).is_some() {
-                        state.focussed = false;
-                    }
-                }
-            }
-            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
-                // Note that we're not resetting state.start_index here, that's on purpose: if we were
-                // actually dragging a selection we want to preserve where we started in case we
-                // want to continue using the SHIFT button. Even if there was just a click, we'll
-                // store the side of the byte/char the click happened, for now. This will
-                // influence the offset at which the SHIFT aided selection will start. May change it
-                // later if necessary.
-                state.dragging = false;
+            let scroll_offset = ScrollOffset::new(
+                (x_viewport.offset + x_steps).min(x_viewport.virtual_max_offset()).max(0),
+                (y_viewport.offset + rows).min(y_viewport.virtual_max_offset()).max(0),
+            );
+
+            if scroll_offset.x != x_viewport.offset
+                || scroll_offset.y != y_viewport.offset
+                || char_steps != 0
+            {
+                let viewport = self.create_viewport_from_scroll_offset(layout, scroll_offset);
+                let char_x = self.content.viewport.char_x + char_steps;
+                self.publish_scrolled(state, shell, self.with_char_x(layout, viewport, char_x));
             }
-            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
-                if let Some(mouse_pos) = cursor_over_abs {
-                    let location = layout.pointer_location(mouse_pos);
+        }
 
-                    if state.dragging
-                        && let Some(selection) = state.start_index
-                        && let Some(loc) = self.index(&layout, location)
-                    {
-                        self.publish_on_selection(
-                            state, shell, self.selection(selection, loc, loc.offset));
-                    }
+        if let Some(timer) = &state.auto_scroll_timer {
+            shell.request_redraw_at(timer.target());
+        }
+    }
 
-                    let column = location.column();
-                    if column != state.hovered_column {
-                        state.hovered_column = column;
-                        shell.request_redraw();
-                    }
+    /// Scrolls to the end if the [`Content`] has grown while its end was shown.
+    fn keep_following_tail<R>(
+        &mut self,
+        state: &mut State<R>,
+        shell: &mut Shell<'_, Message>,
+        layout: &Layout)
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        let y_viewport = self.y_viewport(layout);
+        let size = self.content.source_size;
+        let max_offset = y_viewport.virtual_max_offset();
+
+        let at_end = match state.tail {
+            Some(tail) if tail.content_id == self.content.id
+                && tail.at_end
+                && size > tail.size
+                && y_viewport.offset < max_offset =>
+            {
+                let scroll_offset = ScrollOffset::new(self.x_viewport(layout).offset, max_offset);
+                self.publish_scrolled(
+                    state, shell, self.create_viewport_from_scroll_offset(layout, scroll_offset));
 
-                    let row = location.row();
-                    if row != state.hovered_row {
-                        state.hovered_row = row;
-                        shell.request_redraw();
-                    }
-                }
+                true
             }
-            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
-                if !state.focussed {
-                    return;
-                }
+            _ => y_viewport.offset >= max_offset,
+        };
 
-                let maybe_new_cursor = match key.as_ref() {
-                    keyboard::Key::Named(key::Named::ArrowLeft) => {
-                        self.move_cursor_left()
-                    }
-                    keyboard::Key::Named(key::Named::ArrowRight) => {
-                        self.move_cursor_right()
-                    }
-                    keyboard::Key::Named(key::Named::ArrowUp) => {
-                        self.move_cursor_up()
-                    }
-                    keyboard::Key::Named(key::Named::ArrowDown) => {
-                        self.move_cursor_down()
-                    }
-                    keyboard::Key::Named(key::Named::PageUp) => {
-                        self.move_cursor_page_up(layout.viewport_row_count_floor())
-                    }
-                    keyboard::Key::Named(key::Named::PageDown) => {
-                        self.move_cursor_page_down(layout.viewport_row_count_floor())
-                    }
-                    keyboard::Key::Named(key::Named::Home) => {
-                        self.move_cursor_top()
-                    }
-                    keyboard::Key::Named(key::Named::End) => {
-                        self.move_cursor_bottom()
-                    }
-                    _ => {
-                        // Hitting the escape key cancels the selection without the need for moving
-                        // the cursor.
-                        if matches!(key, keyboard::Key::Named(key::Named::Escape)) {
-                            state.start_index = None;
+        state.tail = Some(Tail {
+            content_id: self.content.id,
+            size,
+            at_end,
+        });
+    }
 
-                            self.publish_on_selection(state, shell, None);
-                        }
+    /// Asks the application to poll the [`Content`] at an interval while data is pending.
+    fn poll_pending<R>(&self, state: &mut State<R>, shell: &mut Shell<'_, Message>)
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        let (true, Some(func)) = (self.content.is_pending(), &self.on_pending) else {
+            state.pending_timer = None;
+            return;
+        };
 
-                        // Any other keys are simply ignored.
-                        return
-                    }
-                };
+        let now = Instant::now();
+        let timer = state.pending_timer.get_or_insert_with(|| Timer::new(now, 50));
+        let (finished, _) = timer.test(&now);
 
-                // Check whether we're creating/modifying a selection by keyboard.
-                if modifiers.shift() {
-                    if let Some(new_cursor) = maybe_new_cursor {
-                        let selection = state.start_index_or_set(self.cursor);
-                        let new_index = Index::new(new_cursor, Side::None);
+        if finished {
+            timer.set_at_interval(&now);
+            shell.publish((func)());
+        }
 
-                        self.publish_on_selection(
-                            state, shell, self.selection(selection, new_index, new_cursor));
+        shell.request_redraw_at(timer.target());
+    }
 
-                        self.cursor = new_cursor;
-                    }
-                } else if let Some(new_cursor) = maybe_new_cursor {
-                        state.start_index = None;
-                        self.publish_cursor_moved(shell, new_cursor);
-                        self.cursor = new_cursor;
-                } else {
-                    // Applies when the cursor is alread at the start/end of the document and
-                    // can't be moved further, yet a movement key was pressed without shift.
-                    state.start_index = None;
-                    self.publish_on_selection(state, shell, None);
-                }
+    /// Carries out the notice commands sent by operations, and displays the queued notices one
+    /// after the other.
+    fn update_notices<R>(&self, state: &mut State<R>, shell: &mut Shell<'_, Message>, renderer: &R)
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone,
+    {
+        for command in std::mem::take(&mut state.commands.notices) {
+            match command {
+                NoticeCommand::Show(notice) => {
+                    state.notices.push_back(notice);
 
-                let get_scroll = |navigation: Navigation| {
-                    match navigation {
-                        Navigation::Lazy => {
-                            if matches!(key.as_ref(),
-                                keyboard::Key::Named(key::Named::ArrowLeft)
-                                | keyboard::Key::Named(key::Named::ArrowUp)
-                                | keyboard::Key::Named(key::Named::PageUp))
-                            {
-                                Scroll::Lazy(LazyAlignment::Start)
-                            } else {
-                                Scroll::Lazy(LazyAlignment::End)
-                            }
-                        }
-                        Navigation::Aligned(alignment) => {
-                            Scroll::Aligned(alignment)
-                        }
+                    if state.notices.len() > MAX_QUEUED_NOTICES {
+                        state.notices.pop_front();
                     }
-                };
-
-                if let Some(viewport) = self.scroll_viewport(
-                    self.cursor,
-                    &layout,
-                    get_scroll(self.horizontal_navigation),
-                    get_scroll(self.vertical_navigation),
-                ) {
-                    self.publish_scrolled(state, shell, viewport);
+                }
+                NoticeCommand::Clear => {
+                    state.notices.clear();
+                    state.notice = None;
+                    shell.request_redraw();
                 }
             }
-            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
-                state.keyboard_modifiers = *modifiers;
-            }
-            _ => {}
         }
-    }
-}
-
-/// The content that is displayed and interacted with by the [`HexViewer`].
-///
-/// This should be instantiated and stored in the application's state, and passed to `HexViewer` in
-/// the application's view method. Two of `HexViewer`'s messages may give reason for your
-/// application to call [`Content::update`]:
-/// - [`HexViewer::on_scrolled`] requests the viewport to be moved, which means `Content` needs to be
-///   updated.
-/// - [`HexViewer::on_logical_viewport_resized`] notifies that the `HexViewer`'s viewport has
-///   resized, which means the number of columns and/or rows that can be displayed has changed, and
-///   `Content` needs to be updated.
-// /// new viewport and reads the corresponding data.
-#[derive(Debug)]
-pub struct Content {
-    source: Box<dyn Source>,
-    source_size: i64,
-    data: Vec<u8>,
-    viewport: Viewport,
-    id: u64,
-}
-
-impl Default for Content {
-    fn default() -> Self {
-        Self::new(Empty::default())
-    }
-}
 
-impl Content {
-    /// Creates a new `Content`.
-    pub fn new<S: Source + 'static>(mut source: S) -> Self {
-        let source_size = source.size() as i64;
+        let now = Instant::now();
 
-        Self {
-            source: Box::new(source),
-            source_size,
-            data: vec![],
-            viewport: Viewport::default(),
-            id: CONTENT_COUNTER.fetch_add(1, atomic::Ordering::SeqCst)
+        if state.notice.as_ref().is_some_and(|(_, timer)| timer.test(&now).0) {
+            state.notice = None;
+            shell.request_redraw();
         }
-    }
 
-    /// Updates the contents based on the [`Viewport`].
-    pub fn update(&mut self, viewport: Viewport) {
-        self.viewport = viewport;
-        if self.viewport.virtual_columns == 0 {
-            return;
+        if state.notice.is_none()
+            && let Some(notice) = state.notices.pop_front()
+        {
+            let paragraph = text::paragraph::Plain::new(Text {
+                content: notice.text,
+                bounds: Size::INFINITE,
+                size: self.font_size.unwrap_or_else(|| renderer.default_size()),
+                line_height: text::LineHeight::default(),
+                font: renderer.default_font(),
+                align_x: text::Alignment::Left,
+                align_y: alignment::Vertical::Center,
+                shaping: text::Shaping::Advanced,
+                wrapping: Wrapping::None,
+            });
+
+            state.notice = Some((paragraph, Timer::new(now, notice.duration.as_millis() as u64)));
+            shell.request_redraw();
         }
 
-        self.source_size = self.source.size() as i64;
-
-        if self.data.len() != viewport.size() {
-            self.data.resize(viewport.size(), 0);
+        if let Some((_, timer)) = &state.notice {
+            shell.request_redraw_at(timer.target());
         }
+    }
 
-        for r in 0..viewport.rows {
-            let source_offset = (viewport.y + r) * viewport.virtual_columns + viewport.x;
+    /// Draws the text of the [`HexViewer::row_value`] column for every row in the viewport.
+    fn draw_row_values<R>(
+        &self,
+        row_value: &RowValueColumn<'_>,
+        renderer: &mut R,
+        layout: &Layout,
+        color: Color,
+    )
+    where
+        R: text::Renderer<Font = Font> + 'static,
+        R::Paragraph: Clone + Default,
+    {
+        let bounds = layout.row_value_area();
+        let font = self.font.unwrap_or(Font::MONOSPACE);
+        let font_size = self.font_size.unwrap_or_else(|| renderer.default_size());
+        let columns = self.virtual_columns;
 
-            let dst_offset = r * viewport.columns;
-            let dst_size = viewport.columns
-                .min(self.source_size - source_offset)
-                .max(0);
-            let dst_end = (dst_offset + dst_size) as usize;
+        renderer.with_layer(bounds, |renderer| {
+            for row in 0..self.content.viewport.rows {
+                let start = (self.content.viewport.y + row) * columns;
 
-            if dst_size == 0 {
-                break;
-            }
+                if start >= self.content.source_size {
+                    break;
+                }
 
-            self.source.read(source_offset as u64, &mut self.data[dst_offset as usize..dst_end]);
-        }
-    }
+                let bytes: Vec<u8> = (start..(start + columns).min(self.content.source_size))
+                    .map_while(|offset| self.content.loaded_byte(offset))
+                    .collect();
 
-    fn iter(&self) -> impl Iterator<Item = ContentItem> {
-        if self.viewport.virtual_columns == 0 {
-            panic!("Virtual column count not set");
-        };
-
-        self.data.iter().enumerate().map(move |(i, v)| {
-
-            let row = i as i64 / self.viewport.columns;
-            let col = i as i64 % self.viewport.columns;
-
-            let offset = (self.viewport.y + row) * self.viewport.virtual_columns + self.viewport.x + col;
-
-            ContentItem::new(offset, i as i64, col, row, *v)
-        }).take_while(|item| item.offset < self.source_size)
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct Empty {}
-
-impl Source for Empty {
-    fn read(&mut self, _: u64, _: &mut [u8]) -> usize {
-        0
-    }
-
-    fn size(&mut self) -> u64 {
-        0
+                if let Some(value) = (row_value.format)(start as u64, &bytes) {
+                    renderer.fill_text(
+                        TextCache::<R>::create_text(value, &font, font_size),
+                        layout.row_value_position(row),
+                        color,
+                        bounds,
+                    );
+                }
+            }
+        });
     }
-}
-
-#[derive(Clone, Debug)]
-struct ContentItem {
-    offset: i64,
-    viewport_offset: i64,
-    column: i64,
-    row: i64,
-    value: u8,
-}
 
-impl ContentItem {
-    fn new(offset: i64, viewport_offset: i64, column: i64, row: i64, byte: u8) -> Self {
-        Self {
-            offset,
-            viewport_offset,
-            column,
-            row,
-            value: byte
+    fn publish_cursor_moved(
+        &self,
+        shell: &mut Shell<'_, Message>,
+        cursor: i64)
+    {
+        if let Some(on_cursor_moved) = &self.on_cursor_moved {
+            let message = (on_cursor_moved)(cursor as u64);
+            shell.publish(message);
+            shell.capture_event();
+            shell.request_redraw();
         }
     }
 }
 
-/// The source of [`Content`]. Must not change its size. In other words, it's expected to be a
-/// static source of bytes such as a file that isn't modified as long as the `Source` is in use.
-pub trait Source: Debug {
-    /// Read as many bytes as necessary to fill `buf`, starting from `offset` in the source file.
-    /// [`Content`]'s read pattern is to issue one read per row. Therefore one call to its
-    /// [`Content::update`] method can result in a lot of very small reads. Depending on how well
-    /// the OS caches the file it may be prudent to implement some form of caching in the
-    /// implementation of this `Source` trait.
-    /// TODO: the return type should be `Result`.
-    fn read(&mut self, offset: u64, buf: &mut [u8]) -> usize;
-
-    /// Gets the file size. `self` is mut so that the file size can be lazily loaded and cachved.
-    /// TODO: the return type should be `Result`.
-    fn size(&mut self) -> u64;
-}
-
-impl<'a, Message, Theme, Renderer> From<HexViewer<'a, Message, Theme>>
-for Element<'a, Message, Theme, Renderer>
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer> for HexViewer<'a, Message, Theme>
 where
-    Message: 'a + Clone,
     Renderer: text::Renderer<Font = Font> + 'static,
     Renderer::Paragraph: Clone,
-    Theme: Catalog + 'static,
+    Theme: Catalog,
 {
-    fn from(
-        hex_viewer_widget: HexViewer<'a, Message, Theme>,
-    ) -> Element<'a, Message, Theme, Renderer> {
-        Self::new(hex_viewer_widget)
+    fn size(&self) -> Size<Length> {
+        Size::new(self.width, self.height)
     }
-}
 
-pub fn hex_viewer_widget<Message, Theme>(content: &Content) -> HexViewer<'_, Message, Theme>
-where
-    Theme: Catalog
-{
-    HexViewer::new(content)
-}
+    fn layout(
+        &mut self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &Limits,
+    ) -> layout::Node {
+        let state = tree.state.downcast_mut::<State<Renderer>>();
 
-#[derive(Default)]
-struct State<R: Renderer>
-where
-    R: text::Renderer<Font = Font> + 'static,
-    R::Paragraph: Clone,
-{
-    text_cache: TextCache<R>,
-    keyboard_modifiers: keyboard::Modifiers,
-    /// State of the [`ScrollArea`].
-    scroll_area_state: ScrollAreaState,
-    /// The last reported selection.
-    last_reported_selection: Option<Selection>,
-    /// The last reported viewport, and the last reported-to Content.
-    last_reported_viewport: Option<(Viewport, u64)>,
-    /// Whether we're making a selection by left click + dragging the mouse.
-    dragging: bool,
-    /// Absolute start index for a current or potential selection.
-    start_index: Option<Index>,
-    /// Whether this widget is focussed, and should accept keyboard input.
-    focussed: bool,
-    /// Tracks time between scrollbar jumps when the track is being pressed, for both the horizontal
-    /// and vertical scrollbar.
-    track_timer: Option<Timer>,
-    /// Used for highlighting the byte/char header cell above the cursor.
-    hovered_column: Option<i64>,
-    /// Used for highlighting the address area cell left of the cursor.
-    hovered_row: Option<i64>,
-}
+        state.text_cache.set(self.text_settings(), renderer);
+        state.text_cache.set_char_map(self.char_map, renderer);
+        state.text_cache.set_placeholder(
+            &self.placeholder, self.on_file_dropped.is_some(), self.font_size, renderer);
+        let metrics = state.text_cache.metrics();
+        let (mut dim, mut settings) = self.create_layout_dimensions(metrics, Size::INFINITE);
 
-impl<R: Renderer> State<R>
-where
-    R: text::Renderer<Font = Font>,
-    R::Paragraph: Clone,
-{
-    fn new() -> Self {
-        Self {
-            text_cache: TextCache::new(),
-            keyboard_modifiers: keyboard::Modifiers::default(),
-            scroll_area_state: ScrollAreaState::default(),
-            last_reported_selection: None,
-            last_reported_viewport: None,
-            dragging: false,
-            start_index: None,
-            focussed: false,
-            track_timer: None,
-            hovered_column: None,
-            hovered_row: None,
-        }
-    }
+        if self.fit_columns && self.width != Length::Shrink {
+            let width = limits.resolve(self.width, dim.height(), Size::new(dim.width(), 0.0)).width;
 
-    fn start_index_or_set(&mut self, cursor: i64) -> Index {
-        if let Some(index) = self.start_index {
-            index
-        } else {
-            let index = Index::new(cursor, Side::None);
-            self.start_index = Some(index);
-            index
-        }
-    }
-}
+            self.virtual_columns = Self::fitted_columns(&dim, &settings, metrics, width);
 
-/// Caches the byte and char texts.
-#[derive(Default)]
-struct TextCache<R: Renderer>
-where
-    R: text::Renderer<Font = Font> + 'static,
-{
-    font: Option<Font>,
-    font_size: Option<Pixels>,
-    uninitialized: bool,
-    byte_paragraphs: Vec<text::paragraph::Plain<R::Paragraph>>,
-    char_paragraphs: Vec<text::paragraph::Plain<R::Paragraph>>,
-}
+            if self.snap_columns {
+                self.virtual_columns = power_of_two_columns(self.virtual_columns as u64) as i64;
+            }
 
-impl<R: Renderer> TextCache<R>
-where
-    R: text::Renderer<Font = Font>,
-    R::Paragraph: Clone + Default,
-{
-    fn new() -> Self {
-        Self {
-            font: None,
-            font_size: None,
-            uninitialized: true,
-            byte_paragraphs: vec![Default::default(); 256],
-            char_paragraphs: vec![Default::default(); 256],
+            (dim, settings) = self.create_layout_dimensions(metrics, Size::INFINITE);
         }
-    }
-
-    fn set(&mut self, font: &Option<Font>, font_size: Option<Pixels>, renderer: &R) {
-        // self.uninitialize is necessary because if we're given only None's then no initialization
-        // will ever happen.
-        if self.uninitialized || self.font != *font || self.font_size != font_size {
-            self.font = *font;
-            self.font_size = font_size;
-
-            let font = self.font.unwrap_or(Font::MONOSPACE);
-            let font_size = self.font_size.unwrap_or_else(|| renderer.default_size());
 
-            for (byte, paragraph) in self.byte_paragraphs.iter_mut().enumerate() {
-                let byte_string = format!("{:02X}", byte);
-                let text = Self::create_text(byte_string, &font, font_size);
-                paragraph.update(text.as_ref());
-            }
+        // The total height needed to show the given number of rows.
+        let rows_height = |rows: i64| {
+            dim.header_height
+                + settings.content_top
+                + settings.content_bottom
+                + rows as f32 * (metrics.height + 2.0 * settings.data_vertical)
+                + dim.horizontal_scrollbar_height
+        };
 
-            for (byte, paragraph) in self.char_paragraphs.iter_mut().enumerate() {
-                //let byte_string = format!("{:02X}", byte);
+        let mut limits = *limits;
 
-                let byte_string = Self::byte_to_decoded_char(byte as u8);
-                let text = Self::create_text(byte_string, &font, font_size);
-                paragraph.update(text.as_ref());
-            }
+        if let Some(rows) = self.min_rows {
+            limits = limits.min_height(rows_height(rows));
+        }
 
-            self.uninitialized = false;
+        if let Some(rows) = self.max_rows {
+            limits = limits.max_height(rows_height(rows));
         }
-    }
 
-    /// Gets the cached paragraph for a byte value, ready for drawing.
-    fn byte(&self, byte: u8) -> &text::paragraph::Plain<R::Paragraph> {
-        &self.byte_paragraphs[byte as usize]
+        layout::Node::new(limits.resolve(self.width, dim.height(), Size::new(dim.width(), 0.0)))
     }
 
-    /// Gets the cached paragraph for a char value in the current encoding, ready for drawing.
-    fn char(&self, byte: u8) -> &text::paragraph::Plain<R::Paragraph> {
-        &self.char_paragraphs[byte as usize]
-    }
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: layout::Layout<'_>,
+        _cursor: Cursor,
+        _viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State<Renderer>>();
+        let resolve = |highlight| theme.highlight(&self.class, highlight);
 
-    /// Gets the cached paragraph for a hex digit value (0-F), ready for drawing.
-    fn hex_digit(&self, hex_digit: u8) -> &text::paragraph::Plain<R::Paragraph> {
-        if hex_digit <= 9 {
-            &self.char_paragraphs[(hex_digit + 0x30) as usize]
-        } else if (0xA..0x10).contains(&hex_digit) {
-            &self.char_paragraphs[(hex_digit + 0x37) as usize]
-        } else {
-            panic!("hex digit out of range");
-        }
-    }
+        let bounds = layout.bounds();
 
-    /// The width of rendered bytes (e.g. "00") and rendered characters (e.g. "0"), and their height
-    fn metrics(&self) -> HexMetrics {
-        let byte_size = self.byte_paragraphs[0].min_bounds();
-        let char_size = self.char_paragraphs[0].min_bounds();
+        let metrics = state.text_cache.metrics();
+        let layout = self.create_layout(metrics, bounds, self.content.viewport.percentage_x);
+        
+        let style = theme.style(&self.class, Status::Active);
 
-        HexMetrics::new(
-            byte_size.width,
-            char_size.width,
-            char_size.height,
-        )
-    }
+        let x_viewport = self.x_viewport(&layout);
+        let y_viewport = self.y_viewport(&layout);
 
-    fn create_text(text: String, font: &Font, font_size: Pixels) -> Text {
-        Text {
-            content: text,
-            bounds: Size::INFINITE,
-            size: font_size,
-            line_height: text::LineHeight::Relative(1.0),
-            font: *font,
-            align_x: text::Alignment::Left,
-            align_y: alignment::Vertical::Center,
-            shaping: text::Shaping::Basic, // Barely makes a difference with Basic in terms of processing performance
-            wrapping: Wrapping::None,
-        }
-    }
+        // The changes that are highlighted, and how far the highlight has faded out.
+        let flash = state.flash.as_ref().zip(self.change_flash)
+            .map(|((changes, start), duration)| {
+                (changes, 1.0 - start.elapsed().as_secs_f32() / duration.as_secs_f32())
+            })
+            .filter(|(_, alpha)| *alpha > 0.0);
 
-    fn byte_to_decoded_char(byte: u8) -> String {
-        if (0x20..0x80).contains(&byte) {
-            let b = byte.to_le_bytes();
-            let (cow, _, had_errors) = encoding_rs::WINDOWS_1252.decode(&b);
-            if !had_errors {
-                cow.to_string()
-            } else {
-                String::from(".")
-            }
-        } else {
-            String::from(".")
-        }
-    }
-}
+        // Draw background of all headers.
+        renderer.fill_quad(
+            Quad {
+                bounds: layout.headers_background(),
+                ..Quad::default()
+            },
+            style.header_background
+        );
 
-/// The amount of space the byte and char paragraphs occupy.
-#[derive(Clone, Copy, Debug, Default)]
-struct HexMetrics {
-    byte_width: f32,
-    char_width: f32,
-    height: f32,
+        // Draw the background of the address area.
+        renderer.fill_quad(
+            Quad {
+                bounds: layout.address_area,
+                ..Quad::default()
+            },
+            style.header_background
+        );
+
+        // Draw the background of the space right of the char area.
+        renderer.fill_quad(
+            Quad {
+                bounds: layout.trailing_area(),
+                ..Quad::default()
+            },
+            style.background
+        );
+
+        // Draw the byte area headers.
+        renderer.with_layer(layout.byte_area_header, |renderer| {
+            if let Some(hovered_column) = state.hovered_column {
+                renderer.fill_quad(
+                    Quad {
+                        bounds: layout.byte_header_cell(hovered_column),
+                        ..Quad::default()
+                    },
+                    style.header_hover
+                );
+            }
+
+            for col in 0 .. self.content.viewport.columns {
+                let col_val = (self.content.viewport.x + col) % 256;
+
+                // The header is in hex regardless of the byte display, one digit at a time.
+                let digits: &[i64] = if col_val < 0x10 {
+                    &[col_val]
+                } else {
+                    &[col_val / 16, col_val % 16]
+                };
+
+                let position = layout.byte_header_text_position(col, digits.len());
+
+                for (index, digit) in digits.iter().enumerate() {
+                    renderer.fill_paragraph(
+                        state.text_cache.hex_digit(*digit as u8).raw(),
+                        position + Vector::new(index as f32 * layout.metrics.char_width, 0.0),
+                        style.header_text,
+                        layout.byte_area_header
+                    );
+                }
+            }
+        });
+
+        // Draw the char area headers.
+        renderer.with_layer(layout.char_area_header, |renderer| {
+            let (char_x, char_columns) = self.content.viewport.char_window();
+
+            // The hovered column is relative to the byte area's columns.
+            if let Some(hovered_column) = state.hovered_column {
+                renderer.fill_quad(
+                    Quad {
+                        bounds: layout.char_header_cell(
+                            hovered_column + self.content.viewport.x - char_x),
+                        ..Quad::default()
+                    },
+                    style.header_hover
+                );
+            }
+
+            for col in 0 .. char_columns {
+                let col_val = char_x + col;
+
+                // We only have space for one char per row, so the last hex digit goes on the last
+                // row, and in two row mode the digit before it on the first.
+                if self.char_header == CharHeader::TwoRows {
+                    renderer.fill_paragraph(
+                        state.text_cache.hex_digit((col_val / 16 % 16) as u8).raw(),
+                        layout.char_header_text_position(col, 0),
+                        style.header_text,
+                        layout.char_area_header
+                    );
+                }
+
+                renderer.fill_paragraph(
+                    state.text_cache.hex_digit((col_val % 16) as u8).raw(),
+                    layout.char_header_text_position(col, self.char_header.rows() - 1),
+                    style.header_text,
+                    layout.char_area_header
+                );
+            }
+        });
+
+        // Draw the address area.
+        renderer.with_layer(layout.address_area, |renderer| {
+            if let Some(hovered_row) = state.hovered_row
+                && y_viewport.offset + hovered_row < y_viewport.size
+            {
+                renderer.fill_quad(
+                    Quad {
+                        bounds: layout.address_area_cell(hovered_row),
+                        ..Quad::default()
+                    },
+                    style.header_hover
+                );
+            }
+
+            for row in self.row_group_starts() {
+                renderer.fill_quad(
+                    Quad {
+                        bounds: layout.row_separator(layout.address_area, row),
+                        ..Quad::default()
+                    },
+                    style.row_separator,
+                );
+            }
+
+            let first_address = self.content.viewport.y * self.virtual_columns;
+            let fill = self.address_area_horizontal_char_count();
+            let content_bounds = layout.address_area_content();
+
+            for row in 0..self.content.viewport.rows {
+                let address = first_address + row * self.virtual_columns;
+                let address_str = self.format_address(
+                    to_address(self.base_address, address as u64),
+                    fill - self.address_format.prefix().len(),
+                );
+
+                // Keep the least significant digits if the address doesn't fit, and replace the
+                // first one with an ellipsis.
+                let skip = address_str.len().saturating_sub(fill);
+
+                if skip > 0 {
+                    renderer.fill_paragraph(
+                        state.text_cache.ellipsis().raw(),
+                        layout.address_area_digit_position(0, row),
+                        style.header_text,
+                        content_bounds
+                    );
+                }
+
+                let digits = address_str.chars()
+                    .enumerate()
+                    .skip(if skip > 0 { skip + 1 } else { 0 })
+                    .map(|(char_num, char_value)| (char_num - skip, char_value));
+
+                for (char_num, char_value) in digits {
+                    renderer.fill_paragraph(
+                        state.text_cache.char(char_value as u8).raw(),
+                        layout.address_area_digit_position(char_num as i64, row),
+                        style.header_text,
+                        content_bounds
+                    );
+                }
+            }
+        });
+
+        // Closure to draw the byte area and char area
+        let mut draw_content = |
+            bounds: Rectangle,
+            content_bounds: Rectangle,
+            cell: fn(&Layout, col: i64, row: i64) -> Rectangle,
+            text_position: fn(&Layout, col: i64, row: i64) -> Point,
+            paragraph: fn(&TextCache<Renderer>, u8) -> &text::paragraph::Plain<Renderer::Paragraph>,
+            placeholder: fn(&TextCache<Renderer>, Availability) -> &text::paragraph::Plain<Renderer::Paragraph>,
+            buffer: Buffer,
+            area: Area|{
+
+            // Finds the cell of an offset in this area.
+            let locate = |offset: i64| match buffer {
+                Buffer::Bytes => self.offset_in_viewport(offset),
+                Buffer::Chars => self.char_offset_in_viewport(offset),
+            };
+
+            // Draw background of the content area.
+            renderer.fill_quad(
+                Quad {
+                    bounds,
+                    ..Quad::default()
+                },
+                style.background
+            );
+
+            // Shade the odd column groups, counted from the first column.
+            if self.column_group > 0 {
+                let (first_column, columns) = match area {
+                    Area::Bytes => (self.content.viewport.x, self.content.viewport.columns),
+                    Area::Chars => self.content.viewport.char_window(),
+                };
+
+                renderer.with_layer(bounds, |renderer| {
+                    let shaded = (0..columns)
+                        .filter(|col| (first_column + col) / self.column_group % 2 == 1);
+
+                    for col in shaded {
+                        let cell = cell(&layout, col, 0);
+
+                        renderer.fill_quad(
+                            Quad {
+                                bounds: Rectangle::new(
+                                    Point::new(cell.x, bounds.y),
+                                    Size::new(cell.width, bounds.height),
+                                ),
+                                ..Quad::default()
+                            },
+                            style.column_group_shade,
+                        );
+                    }
+                });
+            }
+
+            // Stripe the odd rows, counted from the start of the data.
+            if self.zebra_stripes {
+                renderer.with_layer(bounds, |renderer| {
+                    let viewport = &self.content.viewport;
+                    let remaining = self.content.source_size - viewport.y * self.virtual_columns;
+                    let rows = (remaining + self.virtual_columns - 1) / self.virtual_columns;
+
+                    let odd_rows = (0..viewport.rows.min(rows))
+                        .filter(|row| (viewport.y + row) % 2 == 1);
+
+                    for row in odd_rows {
+                        renderer.fill_quad(
+                            Quad {
+                                bounds: layout.row_bounds(bounds, row),
+                                ..Quad::default()
+                            },
+                            style.zebra_stripe,
+                        );
+                    }
+                });
+            }
+
+            // The bounds of the word of the word grouping that contains the offset, or of its
+            // cell without grouping, as far as it's visible.
+            let word_bounds = |offset: i64| {
+                let start = self.word_start(offset);
+
+                (start..(start + self.grouping()).min(self.content.source_size))
+                    .filter_map(&locate)
+                    .map(|(col, row)| cell(&layout, col, row))
+                    .reduce(|bounds, cell| bounds.union(&cell))
+            };
+
+            renderer.start_layer(content_bounds);
+
+            // Draw the crosshair under the bytes/chars.
+            if self.crosshair
+                && let Some(cursor) = word_bounds(self.cursor)
+            {
+                let row = Rectangle::new(
+                    Point::new(bounds.x, cursor.y),
+                    Size::new(bounds.width, cursor.height),
+                );
+                let column = Rectangle::new(
+                    Point::new(cursor.x, bounds.y),
+                    Size::new(cursor.width, bounds.height),
+                );
+
+                for bounds in [row, column] {
+                    renderer.fill_quad(
+                        Quad {
+                            bounds,
+                            ..Quad::default()
+                        },
+                        style.crosshair,
+                    );
+                }
+            }
+
+            // With a multi-byte encoding, the visible chars are decoded here instead of by the text
+            // cache.
+            let glyphs = (area == Area::Chars
+                && self.char_map.is_none()
+                && self.encoding.is_multi_byte())
+                .then(|| self.glyphs(buffer));
+            let columns = self.content.window(buffer).1;
+
+            // Draw the bytes/chars.
+            for item in self.content.iter(buffer) {
+                // Words of the word grouping may show their bytes in another order. A cell then
+                // takes the value, availability and styling of the byte it shows.
+                let item = match area {
+                    Area::Bytes if self.grouping() > 1 => {
+                        let offset = self.displayed_offset(item.offset);
+
+                        self.content.displayed_item(item, offset)
+                    }
+                    _ => item,
+                };
+
+                if item.availability != Availability::Ready {
+                    let (text_color, background) = match item.availability {
+                        Availability::Pending => (style.pending_text, style.pending_background),
+                        _ => (style.unreadable_text, style.unreadable_background),
+                    };
+
+                    renderer.fill_quad(
+                        Quad {
+                            bounds: cell(&layout, item.column, item.row),
+                            ..Quad::default()
+                        },
+                        background,
+                    );
+
+                    renderer.fill_paragraph(
+                        placeholder(&state.text_cache, item.availability).raw(),
+                        text_position(&layout, item.column, item.row),
+                        text_color,
+                        content_bounds
+                    );
+
+                    continue;
+                }
+
+                if let Some(color) = self.styled_background_color(&item, area, &resolve) {
+                    renderer.fill_quad(
+                        Quad {
+                            bounds: cell(&layout, item.column, item.row),
+                            ..Quad::default()
+                        },
+                        color,
+                    )
+                }
+
+                if let Some(animation) = self.styled_animation(&item) {
+                    let intensity = animation.intensity(state.animation_clock.elapsed());
+
+                    if intensity > 0.0 {
+                        renderer.fill_quad(
+                            Quad {
+                                bounds: cell(&layout, item.column, item.row),
+                                ..Quad::default()
+                            },
+                            animation.color.scale_alpha(intensity),
+                        )
+                    }
+                }
+
+                if let Some((changes, alpha)) = flash
+                    && ranges_contain(changes, item.offset as u64)
+                {
+                    renderer.fill_quad(
+                        Quad {
+                            bounds: cell(&layout, item.column, item.row),
+                            ..Quad::default()
+                        },
+                        style.change_flash.scale_alpha(alpha),
+                    )
+                }
+
+                if let Some(border) = self.styled_border(&item) {
+                    border.draw(renderer, cell(&layout, item.column, item.row));
+                }
+
+                let value = item.value;
+                let decoded = area == Area::Chars && self.char_map.is_none();
+                let glyph = glyphs.as_ref()
+                    .and_then(|glyphs| glyphs.get((item.row * columns + item.column) as usize))
+                    .map(|&glyph| match glyph {
+                        // In UTF-8, ASCII control chars are a byte by themselves.
+                        Glyph::Invalid if self.control_pictures
+                            && self.encoding == Encoding::Utf8
+                            && value.is_ascii_control() =>
+                        {
+                            Glyph::Char(control_picture(value))
+                        }
+                        glyph => glyph,
+                    });
+
+                let non_printable = match glyph {
+                    Some(glyph) => glyph == Glyph::Invalid,
+                    None => decoded && self.is_non_printable(value),
+                };
+
+                let color = self.styled_text_color(&item, area, &resolve)
+                    .or_else(|| {
+                        self.byte_class_coloring
+                            .then(|| style.byte_classes.color(ByteClass::of(value)))
+                    })
+                    .or(style.non_printable_text.filter(|_| non_printable))
+                    .unwrap_or(style.text);
+                let position = text_position(&layout, item.column, item.row);
+
+                if let Some(glyph) = glyph {
+                    let (glyph, color) = match glyph {
+                        Glyph::Char(char) => (char, color),
+                        Glyph::Continuation => (CONTINUATION, color.scale_alpha(0.4)),
+                        Glyph::Invalid => (self.non_printable_char, color),
+                    };
+
+                    // Multi-byte chars aren't in the cache of single bytes.
+                    if glyph.is_ascii() {
+                        renderer.fill_paragraph(
+                            state.text_cache.char(glyph as u8).raw(),
+                            position,
+                            color,
+                            content_bounds
+                        );
+                    } else {
+                        let paragraph = state.text_cache.glyph(glyph, renderer.default_size());
+                        renderer.fill_paragraph(paragraph.raw(), position, color, content_bounds);
+                    }
+                } else {
+                    renderer.fill_paragraph(
+                        paragraph(&state.text_cache, value).raw(),
+                        position,
+                        color,
+                        content_bounds
+                    );
+                }
+
+                if let Some(decorations) = self.styled_decorations(&item) {
+                    let cell = cell(&layout, item.column, item.row);
+                    let padding = position - cell.position();
+                    let text = Rectangle::new(
+                        position,
+                        Size::new(cell.width - 2.0 * padding.x, cell.height - 2.0 * padding.y),
+                    );
+
+                    decorations.draw(renderer, cell, text, color);
+                }
+            };
+
+            for row in self.row_group_starts() {
+                renderer.fill_quad(
+                    Quad {
+                        bounds: layout.row_separator(bounds, row),
+                        ..Quad::default()
+                    },
+                    style.row_separator,
+                );
+            }
+
+            // Draw the ghost cursor under the cursor, in case they're at the same offset.
+            if let Some(ghost_cursor) = self.ghost_cursor
+                && let Some(bounds) = word_bounds(ghost_cursor)
+            {
+                let quad = Quad {
+                    bounds,
+                    border: Border {
+                        color: style.ghost_cursor,
+                        width: 1.0,
+                        ..Border::default()
+                    },
+                    ..Quad::default()
+                };
+
+                renderer.fill_quad(
+                    quad,
+                    Color::TRANSPARENT,
+                )
+            }
+
+            // Draw the cursor
+            if let Some(bounds) = word_bounds(self.cursor) {
+                let quad = Quad {
+                    bounds,
+                    border: Border {
+                        color: style.text,
+                        width: 1.0,
+                        ..Border::default()
+                    },
+                    ..Quad::default()
+                };
+
+                renderer.fill_quad(
+                    quad,
+                    Color::TRANSPARENT,
+                )
+            }
+
+            renderer.end_layer();
+        };
+
+        // The data is laid out for other columns until the viewport has been updated, such as right
+        // after a new Content was created for other virtual columns.
+        if self.content.viewport.virtual_columns == self.virtual_columns {
+            // Draw the entire byte area.
+            draw_content(
+                layout.byte_area,
+                layout.byte_area_content(),
+                Layout::byte_cell,
+                Layout::byte_text_position,
+                TextCache::<Renderer>::byte,
+                TextCache::<Renderer>::byte_placeholder,
+                Buffer::Bytes,
+                Area::Bytes,
+            );
+
+            // Draw the entire char area.
+            draw_content(
+                layout.char_area,
+                layout.char_area_content(),
+                Layout::char_cell,
+                Layout::char_text_position,
+                TextCache::<Renderer>::mapped_char,
+                TextCache::<Renderer>::char_placeholder,
+                self.content.char_buffer(),
+                Area::Chars,
+            );
+
+            if let Some(row_value) = &self.row_value {
+                self.draw_row_values(row_value, renderer, &layout, style.text);
+            }
+        }
+
+        // Tell an empty source apart from one that's still loading or failed to load.
+        if self.content.source_size == 0 && !self.content.size_pending {
+            let data_area = layout.byte_area.union(&layout.char_area);
+            let lines: Vec<_> = state.text_cache.placeholder_lines().collect();
+            let height = lines.iter().map(|line| line.min_bounds().height).sum::<f32>()
+                + PLACEHOLDER_SPACING * lines.len().saturating_sub(1) as f32;
+            let mut y = data_area.center_y() - height / 2.0;
+
+            for line in lines {
+                let size = line.min_bounds();
+
+                renderer.fill_paragraph(
+                    line.raw(),
+                    Point::new(data_area.center_x() - size.width / 2.0, y + size.height / 2.0),
+                    style.placeholder_text,
+                    data_area,
+                );
+
+                y += size.height + PLACEHOLDER_SPACING;
+            }
+        }
+
+        if state.file_hovered && self.on_file_dropped.is_some() {
+            let data_area = layout.byte_area.union(&layout.char_area);
+
+            renderer.with_layer(data_area, |renderer| {
+                renderer.fill_quad(
+                    Quad {
+                        bounds: data_area.shrink(DROP_TARGET_INSET),
+                        border: Border {
+                            color: style.drop_target,
+                            width: 2.0,
+                            radius: 6.0.into(),
+                        },
+                        ..Quad::default()
+                    },
+                    style.drop_target.scale_alpha(0.1),
+                );
+            });
+        }
+
+        // Draw shadows under the headers and right of the address area once the content has been
+        // scrolled, hinting that rows/columns are clipped. Only the shadows of the quads fall
+        // within the layers, the quads themselves are just outside of them.
+        if let Some(shadow) = style.scroll_shadow {
+            let data_area = layout.byte_area.union(&layout.char_area);
+
+            if self.content.viewport.y > 0 {
+                renderer.with_layer(data_area, |renderer| {
+                    renderer.fill_quad(
+                        Quad {
+                            bounds: Rectangle::new(
+                                Point::new(data_area.x, data_area.y - 1.0),
+                                Size::new(data_area.width, 1.0),
+                            ),
+                            shadow: Shadow {
+                                offset: Vector::new(0.0, shadow.offset.y),
+                                ..shadow
+                            },
+                            ..Quad::default()
+                        },
+                        shadow.color,
+                    );
+                });
+            }
+
+            if self.content.viewport.x > 0 || self.content.viewport.percentage_x > 0.0 {
+                let scrolled_area = data_area.union(&layout.byte_area_header);
+
+                renderer.with_layer(scrolled_area, |renderer| {
+                    renderer.fill_quad(
+                        Quad {
+                            bounds: Rectangle::new(
+                                Point::new(scrolled_area.x - 1.0, scrolled_area.y),
+                                Size::new(1.0, scrolled_area.height),
+                            ),
+                            shadow: Shadow {
+                                offset: Vector::new(shadow.offset.x, 0.0),
+                                ..shadow
+                            },
+                            ..Quad::default()
+                        },
+                        shadow.color,
+                    );
+                });
+            }
+        }
+
+        // Draw the notice in a layer of its own, so it covers the bytes/chars.
+        if let Some((paragraph, _)) = &state.notice {
+            let data_area = layout.byte_area.union(&layout.char_area);
+            let size = paragraph.min_bounds().expand(NOTICE_PADDING);
+            let bounds = Rectangle::new(
+                Point::new(
+                    data_area.center_x() - size.width / 2.0,
+                    data_area.y + data_area.height - NOTICE_MARGIN - size.height,
+                ),
+                size,
+            );
+
+            renderer.with_layer(data_area, |renderer| {
+                renderer.fill_quad(
+                    Quad {
+                        bounds,
+                        border: Border {
+                            radius: 4.0.into(),
+                            ..Border::default()
+                        },
+                        ..Quad::default()
+                    },
+                    style.notice_background,
+                );
+
+                renderer.fill_paragraph(
+                    paragraph.raw(),
+                    Point::new(bounds.x + NOTICE_PADDING.left, bounds.center_y()),
+                    style.notice_text,
+                    bounds,
+                );
+            });
+        }
+
+        // The scrollbars are drawn next to the content as opposed to hovering over it (and
+        // therefore obstructing it), but this might become configurable in the future. Either way
+        // it makes most sense draw the scrollbars last.
+        self.scroll_area.draw(
+            renderer,
+            theme,
+            layout.scroll_area_bounds(),
+            Some(x_viewport),
+            Some(y_viewport),
+        );
+
+        // Draw a border around the widget.
+        renderer.fill_quad(
+            Quad {
+                bounds,
+                border: style.border,
+                ..Quad::default()
+            },
+            Color::TRANSPARENT,
+        );
+    }
+
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State<Renderer>>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::<Renderer>::new())
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: layout::Layout<'_>,
+        _renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        let state = tree.state.downcast_mut::<State<Renderer>>();
+
+        operation.custom(self.id.as_ref(), layout.bounds(), &mut state.commands);
+
+        let bounds = layout.bounds();
+        let layout = self.create_layout(
+            state.text_cache.metrics(), bounds, self.content.viewport.percentage_x);
+        let mut snapshot = self.layout_snapshot(bounds, &layout);
+
+        operation.custom(self.id.as_ref(), bounds, &mut snapshot);
+    }
+
+    // We assume this may get called multiple times in between two HexViewer::update() calls
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: layout::Layout<'_>,
+        cursor: Cursor,
+        renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_mut::<State<Renderer>>();
+
+        let bounds = layout.bounds();
+        let cursor_over_abs = cursor.position_over(bounds);
+        let metrics = state.text_cache.metrics();
+
+        let layout = self.check_state(state, shell, metrics, bounds);
+
+        if let Some(func) = &self.on_layout {
+            let snapshot = self.layout_snapshot(bounds, &layout);
+
+            if state.last_reported_layout.as_ref() != Some(&snapshot) {
+                shell.publish((func)(snapshot.clone()));
+                state.last_reported_layout = Some(snapshot);
+            }
+        }
+
+        let update = (self.content.id, self.content.updates);
+
+        if let Some(func) = &self.on_read_error
+            && state.last_reported_update != Some(update)
+        {
+            for error in &self.content.errors {
+                shell.publish((func)(error.clone()));
+            }
+            state.last_reported_update = Some(update);
+        }
+
+        let generation = (self.content.id, self.content.generation);
+
+        if let Some(func) = &self.on_content_changed
+            && state.last_reported_generation != Some(generation)
+        {
+            // The first content that's seen isn't a change.
+            if state.last_reported_generation.is_some() {
+                shell.publish((func)(self.content.generation));
+            }
+            state.last_reported_generation = Some(generation);
+        }
+
+        self.check_shrunk(state, shell);
+        self.update_flash(state, shell);
+
+        self.poll_pending(state, shell);
+        self.update_notices(state, shell, renderer);
+
+        if self.follow_tail {
+            self.keep_following_tail(state, shell, &layout);
+        }
+
+        // Recompute what's hovered for every event, since scrolling or resizing may move other
+        // rows/columns under a stationary mouse, and the mouse may leave without moving over us.
+        self.update_hover(state, shell, &layout, cursor_over_abs);
+
+        // Adopt the selection passed by the application, so the user can continue it.
+        if let Some(selection) = self.selection
+            && selection != state.last_reported_selection
+        {
+            self.anchor_selection(state, selection);
+            state.last_reported_selection = selection;
+        }
+
+        self.handle_commands(state, shell, &layout);
+        self.track_cursor_jumps(state, shell);
+
+        let x_viewport = self.x_viewport(&layout);
+        let y_viewport = self.y_viewport(&layout);
+
+        // A char area that scrolls on its own takes the horizontal part of wheel scrolls over it.
+        if let Event::Mouse(mouse::Event::WheelScrolled { .. }) = event
+            && self.content.viewport.is_split()
+            && cursor_over_abs.is_some_and(|position| {
+                layout.char_area.union(&layout.char_area_header).contains(position)
+            })
+        {
+            let char_viewport = ScrollViewport::new(
+                self.content.viewport.char_x,
+                self.virtual_columns,
+                layout.char_cell_width,
+                layout.char_area_content().width.ceil(),
+            );
+
+            if let ScrollAreaResult::WheelScroll { x, y } = self.scroll_area.update(
+                &mut state.scroll_area_state,
+                event,
+                layout.scroll_area_bounds(),
+                Some(char_viewport),
+                Some(y_viewport),
+                cursor,
+            ) {
+                let scroll_offset = ScrollOffset::new(x_viewport.offset, y);
+                let viewport = self.create_viewport_from_scroll_offset(&layout, scroll_offset);
+                self.publish_scrolled(state, shell, self.with_char_x(&layout, viewport, x));
+            }
+
+            return;
+        }
+
+        let result = self.scroll_area.update(
+            &mut state.scroll_area_state,
+            event,
+            layout.scroll_area_bounds(),
+            Some(x_viewport),
+            Some(y_viewport),
+            cursor,
+        );
+
+        if let Some(scroll_offset) = self.handle_scroll_result(
+            state, shell, result, &layout, x_viewport, y_viewport)
+        {
+            self.publish_scrolled(
+                state, shell, self.create_viewport_from_scroll_offset(&layout, scroll_offset));
+            return;
+        }
+
+        // Keep scrolling while a selection is dragged outside of the data, even when the mouse
+        // doesn't move.
+        if matches!(event,
+            Event::Mouse(mouse::Event::CursorMoved { .. })
+            | Event::Window(window::Event::RedrawRequested(_)))
+        {
+            self.auto_scroll(state, shell, &layout, x_viewport, y_viewport, cursor);
+        }
+
+        if let Event::Window(window::Event::RedrawRequested(now)) = event {
+            self.request_animation_frame(state, shell, *now);
+        }
+
+        // The event wasn't handled by ScrollArea; do our own processing.
+        match event {
+            Event::Window(window::Event::FileHovered(_))
+                if !state.file_hovered && self.on_file_dropped.is_some() =>
+            {
+                state.file_hovered = true;
+                shell.request_redraw();
+            }
+            Event::Window(window::Event::FilesHoveredLeft) if state.file_hovered => {
+                state.file_hovered = false;
+                shell.request_redraw();
+            }
+            Event::Window(window::Event::FileDropped(path)) => {
+                state.file_hovered = false;
+
+                if let Some(func) = &self.on_file_dropped {
+                    shell.publish((func)(path.clone()));
+                    shell.request_redraw();
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                if let Some(mouse_pos) = cursor_over_abs {
+                    state.focussed = true;
+
+                    let location = layout.pointer_location(mouse_pos);
+
+                    // Handle a cell being clicked, or close to it.
+                    if let Some(index) = self.index(&layout, location) {
+                        state.drag_in_chars = matches!(location, Location::CharArea(_));
+
+                        // Holding ALT makes a rectangular selection instead of a linear one.
+                        state.block_selection = state.keyboard_modifiers.alt();
+
+                        // If shift is held we try to continue a previously created selection, from
+                        // its starting point.
+                        if state.keyboard_modifiers.shift() {
+                            let start = state.start_index_or_set(self.cursor);
+
+                            let selection = if state.block_selection {
+                                self.block_selection(start, index)
+                            } else {
+                                self.linear_selection(start, index, index.offset)
+                            };
+
+                            self.publish_on_selection(state, shell, selection);
+                            state.dragging = true;
+                        } else {
+                            let click = mouse::Click::new(
+                                mouse_pos, mouse::Button::Left, state.last_click);
+                            state.last_click = Some(click);
+
+                            match click.kind() {
+                                mouse::click::Kind::Single if self.on_drag_started.is_some()
+                                    && let Some(selection) = state.last_reported_selection
+                                    && selection.contains(index.offset as u64) =>
+                                {
+                                    // This may be the start of dragging the selection out of the
+                                    // widget, which we only know once the mouse moves.
+                                    state.pending_drag = Some(PendingDrag {
+                                        selection,
+                                        origin: mouse_pos,
+                                        index,
+                                    });
+                                }
+                                mouse::click::Kind::Single => {
+                                    let cursor = self.word_start(index.offset);
+
+                                    if cursor != self.cursor {
+                                        self.publish_cursor_moved(shell, cursor);
+                                    }
+
+                                    self.cursor = cursor;
+
+                                    // Start a drag interaction, even though the user may not
+                                    // intend to drag. We'll cancel the drag later in that case.
+                                    state.start_index = Some(index);
+                                    state.dragging = true;
+                                }
+                                mouse::click::Kind::Double => {
+                                    self.publish_on_selection(
+                                        state, shell, self.word_selection(index.offset));
+                                    state.dragging = false;
+
+                                    if let Some(func) = &self.on_double_click {
+                                        shell.publish((func)(index.offset as u64));
+                                    }
+                                }
+                                mouse::click::Kind::Triple => {
+                                    self.publish_on_selection(
+                                        state, shell, self.row_selection(index.offset));
+                                    state.dragging = false;
+                                }
+                            }
+                        }
+                    } else if matches!(location, Location::AddressArea)
+                        && let Some(row_start) = self.address_area_row_start(&layout, mouse_pos)
+                    {
+                        // Like the gutter of a text editor, the address area selects whole rows,
+                        // from the row of the cursor if SHIFT is held.
+                        let anchor = if state.keyboard_modifiers.shift() {
+                            self.cursor - self.cursor % self.virtual_columns
+                        } else {
+                            if row_start != self.cursor {
+                                self.publish_cursor_moved(shell, row_start);
+                            }
+
+                            self.cursor = row_start;
+                            row_start
+                        };
+
+                        let selection = self.rows_selection(anchor, row_start);
+
+                        self.publish_on_selection(state, shell, selection);
+                        self.anchor_selection(state, selection);
+                        state.row_drag = Some(anchor);
+                    } else if let Some(column) = self.header_column(&layout, location, mouse_pos) {
+                        // Clicking a header selects its column, in all rows with COMMAND.
+                        let selection = self.column_selection(
+                            column, state.keyboard_modifiers.command());
+
+                        self.publish_on_selection(state, shell, selection);
+                        self.anchor_selection(state, selection);
+                    }
+                } else {
+                    // We lose focus if the button is pressed anywhere outside our widget, but
+                    // within the bounds of the drawable area of the main window.
+                    if cursor.position_over(*_viewport).is_some() {
+                        state.focussed = false;
+                    }
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) => {
+                if let Some(func) = &self.on_right_click
+                    && let Some(mouse_pos) = cursor_over_abs
+                    && let Some(click) = self.right_click(state, &layout, mouse_pos)
+                {
+                    shell.publish((func)(click));
+                    shell.capture_event();
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                // Note that we're not resetting state.start_index here, that's on purpose: if we were
+                // actually dragging a selection we want to preserve where we started in case we
+                // want to continue using the SHIFT button. Even if there was just a click, we'll
+                // store the side of the byte/char the click happened, for now. This will
+                // influence the offset at which the SHIFT aided selection will start. May change it
+                // later if necessary.
+                state.dragging = false;
+                state.auto_scroll_timer = None;
+                state.row_drag = None;
+
+                // The selection was clicked but not dragged, which is a regular click after all.
+                if let Some(PendingDrag { index, .. }) = state.pending_drag.take() {
+                    let cursor = self.word_start(index.offset);
+
+                    if cursor != self.cursor {
+                        self.publish_cursor_moved(shell, cursor);
+                    }
+
+                    self.cursor = cursor;
+                    state.start_index = Some(index);
+                    self.publish_on_selection(state, shell, None);
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                if let Some(PendingDrag { selection, origin, .. }) = state.pending_drag
+                    && origin.distance(*position) > DRAG_THRESHOLD
+                {
+                    state.pending_drag = None;
+
+                    if let Some(func) = &self.on_drag_started {
+                        shell.publish((func)(selection));
+                    }
+                }
+
+                if let Some(anchor) = state.row_drag
+                    && let Some(row_start) = self.address_area_row_start(&layout, *position)
+                {
+                    let selection = self.rows_selection(anchor, row_start);
+
+                    self.publish_on_selection(state, shell, selection);
+                    self.anchor_selection(state, selection);
+                }
+
+                if let Some(mouse_pos) = cursor_over_abs {
+                    let location = layout.pointer_location(mouse_pos);
+
+                    if state.dragging
+                        && let Some(start) = state.start_index
+                        && let Some(loc) = self.index(&layout, location)
+                    {
+                        let selection = if state.block_selection {
+                            self.block_selection(start, loc)
+                        } else {
+                            self.linear_selection(start, loc, loc.offset)
+                        };
+
+                        self.publish_on_selection(state, shell, selection);
+                    }
+                }
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+                if !state.focussed {
+                    return;
+                }
+
+                let history = match key.as_ref() {
+                    keyboard::Key::Named(key::Named::ArrowLeft) if modifiers.alt() => {
+                        Some(HistoryCommand::Back)
+                    }
+                    keyboard::Key::Named(key::Named::ArrowRight) if modifiers.alt() => {
+                        Some(HistoryCommand::Forward)
+                    }
+                    _ => None,
+                };
+
+                // Without a position to move to, the keys are left to the application.
+                if let Some(command) = history {
+                    if self.navigate_history(state, shell, &layout, command) {
+                        shell.capture_event();
+                    }
+
+                    return;
+                }
+
+                // `zt`, `zz` and `zb` align the row of the cursor with the start, center or end
+                // of the viewport, as in vim.
+                let plain = !modifiers.command() && !modifiers.alt();
+
+                if std::mem::take(&mut state.align_pending) && plain {
+                    let alignment = match key.as_ref() {
+                        keyboard::Key::Character("t") => Some(Alignment::Start),
+                        keyboard::Key::Character("z") => Some(Alignment::Center),
+                        keyboard::Key::Character("b") => Some(Alignment::End),
+                        _ => None,
+                    };
+
+                    if let Some(alignment) = alignment {
+                        self.align_viewport(state, shell, &layout, self.cursor, alignment);
+                        shell.capture_event();
+                        return;
+                    }
+                }
+
+                if plain && key.as_ref() == keyboard::Key::Character("z") {
+                    state.align_pending = true;
+                    shell.capture_event();
+                    return;
+                }
+
+                let maybe_new_cursor = match key.as_ref() {
+                    // CTRL with the arrow keys jumps over runs of identical bytes.
+                    keyboard::Key::Named(key::Named::ArrowLeft) if modifiers.command() => {
+                        self.jump_over_run(shell, -1, modifiers.shift())
+                    }
+                    keyboard::Key::Named(key::Named::ArrowRight) if modifiers.command() => {
+                        self.jump_over_run(shell, 1, modifiers.shift())
+                    }
+                    keyboard::Key::Named(key::Named::ArrowUp) if modifiers.command() => {
+                        self.jump_over_run(shell, -self.virtual_columns, modifiers.shift())
+                    }
+                    keyboard::Key::Named(key::Named::ArrowDown) if modifiers.command() => {
+                        self.jump_over_run(shell, self.virtual_columns, modifiers.shift())
+                    }
+                    keyboard::Key::Named(key::Named::ArrowLeft) => {
+                        self.move_cursor_left()
+                    }
+                    keyboard::Key::Named(key::Named::ArrowRight) => {
+                        self.move_cursor_right()
+                    }
+                    keyboard::Key::Named(key::Named::ArrowUp) => {
+                        self.move_cursor_up()
+                    }
+                    keyboard::Key::Named(key::Named::ArrowDown) => {
+                        self.move_cursor_down()
+                    }
+                    keyboard::Key::Named(key::Named::PageUp) => {
+                        self.move_cursor_page_up(layout.viewport_row_count_floor())
+                    }
+                    keyboard::Key::Named(key::Named::PageDown) => {
+                        self.move_cursor_page_down(layout.viewport_row_count_floor())
+                    }
+                    // CTRL+U and CTRL+D move half a page, as in vim.
+                    keyboard::Key::Character("u") if modifiers.command() => {
+                        self.move_cursor_page_up((layout.viewport_row_count_floor() / 2).max(1))
+                    }
+                    keyboard::Key::Character("d") if modifiers.command() => {
+                        self.move_cursor_page_down((layout.viewport_row_count_floor() / 2).max(1))
+                    }
+                    // Home/End move within the row, and to the document's extremes with CTRL.
+                    keyboard::Key::Named(key::Named::Home) if modifiers.command() => {
+                        self.move_cursor_top()
+                    }
+                    keyboard::Key::Named(key::Named::End) if modifiers.command() => {
+                        self.move_cursor_bottom()
+                    }
+                    keyboard::Key::Named(key::Named::Home) => {
+                        self.move_cursor_row_start()
+                    }
+                    keyboard::Key::Named(key::Named::End) => {
+                        self.move_cursor_row_end()
+                    }
+                    _ => {
+                        // Hitting the escape key cancels the selection without the need for moving
+                        // the cursor.
+                        if matches!(key, keyboard::Key::Named(key::Named::Escape)) {
+                            state.start_index = None;
+
+                            self.publish_on_selection(state, shell, None);
+                        }
+
+                        // Any other keys are simply ignored.
+                        return
+                    }
+                };
+
+                // Check whether we're creating/modifying a selection by keyboard.
+                if modifiers.shift() {
+                    if let Some(new_cursor) = maybe_new_cursor {
+                        let selection = state.start_index_or_set(self.cursor);
+                        let new_index = Index::new(new_cursor, Side::None);
+
+                        self.publish_on_selection(
+                            state, shell, self.linear_selection(selection, new_index, new_cursor));
+
+                        self.cursor = new_cursor;
+                    }
+                } else if let Some(new_cursor) = maybe_new_cursor {
+                        state.start_index = None;
+                        self.publish_cursor_moved(shell, new_cursor);
+                        self.cursor = new_cursor;
+                } else {
+                    // Applies when the cursor is alread at the start/end of the document and
+                    // can't be moved further, yet a movement key was pressed without shift.
+                    state.start_index = None;
+                    self.publish_on_selection(state, shell, None);
+                }
+
+                let get_scroll = |navigation: Navigation| {
+                    match navigation {
+                        Navigation::Lazy => {
+                            if matches!(key.as_ref(),
+                                keyboard::Key::Named(key::Named::ArrowLeft)
+                                | keyboard::Key::Named(key::Named::ArrowUp)
+                                | keyboard::Key::Named(key::Named::PageUp)
+                                | keyboard::Key::Named(key::Named::Home))
+                            {
+                                Scroll::Lazy(LazyAlignment::Start)
+                            } else {
+                                Scroll::Lazy(LazyAlignment::End)
+                            }
+                        }
+                        Navigation::Aligned(alignment) => {
+                            Scroll::Aligned(alignment)
+                        }
+                    }
+                };
+
+                if let Some(viewport) = self.scroll_viewport(
+                    self.cursor,
+                    &layout,
+                    get_scroll(self.horizontal_navigation),
+                    get_scroll(self.vertical_navigation),
+                ) {
+                    self.publish_scrolled(state, shell, viewport);
+                }
+            }
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.keyboard_modifiers = *modifiers;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The content that is displayed and interacted with by the [`HexViewer`].
+///
+/// This should be instantiated and stored in the application's state, and passed to `HexViewer` in
+/// the application's view method. Two of `HexViewer`'s messages may give reason for your
+/// application to call [`Content::update`]:
+/// - [`HexViewer::on_scrolled`] requests the viewport to be moved, which means `Content` needs to be
+///   updated.
+/// - [`HexViewer::on_logical_viewport_resized`] notifies that the `HexViewer`'s viewport has
+///   resized, which means the number of columns and/or rows that can be displayed has changed, and
+///   `Content` needs to be updated.
+///
+/// A new `Content` already reads the first rows of data, so they're displayed in the first frame,
+/// before the `HexViewer` has reported its viewport. See [`Content::initial_columns`].
+// /// new viewport and reads the corresponding data.
+#[derive(Debug)]
+pub struct Content {
+    source: Box<dyn Source>,
+    source_size: i64,
+    data: Vec<u8>,
+    /// The data for the char area, if it shows other columns than the byte area.
+    char_data: Vec<u8>,
+    viewport: Viewport,
+    id: u64,
+    /// Tells this `Content` apart from others with the same id.
+    instance: u64,
+    /// Ranges in `data` or `char_data` that couldn't be read.
+    unreadable: Vec<(Buffer, Range<usize>)>,
+    /// Rows of `data` or `char_data` that the [`Source`] will provide later, relative to the
+    /// viewport. Each entry is read at once again when polled.
+    pending: Vec<(Buffer, Range<i64>)>,
+    /// Whether the [`Source`] will provide its size later.
+    size_pending: bool,
+    /// The errors of the last update.
+    errors: Vec<ReadError>,
+    /// The number of updates, so new errors can be told apart from ones already reported.
+    updates: u64,
+    /// See [`Content::generation`].
+    generation: u64,
+    /// See [`Content::margin_rows`].
+    margin_rows: i64,
+    /// The rows read around the viewport.
+    margin: Option<Margin>,
+    /// See [`Content::read_alignment`].
+    read_alignment: u64,
+    /// Kept between reads of several rows at once, so it doesn't have to be allocated every time.
+    batch_buffer: Vec<u8>,
+    /// Kept between aligned reads, so it doesn't have to be allocated every time.
+    aligned_buffer: Vec<u8>,
+    /// The [`Content::generation`] the viewport was last read in.
+    read_generation: u64,
+    /// The size of the [`Source`] when the viewport was last read.
+    read_size: i64,
+    /// The byte area as it was before it was read again, to find the changes.
+    previous_data: Vec<u8>,
+    /// See [`Content::changes`].
+    changes: Vec<Range<u64>>,
+    /// The number of times changes were found, so new ones can be told apart.
+    change_count: u64,
+}
+
+/// Whole rows read around the viewport of a [`Content`], see [`Content::margin_rows`].
+#[derive(Debug)]
+struct Margin {
+    /// The absolute index of the first row.
+    y: i64,
+    /// The rows, shorter at the end of the [`Source`].
+    data: Vec<u8>,
+    /// The [`Viewport::virtual_columns`] the rows were read with.
+    virtual_columns: i64,
+    /// The [`Content::generation`] the rows were read in.
+    generation: u64,
+}
+
+impl Default for Content {
+    fn default() -> Self {
+        Self::new(Empty::default())
+    }
+}
+
+impl Content {
+    /// Creates a new `Content`.
+    pub fn new<S: Source + 'static>(source: S) -> Self {
+        Self::with_id(source, CONTENT_COUNTER.fetch_add(1, atomic::Ordering::SeqCst))
+    }
+
+    /// Creates a new `Content` with room for a viewport of `bytes` bytes, so that continuous
+    /// scrolling doesn't allocate until the viewport grows beyond it.
+    pub fn with_capacity<S: Source + 'static>(source: S, bytes: usize) -> Self {
+        let mut content = Self::new(source);
+        content.data.reserve(bytes);
+        content.char_data.reserve(bytes);
+        content.batch_buffer.reserve(bytes.min(MAX_BATCH_SIZE as usize));
+        content
+    }
+
+    /// Creates a new `Content` with the [`Content::id`] of a previous one. Use this when the same
+    /// data is reloaded. The [`HexViewer`] treats it as the same content: the selection the user is
+    /// making is kept, and it scrolls to the viewport of the previous `Content` with
+    /// [`HexViewer::on_scrolled`]. If the new data is shorter, the viewport is clamped to its end.
+    pub fn with_id<S: Source + 'static>(source: S, id: u64) -> Self {
+        let mut content = Self {
+            source: Box::new(source),
+            source_size: 0,
+            data: vec![],
+            char_data: vec![],
+            viewport: Viewport::default(),
+            id,
+            instance: CONTENT_COUNTER.fetch_add(1, atomic::Ordering::SeqCst),
+            unreadable: vec![],
+            pending: vec![],
+            size_pending: false,
+            errors: vec![],
+            updates: 0,
+            generation: 0,
+            margin_rows: 0,
+            margin: None,
+            read_alignment: 1,
+            batch_buffer: vec![],
+            aligned_buffer: vec![],
+            read_generation: 0,
+            read_size: 0,
+            previous_data: vec![],
+            changes: vec![],
+            change_count: 0,
+        };
+
+        content.update(Viewport::initial(INITIAL_COLUMNS));
+        // Reading the initial size isn't a change.
+        content.generation = 0;
+        content.read_generation = 0;
+        content
+    }
+
+    /// Sets the number of columns per row of the data that's read when the `Content` is created,
+    /// 32 by default. Set it to the [`HexViewer::virtual_columns`] of the viewer that displays it,
+    /// since the data is only displayed once these match. Has no effect once the `Content` has been
+    /// updated with a viewport of a [`HexViewer`].
+    pub fn initial_columns(mut self, columns: u64) -> Self {
+        let columns = columns.max(1) as i64;
+
+        // Only the update done by the constructor has happened so far.
+        if self.updates == 1 && self.viewport.virtual_columns != columns {
+            let generation = self.generation;
+            self.update(Viewport::initial(columns));
+            self.generation = generation;
+            self.read_generation = generation;
+        }
+
+        self
+    }
+
+    /// Sets the number of rows above and below the viewport that are read along with it, 0 by
+    /// default. When the viewport moves within these rows, [`Content::update`] copies the data
+    /// from memory instead of reading the [`Source`] again, so small scrolls are shown right away,
+    /// even from a slow source. The rows are read again once the viewport moves beyond them.
+    ///
+    /// Whole rows are read, up to 4 MiB. Rows beyond that aren't read ahead.
+    pub fn margin_rows(mut self, rows: u64) -> Self {
+        self.margin_rows = rows as i64;
+        self.margin = None;
+        self
+    }
+
+    /// Aligns all reads from the [`Source`] to blocks of `bytes` bytes: they start at a multiple of
+    /// it and span a multiple of it, reading slightly more than needed. This speeds up devices and
+    /// network sources with a large overhead per read. 1, the default, doesn't align reads. The
+    /// [`SourceInfo::block_size`] of the source is a good choice.
+    pub fn read_alignment(mut self, bytes: u64) -> Self {
+        self.read_alignment = bytes.max(1);
+        self
+    }
+
+    /// Replaces the [`Source`] while keeping the [`Content::id`] and viewport, and reads the data
+    /// for the viewport from the new source. Like [`Content::with_id`], the [`HexViewer`] keeps the
+    /// selection the user is making.
+    pub fn replace_source<S: Source + 'static>(&mut self, source: S) {
+        self.source = Box::new(source);
+        self.generation += 1;
+        self.update(self.viewport);
+    }
+
+    /// Reads the data for the current viewport again, for instance because the data behind the
+    /// [`Source`] has changed. The source is reloaded first, see [`Source::reload`]. If that fails,
+    /// the error is added to the [`Content::errors`]. Increments the [`Content::generation`].
+    pub fn refresh(&mut self) {
+        let reloaded = self.source.reload();
+
+        self.generation += 1;
+        self.update(self.viewport);
+
+        if let Err(error) = reloaded {
+            self.errors.push(ReadError::new(None, &error));
+        }
+    }
+
+    /// Reads the bytes in `range` again, for instance because the application wrote to the memory
+    /// behind the [`Source`] or a live capture overwrote part of it. Only the rows of the viewport
+    /// that contain these bytes are read, and the rows read by [`Content::margin_rows`] are kept.
+    /// Increments the [`Content::generation`]. Like [`Content::poll`], this replaces the
+    /// [`Content::errors`].
+    ///
+    /// Use [`Content::refresh`] if the size of the source may have changed as well.
+    pub fn invalidate(&mut self, range: Range<u64>) {
+        let start = range.start.min(self.source_size as u64) as i64;
+        let end = range.end.min(self.source_size as u64) as i64;
+
+        if start >= end {
+            return;
+        }
+
+        self.generation += 1;
+        self.updates += 1;
+        self.errors.clear();
+        self.changes.clear();
+
+        self.invalidate_margin(start..end);
+
+        if self.viewport.virtual_columns == 0 {
+            return;
+        }
+
+        let stale = self.stale_rows();
+        self.previous_data.clone_from(&self.data);
+
+        self.invalidate_rows(Buffer::Bytes, start..end);
+
+        if self.viewport.is_split() {
+            self.invalidate_rows(Buffer::Chars, start..end);
+        }
+
+        self.find_changes(&stale, self.source_size);
+        self.read_generation = self.generation;
+    }
+
+    /// Reads the absolute `range` of the rows around the viewport again, so they stay valid for
+    /// the new [`Content::generation`]. If that fails, they're read again with the next update.
+    fn invalidate_margin(&mut self, range: Range<i64>) {
+        let Some(mut margin) = self.margin.take() else {
+            return;
+        };
+
+        // Rows that were out of date before aren't worth updating.
+        if margin.generation + 1 != self.generation {
+            return;
+        }
+
+        let margin_start = margin.y * margin.virtual_columns;
+        let start = range.start.max(margin_start);
+        let end = range.end.min(margin_start + margin.data.len() as i64);
+
+        if start < end {
+            let data = &mut margin.data[(start - margin_start) as usize..(end - margin_start) as usize];
+
+            match self.read_source(start as u64, data) {
+                Ok(read) if read == data.len() => {}
+                _ => return,
+            }
+        }
+
+        margin.generation = self.generation;
+        self.margin = Some(margin);
+    }
+
+    /// Reads the rows of the viewport in `buffer` that contain bytes of the absolute `range` again.
+    fn invalidate_rows(&mut self, buffer: Buffer, range: Range<i64>) {
+        let viewport = self.viewport;
+        let (x, columns) = self.window(buffer);
+        let stride = viewport.virtual_columns;
+
+        if columns == 0 {
+            return;
+        }
+
+        let mut rows = (0..viewport.rows).filter(|row| {
+            let offset = (viewport.y + row) * stride + x;
+            offset < range.end && offset + columns > range.start && offset < self.source_size
+        });
+
+        let Some(first) = rows.next() else {
+            return;
+        };
+
+        let mut affected = first..rows.next_back().unwrap_or(first) + 1;
+
+        // Pending rows are read at once when polled, so they're read again as a whole.
+        loop {
+            let before = affected.clone();
+
+            for (pending_buffer, rows) in &self.pending {
+                if *pending_buffer == buffer && rows.start < affected.end && rows.end > affected.start {
+                    affected = affected.start.min(rows.start)..affected.end.max(rows.end);
+                }
+            }
+
+            if affected == before {
+                break;
+            }
+        }
+
+        let overlaps = |other: Buffer, start: i64, end: i64| {
+            other == buffer && start < affected.end && end > affected.start
+        };
+
+        self.pending.retain(|(other, rows)| !overlaps(*other, rows.start, rows.end));
+        self.unreadable.retain(|(other, range)| {
+            let columns = columns as usize;
+            !overlaps(*other, (range.start / columns) as i64, range.end.div_ceil(columns) as i64)
+        });
+
+        self.read_batches(buffer, affected);
+    }
+
+    /// Counts the changes of the data. It's incremented when the [`Source`] is replaced, when the
+    /// content is refreshed and when the size of the source changes, but not when the viewport
+    /// moves. Widgets that depend on the data can compare it to a generation they've seen before to
+    /// find out whether they're out of date. See also [`HexViewer::on_content_changed`].
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Identifies the content. Each `Content` created with [`Content::new`] gets a unique id, see
+    /// [`Content::with_id`] to reuse one.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Updates the contents based on the [`Viewport`]. Errors returned by the [`Source`] are
+    /// available with [`Content::errors`] until the next update.
+    ///
+    /// When the viewport only moves vertically, the rows that stay visible are kept and only the
+    /// newly exposed rows are read from the [`Source`]. Rows that were pending or unreadable are
+    /// read again. Use [`Content::refresh`] to read all rows again.
+    pub fn update(&mut self, viewport: Viewport) {
+        let previous = self.viewport;
+        let previous_size = self.read_size;
+        let generation = self.read_generation;
+        let stale = self.stale_rows();
+
+        self.viewport = viewport;
+        self.updates += 1;
+        self.errors.clear();
+        self.unreadable.clear();
+        self.pending.clear();
+        self.changes.clear();
+
+        self.update_size();
+
+        if self.viewport.virtual_columns == 0 {
+            return;
+        }
+
+        // Data that changed in place is compared with what was read before.
+        let compare = self.generation != generation
+            && previous.y == viewport.y
+            && previous.rows == viewport.rows
+            && previous.virtual_columns == viewport.virtual_columns
+            && previous.window(Buffer::Bytes) == viewport.window(Buffer::Bytes)
+            && self.data.len() == viewport.size();
+
+        if compare {
+            self.previous_data.clone_from(&self.data);
+        }
+
+        // The rows read before are only worth keeping if the data hasn't changed since.
+        let previous = (self.generation == generation).then_some(previous);
+        self.read_generation = self.generation;
+        self.read_size = self.source_size;
+
+        if self.margin_rows > 0 {
+            self.read_margin();
+        }
+
+        let reused = self.shift_rows(Buffer::Bytes, previous);
+        self.read_rows(Buffer::Bytes, reused, &stale);
+
+        if viewport.is_split() {
+            let reused = self.shift_rows(Buffer::Chars, previous);
+            self.read_rows(Buffer::Chars, reused, &stale);
+        } else {
+            self.char_data.clear();
+        }
+
+        if compare {
+            self.find_changes(&stale, previous_size);
+        }
+    }
+
+    /// Compares the byte area with the data it held before it was read again, and keeps the
+    /// ranges of bytes that differ. Bytes that weren't available before or after, and bytes beyond
+    /// the `previous_size` of the [`Source`], didn't change but were added.
+    fn find_changes(&mut self, stale: &[(Buffer, i64)], previous_size: i64) {
+        let viewport = self.viewport;
+        let (x, columns) = self.window(Buffer::Bytes);
+        let stride = viewport.virtual_columns;
+        let size = self.source_size.min(previous_size);
+        let mut changes: Vec<Range<u64>> = Vec::new();
+
+        for row in 0..viewport.rows {
+            let start = (viewport.y + row) * stride + x;
+            let length = columns.min(size - start);
+
+            if length <= 0 {
+                break;
+            }
+
+            if stale.contains(&(Buffer::Bytes, viewport.y + row))
+                || !self.is_row_available(Buffer::Bytes, row)
+            {
+                continue;
+            }
+
+            let range = (row * columns) as usize..(row * columns + length) as usize;
+            let pairs = self.previous_data[range.clone()].iter().zip(&self.data[range]);
+
+            for (column, (previous, current)) in pairs.enumerate() {
+                if previous == current {
+                    continue;
+                }
+
+                let offset = (start + column as i64) as u64;
+
+                match changes.last_mut() {
+                    Some(last) if last.end == offset => last.end += 1,
+                    _ => changes.push(offset..offset + 1),
+                }
+            }
+        }
+
+        if !changes.is_empty() {
+            self.change_count += 1;
+        }
+
+        self.changes = changes;
+    }
+
+    /// Whether a row of the viewport in `buffer` was read, rather than pending or unreadable.
+    fn is_row_available(&self, buffer: Buffer, row: i64) -> bool {
+        let columns = self.window(buffer).1 as usize;
+        let cells = row as usize * columns..(row as usize + 1) * columns;
+
+        !self.pending.iter().any(|(other, rows)| *other == buffer && rows.contains(&row))
+            && !self.unreadable.iter().any(|(other, range)| {
+                *other == buffer && range.start < cells.end && range.end > cells.start
+            })
+    }
+
+    /// The ranges of bytes in the viewport whose value changed during the last update, refresh or
+    /// invalidation, such as a [`Content::refresh`] of a live source. The ranges are sorted. Only
+    /// the bytes in the byte area that were read both before and after are compared, and only if
+    /// the viewport didn't move. Use [`HexViewer::change_flash`] to highlight them.
+    pub fn changes(&self) -> &[Range<u64>] {
+        &self.changes
+    }
+
+    /// The absolute rows that were pending or unreadable during the last update, so they aren't
+    /// kept when the viewport moves.
+    fn stale_rows(&self) -> Vec<(Buffer, i64)> {
+        let pending = self.pending.iter()
+            .flat_map(|(buffer, rows)| rows.clone().map(|row| (*buffer, row)));
+
+        let unreadable = self.unreadable.iter()
+            .filter(|(_, range)| !range.is_empty())
+            .flat_map(|(buffer, range)| {
+                let columns = self.window(*buffer).1.max(1) as usize;
+                (range.start / columns..(range.end - 1) / columns + 1)
+                    .map(|row| (*buffer, row as i64))
+            });
+
+        pending.chain(unreadable)
+            .map(|(buffer, row)| (buffer, self.viewport.y + row))
+            .collect()
+    }
+
+    /// Sizes `buffer` for the viewport, and moves the rows that were already read for the
+    /// `previous` viewport to where they are in the current one. Returns the moved rows, relative
+    /// to the viewport. Rows are only moved if the viewport didn't move horizontally.
+    fn shift_rows(&mut self, buffer: Buffer, previous: Option<Viewport>) -> Range<i64> {
+        let viewport = self.viewport;
+        let (x, columns) = viewport.window(buffer);
+        let size = (columns * viewport.rows) as usize;
+
+        let dst = match buffer {
+            Buffer::Bytes => &mut self.data,
+            Buffer::Chars => &mut self.char_data,
+        };
+
+        let overlap = previous
+            .filter(|previous| {
+                previous.virtual_columns == viewport.virtual_columns
+                    && previous.window(buffer) == (x, columns)
+                    && dst.len() == (columns * previous.rows) as usize
+            })
+            .map(|previous| {
+                let first = viewport.y.max(previous.y);
+                let end = (viewport.y + viewport.rows).min(previous.y + previous.rows);
+                (previous.y, first..end)
+            })
+            .filter(|(_, rows)| columns > 0 && !rows.is_empty());
+
+        let Some((previous_y, rows)) = overlap else {
+            dst.resize(size, 0);
+            return 0..0;
+        };
+
+        // Grow before moving rows down and shrink after moving them up.
+        if dst.len() < size {
+            dst.resize(size, 0);
+        }
+
+        let src = ((rows.start - previous_y) * columns) as usize
+            ..((rows.end - previous_y) * columns) as usize;
+        dst.copy_within(src, ((rows.start - viewport.y) * columns) as usize);
+        dst.truncate(size);
+
+        rows.start - viewport.y..rows.end - viewport.y
+    }
+
+    /// Reads the rows around the viewport, unless the ones read before still cover it. If they
+    /// can't be read, they're left out and the viewport is read as usual.
+    fn read_margin(&mut self) {
+        let viewport = self.viewport;
+        let stride = viewport.virtual_columns;
+        let visible_end = ((viewport.y + viewport.rows) * stride).min(self.source_size);
+
+        let covered = self.margin.as_ref().is_some_and(|margin| {
+            margin.virtual_columns == stride
+                && margin.generation == self.generation
+                && margin.y <= viewport.y
+                && margin.y * stride + margin.data.len() as i64 >= visible_end
+        });
+
+        if covered {
+            return;
+        }
+
+        // Reuse the memory of the rows read before.
+        let mut data = self.margin.take().map(|margin| margin.data).unwrap_or_default();
+
+        let y = (viewport.y - self.margin_rows).max(0);
+        let start = y * stride;
+        let end = ((viewport.y + viewport.rows + self.margin_rows) * stride).min(self.source_size);
+
+        if start >= end || end - start > MAX_MARGIN_SIZE {
+            return;
+        }
+
+        data.clear();
+        data.resize((end - start) as usize, 0);
+        let mut total = 0;
+
+        while total < data.len() {
+            let length = (data.len() - total).min(MAX_BATCH_SIZE as usize);
+
+            // Errors are reported by the regular reads of the viewport.
+            match self.read_source((start + total as i64) as u64, &mut data[total..total + length]) {
+                Ok(0) => break,
+                Ok(read) => total += read,
+                Err(_) => return,
+            }
+        }
+
+        data.truncate(total);
+
+        self.margin = Some(Margin { y, data, virtual_columns: stride, generation: self.generation });
+    }
+
+    /// Copies the rows in the viewport into `buffer` from the rows around the viewport, if they
+    /// cover it. Returns whether they did.
+    fn copy_margin(&mut self, buffer: Buffer) -> bool {
+        let viewport = self.viewport;
+        let (x, columns) = self.window(buffer);
+        let stride = viewport.virtual_columns;
+
+        let Some(margin) = &self.margin else {
+            return false;
+        };
+
+        let visible_end = ((viewport.y + viewport.rows) * stride).min(self.source_size);
+
+        if margin.y > viewport.y || margin.y * stride + (margin.data.len() as i64) < visible_end {
+            return false;
+        }
+
+        let dst = match buffer {
+            Buffer::Bytes => &mut self.data,
+            Buffer::Chars => &mut self.char_data,
+        };
+
+        for row in 0..viewport.rows {
+            let offset = (viewport.y + row) * stride + x;
+
+            if offset >= self.source_size {
+                break;
+            }
+
+            let length = columns.min(self.source_size - offset) as usize;
+            let available = margin.data.get((offset - margin.y * stride) as usize..)
+                .unwrap_or_default();
+            let copied = available.len().min(length);
+            let dst_start = (row * columns) as usize;
+
+            dst[dst_start..dst_start + copied].copy_from_slice(&available[..copied]);
+            dst[dst_start + copied..dst_start + length].fill(0);
+        }
+
+        true
+    }
+
+    /// Reads all rows in the viewport that contain data into `buffer`, several rows at a time.
+    /// The `reused` rows are already in the buffer, and are only read again if they're `stale`.
+    fn read_rows(&mut self, buffer: Buffer, reused: Range<i64>, stale: &[(Buffer, i64)]) {
+        if self.copy_margin(buffer) {
+            return;
+        }
+
+        let viewport = self.viewport;
+        let (x, _) = self.window(buffer);
+        let stride = viewport.virtual_columns;
+
+        let rows = (0..viewport.rows)
+            .take_while(|row| (viewport.y + row) * stride + x < self.source_size)
+            .count() as i64;
+
+        for part in [0..reused.start.min(rows), reused.end.min(rows)..rows] {
+            self.read_batches(buffer, part);
+        }
+
+        let stale_rows = stale.iter()
+            .filter(|(stale_buffer, _)| *stale_buffer == buffer)
+            .map(|(_, row)| row - viewport.y)
+            .filter(|row| *row < rows && reused.contains(row));
+
+        for row in stale_rows {
+            self.read_batch(buffer, row..row + 1);
+        }
+    }
+
+    /// Reads consecutive rows of the viewport into `buffer`, several rows at a time.
+    fn read_batches(&mut self, buffer: Buffer, rows: Range<i64>) {
+        let (_, columns) = self.window(buffer);
+        let stride = self.viewport.virtual_columns;
+
+        // Reading several rows at once also reads the columns in between them, which is only worth
+        // it if those aren't the bulk of the read.
+        let batch_rows = if columns * 2 >= stride { (MAX_BATCH_SIZE / stride).max(1) } else { 1 };
+        let mut start = rows.start;
+
+        while start < rows.end {
+            let end = (start + batch_rows).min(rows.end);
+            self.read_batch(buffer, start..end);
+            start = end;
+        }
+    }
+
+    /// Queries the size of the [`Source`] again, and if it has changed, reads the data for the
+    /// current viewport again. Returns whether the size has changed. Call this periodically for a
+    /// source that grows or shrinks, for instance from a subscription.
+    pub fn check_size(&mut self) -> bool {
+        let generation = self.generation;
+        self.update_size();
+
+        if self.generation != generation {
+            self.update(self.viewport);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether the [`Source`] is still working on data for the current viewport, see
+    /// [`Content::poll`].
+    pub fn is_pending(&self) -> bool {
+        self.size_pending || !self.pending.is_empty()
+    }
+
+    /// Reads the data that was pending during the last update again. Returns whether any of it
+    /// has arrived. Like [`Content::update`], this replaces the [`Content::errors`].
+    ///
+    /// A [`Source`] whose data isn't available yet returns [`io::ErrorKind::WouldBlock`], and the
+    /// [`HexViewer`] displays a placeholder until it arrives. Call this in response to the message
+    /// set with [`HexViewer::on_pending`].
+    pub fn poll(&mut self) -> bool {
+        if self.size_pending {
+            // Without the size the viewport may not even be filled, so start over.
+            self.update(self.viewport);
+            return !self.size_pending;
+        }
+
+        if self.pending.is_empty() {
+            return false;
+        }
+
+        self.updates += 1;
+        self.errors.clear();
+
+        let pending = std::mem::take(&mut self.pending);
+        let count = pending.len();
+
+        for (buffer, rows) in pending {
+            self.read_batch(buffer, rows);
+        }
+
+        self.pending.len() < count
+    }
+
+    /// Reads consecutive rows of the viewport with a single read, and keeps track of them if
+    /// they're pending or unreadable. If the read fails, the rows are read one by one, so only the
+    /// rows that fail are unreadable.
+    fn read_batch(&mut self, buffer: Buffer, rows: Range<i64>) {
+        let viewport = self.viewport;
+        let (x, columns) = self.window(buffer);
+        let stride = viewport.virtual_columns;
+
+        let start = (viewport.y + rows.start) * stride + x;
+        let end = ((viewport.y + rows.end - 1) * stride + x + columns).min(self.source_size);
+
+        let mut data = std::mem::take(&mut self.batch_buffer);
+        data.clear();
+        data.resize((end - start).max(0) as usize, 0);
+        let result = self.read_source(start as u64, &mut data);
+
+        let dst = match buffer {
+            Buffer::Bytes => &mut self.data,
+            Buffer::Chars => &mut self.char_data,
+        };
+
+        // The range of each row in `data` and in `dst`.
+        let row_ranges = rows.clone().map(|row| {
+            let src = ((row - rows.start) * stride) as usize;
+            let length = columns.min(end - start - src as i64).max(0) as usize;
+            let dst = (row * columns) as usize;
+
+            (row, src..src + length, dst..dst + length)
+        });
+
+        match result {
+            Ok(read) => {
+                let mut short = Vec::new();
+
+                for (row, src, dst_range) in row_ranges {
+                    // A single row is used as far as it was read, like any read of a row would be.
+                    if src.end > read && rows.end - rows.start > 1 {
+                        short.push(row);
+                        continue;
+                    }
+
+                    let length = src.len().min(read.saturating_sub(src.start));
+                    dst[dst_range.start..dst_range.start + length]
+                        .copy_from_slice(&data[src.start..src.start + length]);
+                    dst[dst_range.start + length..dst_range.end].fill(0);
+                }
+
+                for row in short {
+                    self.read_batch(buffer, row..row + 1);
+                }
+            }
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => {
+                for (_, _, dst_range) in row_ranges {
+                    dst[dst_range].fill(0);
+                }
+
+                self.pending.push((buffer, rows));
+            }
+            Err(_) if rows.end - rows.start > 1 => {
+                for row in rows {
+                    self.read_batch(buffer, row..row + 1);
+                }
+            }
+            Err(error) => {
+                let dst_range = (rows.start * columns) as usize..(rows.start * columns) as usize
+                    + data.len();
+                dst[dst_range.clone()].fill(0);
+
+                self.errors.push(ReadError::new(Some(start as u64..end as u64), &error));
+                self.unreadable.push((buffer, dst_range));
+            }
+        }
+
+        self.batch_buffer = data;
+    }
+
+    /// The first column and the number of columns that are read into `buffer`.
+    fn window(&self, buffer: Buffer) -> (i64, i64) {
+        self.viewport.window(buffer)
+    }
+
+    /// The errors the [`Source`] returned during the last [`Content::update`] or
+    /// [`Content::poll`], including a failure to determine its size.
+    pub fn errors(&self) -> &[ReadError] {
+        &self.errors
+    }
+
+    /// The byte at the absolute `offset`, if it was read with the viewport or the
+    /// [`Content::margin_rows`].
+    fn loaded_byte(&self, offset: i64) -> Option<u8> {
+        if !(0..self.source_size).contains(&offset) {
+            return None;
+        }
+
+        let stride = self.viewport.virtual_columns;
+
+        if let Some(margin) = &self.margin
+            && margin.virtual_columns == stride
+            && margin.generation == self.generation
+            && offset >= margin.y * stride
+            && let Some(&value) = margin.data.get((offset - margin.y * stride) as usize)
+        {
+            return Some(value);
+        }
+
+        let (column, row) = self.viewport.contains(offset as u64)?;
+        let index = (row as i64 * self.viewport.columns + column as i64) as usize;
+
+        let pending = self.pending.iter()
+            .any(|(buffer, rows)| *buffer == Buffer::Bytes && rows.contains(&(row as i64)));
+        let unreadable = self.unreadable.iter()
+            .any(|(buffer, range)| *buffer == Buffer::Bytes && range.contains(&index));
+
+        if pending || unreadable {
+            None
+        } else {
+            self.data.get(index).copied()
+        }
+    }
+
+    /// The cell of `item` as it shows the byte at `offset` instead. The byte is shown as pending if
+    /// it isn't loaded, such as when it's just outside the window.
+    fn displayed_item(&self, item: ContentItem, offset: i64) -> ContentItem {
+        if offset == item.offset {
+            return item;
+        }
+
+        let (value, availability) = match self.loaded_byte(offset) {
+            Some(value) => (value, Availability::Ready),
+            None if self.unreadable_byte(offset) => (0, Availability::Unreadable),
+            None => (0, Availability::Pending),
+        };
+
+        ContentItem::new(offset, item.column, item.row, value, availability)
+    }
+
+    /// Whether the byte at the absolute `offset` is in the viewport but couldn't be read.
+    fn unreadable_byte(&self, offset: i64) -> bool {
+        self.viewport.contains(offset as u64).is_some_and(|(column, row)| {
+            let index = (row as i64 * self.viewport.columns + column as i64) as usize;
+
+            self.unreadable.iter()
+                .any(|(buffer, range)| *buffer == Buffer::Bytes && range.contains(&index))
+        })
+    }
+
+    /// Determines the size of the [`Source`]. If that fails, the last known size is kept.
+    fn update_size(&mut self) {
+        self.size_pending = false;
+
+        match self.source.size() {
+            Ok(size) => {
+                if size as i64 != self.source_size {
+                    self.generation += 1;
+                }
+
+                self.source_size = size as i64;
+            }
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => self.size_pending = true,
+            Err(error) => self.errors.push(ReadError::new(None, &error)),
+        }
+    }
+
+    /// The [`Viewport`] the data was last read for.
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    /// The size of the [`Source`] as of the last call to [`Content::new`] or [`Content::update`].
+    pub fn size(&self) -> u64 {
+        self.source_size as u64
+    }
+
+    /// Describes the [`Source`].
+    pub fn info(&self) -> SourceInfo {
+        self.source.info()
+    }
+
+    /// Reads straight from the [`Source`], regardless of the current viewport. The read is widened
+    /// to the [`Content::read_alignment`].
+    pub(crate) fn read_source(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let alignment = self.read_alignment;
+        let start = offset - offset % alignment;
+        let end = (offset + buf.len() as u64).div_ceil(alignment) * alignment;
+        // Don't read beyond the end, unless the read already did.
+        let end = end.min((self.source_size as u64).max(offset + buf.len() as u64));
+
+        if start == offset && end == offset + buf.len() as u64 {
+            return self.source.read(offset, buf);
+        }
+
+        let mut data = std::mem::take(&mut self.aligned_buffer);
+        data.clear();
+        data.resize((end - start) as usize, 0);
+
+        let result = self.source.read(start, &mut data);
+        let skip = (offset - start) as usize;
+
+        let result = result.map(|read| {
+            let copied = read.saturating_sub(skip).min(buf.len());
+            buf[..copied].copy_from_slice(&data[skip..skip + copied]);
+            copied
+        });
+
+        self.aligned_buffer = data;
+
+        result
+    }
+
+    /// Reads an absolute range of bytes straight from the [`Source`], regardless of the current
+    /// viewport. Use this to copy, hash or inspect data without keeping a second handle to the
+    /// source. The range is clamped to [`Content::size`], and fewer bytes are returned if the
+    /// source runs out of data.
+    ///
+    /// The whole range is held in memory. For large ranges, read in parts or see
+    /// [`Selection::read_chunks`].
+    pub fn read_range(&mut self, range: Range<u64>) -> io::Result<Vec<u8>> {
+        let end = range.end.min(self.size());
+        let mut data = vec![0; end.saturating_sub(range.start) as usize];
+        let mut total = 0;
+
+        while total < data.len() {
+            let read = self.read_source(range.start + total as u64, &mut data[total..])?;
+
+            if read == 0 {
+                break;
+            }
+
+            total += read;
+        }
+
+        data.truncate(total);
+
+        Ok(data)
+    }
+
+    /// Finds the position the cursor jumps to with a [`RunJump`], by scanning the [`Source`]
+    /// regardless of the current viewport. Moving forward goes to the start of the next run, and
+    /// moving back to the start of the run, or of the previous one if the cursor is already at it.
+    /// Returns `None` if the cursor can't move.
+    ///
+    /// At most 64 MiB is scanned. A longer run is jumped over up to there, and the next jump
+    /// continues from there.
+    pub fn run_jump(&mut self, jump: RunJump) -> io::Result<Option<u64>> {
+        let from = jump.from.min(i64::MAX as u64) as i64;
+        let step = jump.step;
+        let size = self.source_size;
+        let in_source = |offset: i64| (0..size).contains(&offset);
+        let in_reach = |offset: i64| in_source(offset) && (offset - from).abs() <= MAX_RUN_SCAN;
+        let mut scan = RunScan::default();
+
+        if step == 0 || !in_source(from.saturating_add(step)) {
+            return Ok(None);
+        }
+
+        let mut position = if step > 0 { from } else { from + step };
+        let run = scan.byte(self, position, step > 0)?;
+
+        while in_reach(position + step) && scan.byte(self, position + step, step > 0)? == run {
+            position += step;
+        }
+
+        if step < 0 {
+            return Ok(Some(position as u64));
+        }
+
+        let next = position + step;
+
+        if in_source(next) {
+            Ok(Some(next as u64))
+        } else {
+            Ok((position != from).then_some(position as u64))
+        }
+    }
+
+    /// The buffer that holds the data for the char area.
+    fn char_buffer(&self) -> Buffer {
+        if self.viewport.is_split() { Buffer::Chars } else { Buffer::Bytes }
+    }
+
+    fn iter(&self, buffer: Buffer) -> impl Iterator<Item = ContentItem> {
+        if self.viewport.virtual_columns == 0 {
+            panic!("Virtual column count not set");
+        };
+
+        let data = match buffer {
+            Buffer::Bytes => &self.data,
+            Buffer::Chars => &self.char_data,
+        };
+        let (x, columns) = self.window(buffer);
+
+        data.iter().enumerate().map(move |(i, v)| {
+
+            let row = i as i64 / columns;
+            let col = i as i64 % columns;
+
+            let offset = (self.viewport.y + row) * self.viewport.virtual_columns + x + col;
+
+            let availability = if self.unreadable.iter()
+                .any(|(b, range)| *b == buffer && range.contains(&i))
+            {
+                Availability::Unreadable
+            } else if self.pending.iter().any(|(b, rows)| *b == buffer && rows.contains(&row)) {
+                Availability::Pending
+            } else {
+                Availability::Ready
+            };
+
+            ContentItem::new(offset, col, row, *v, availability)
+        }).take_while(|item| item.offset < self.source_size)
+    }
+}
+
+/// The data [`Content::run_jump`] reads from the [`Source`], a batch at a time.
+#[derive(Default)]
+struct RunScan {
+    start: i64,
+    data: Vec<u8>,
+}
+
+impl RunScan {
+    /// The byte at `offset`. When it has to be read, the batch starts at it when scanning
+    /// `forward`, and ends at it otherwise.
+    fn byte(&mut self, content: &mut Content, offset: i64, forward: bool) -> io::Result<u8> {
+        if offset >= self.start
+            && let Some(&byte) = self.data.get((offset - self.start) as usize)
+        {
+            return Ok(byte);
+        }
+
+        self.start = if forward { offset } else { (offset + 1 - MAX_BATCH_SIZE).max(0) };
+        let end = (self.start + MAX_BATCH_SIZE).min(content.source_size);
+
+        self.data.resize((end - self.start).max(0) as usize, 0);
+        let read = content.read_source(self.start as u64, &mut self.data)?;
+        self.data.truncate(read);
+
+        self.data.get((offset - self.start) as usize)
+            .copied()
+            .ok_or_else(|| io::ErrorKind::UnexpectedEof.into())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Empty {}
+
+impl Source for Empty {
+    fn read(&mut self, _: u64, _: &mut [u8]) -> io::Result<usize> {
+        Ok(0)
+    }
+
+    fn size(&mut self) -> io::Result<u64> {
+        Ok(0)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ContentItem {
+    offset: i64,
+    column: i64,
+    row: i64,
+    value: u8,
+    /// Whether the byte could be read from the [`Source`].
+    availability: Availability,
+}
+
+/// The buffers of a [`Content`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Buffer {
+    /// The data shown in the byte area, and in the char area unless it scrolls on its own.
+    Bytes,
+    /// The data shown in the char area when it scrolls on its own.
+    Chars,
+}
+
+/// Whether the data of a [`ContentItem`] could be read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Availability {
+    Ready,
+    /// The [`Source`] will provide the data later.
+    Pending,
+    /// The [`Source`] returned an error.
+    Unreadable,
+}
+
+impl ContentItem {
+    fn new(offset: i64, column: i64, row: i64, byte: u8, availability: Availability) -> Self {
+        Self {
+            offset,
+            column,
+            row,
+            value: byte,
+            availability,
+        }
+    }
+}
+
+/// An error returned by a [`Source`], as reported by [`HexViewer::on_read_error`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadError {
+    /// The absolute range that couldn't be read, or `None` if the size couldn't be determined.
+    pub range: Option<Range<u64>>,
+    /// The kind of error.
+    pub kind: io::ErrorKind,
+    /// The error message.
+    pub message: String,
+}
+
+impl ReadError {
+    fn new(range: Option<Range<u64>>, error: &io::Error) -> Self {
+        Self {
+            range,
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// The source of [`Content`]. It's typically a static source of bytes such as a file that isn't
+/// modified as long as the `Source` is in use. A source may grow or shrink, for instance a log
+/// file that's being written to: call [`Content::check_size`] periodically to pick up the new size,
+/// and see [`HexViewer::follow_tail`].
+pub trait Source: Debug {
+    /// Read as many bytes as necessary to fill `buf`, starting from `offset` in the source file.
+    /// [`Content`] reads several rows at once, up to 64 KiB, unless the viewport shows only a small
+    /// part of each row. Then it reads one row at a time, and one call to its
+    /// [`Content::update`] method can result in a lot of very small reads. Depending on how well
+    /// the OS caches the file it may be prudent to implement some form of caching in the
+    /// implementation of this `Source` trait.
+    ///
+    /// Returns the number of bytes read. If an error is returned, the [`HexViewer`] displays the
+    /// bytes as unreadable and reports the error with [`HexViewer::on_read_error`].
+    ///
+    /// A slow source shouldn't block. It can return [`io::ErrorKind::WouldBlock`] instead, until
+    /// the data has arrived. See [`Content::poll`] and [`ThreadedSource`].
+    ///
+    /// [`ThreadedSource`]: crate::hex::source::ThreadedSource
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Gets the file size. `self` is mut so that the file size can be lazily loaded and cached.
+    /// Like [`Source::read`], this may return [`io::ErrorKind::WouldBlock`].
+    fn size(&mut self) -> io::Result<u64>;
+
+    /// Drops any data the source has read ahead or cached, and opens the underlying resource again
+    /// if it can, because its data may have changed. [`Content::refresh`] calls this before reading
+    /// the viewport again. Sources that wrap another one pass it on. The default implementation
+    /// does nothing.
+    fn reload(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Describes the source. The default implementation returns an empty [`SourceInfo`].
+    fn info(&self) -> SourceInfo {
+        SourceInfo::default()
+    }
+}
+
+/// A description of a [`Source`], for titles, read-only enforcement and read alignment.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceInfo {
+    /// A name to display, such as the file name.
+    pub name: Option<String>,
+    /// Whether the source can be written to.
+    pub writable: bool,
+    /// Whether the data may change while it's shown, for instance because another process writes
+    /// to it.
+    pub volatile: bool,
+    /// The size of the blocks the source prefers to be read in, if any.
+    pub block_size: Option<u64>,
+}
+
+impl<'a, Message, Theme, Renderer> From<HexViewer<'a, Message, Theme>>
+for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a + Clone,
+    Renderer: text::Renderer<Font = Font> + 'static,
+    Renderer::Paragraph: Clone,
+    Theme: Catalog + 'static,
+{
+    fn from(
+        hex_viewer_widget: HexViewer<'a, Message, Theme>,
+    ) -> Element<'a, Message, Theme, Renderer> {
+        Self::new(hex_viewer_widget)
+    }
+}
+
+pub fn hex_viewer_widget<Message, Theme>(content: &Content) -> HexViewer<'_, Message, Theme>
+where
+    Theme: Catalog
+{
+    HexViewer::new(content)
+}
+
+/// Rounds `columns` down to a power of two from 1 to 64, like [`HexViewer::snap_columns`] does,
+/// for instance to snap the value of a slider that sets [`HexViewer::virtual_columns`].
+pub fn power_of_two_columns(columns: u64) -> u64 {
+    const MAX_SNAPPED_COLUMNS: u64 = 64;
+
+    match columns.min(MAX_SNAPPED_COLUMNS) {
+        0 => 1,
+        columns => 1 << columns.ilog2(),
+    }
+}
+
+/// The address of the byte at `offset` when the first byte is at the address `base`, as shown in
+/// the address area of a [`HexViewer`] with [`HexViewer::base_address`]. Saturates at `u64::MAX`.
+pub fn to_address(base: u64, offset: u64) -> u64 {
+    base.saturating_add(offset)
+}
+
+/// The offset of the byte at `address` when the first byte is at the address `base`, the inverse
+/// of [`to_address`]. `None` if the address lies before `base`.
+pub fn from_address(base: u64, address: u64) -> Option<u64> {
+    address.checked_sub(base)
+}
+
+struct State<R: Renderer>
+where
+    R: text::Renderer<Font = Font> + 'static,
+    R::Paragraph: Clone,
+{
+    text_cache: TextCache<R>,
+    keyboard_modifiers: keyboard::Modifiers,
+    /// State of the [`ScrollArea`].
+    scroll_area_state: ScrollAreaState,
+    /// The last reported selection.
+    last_reported_selection: Option<Selection>,
+    /// The last reported viewport, and the last reported-to Content.
+    last_reported_viewport: Option<(Viewport, u64)>,
+    /// Whether we're making a selection by left click + dragging the mouse.
+    dragging: bool,
+    /// Whether the selection being made by mouse is a rectangular block selection.
+    block_selection: bool,
+    /// Whether the selection being made by mouse was started in the char area.
+    drag_in_chars: bool,
+    /// Tracks time between scroll steps when a selection is dragged beyond the edges.
+    auto_scroll_timer: Option<Timer>,
+    /// Set while the [`Content`] is waiting for data.
+    pending_timer: Option<Timer>,
+    /// What was last seen of the end of the content, see [`HexViewer::follow_tail`].
+    tail: Option<Tail>,
+    /// Absolute start index for a current or potential selection.
+    start_index: Option<Index>,
+    /// The last click, used to recognize double and triple clicks.
+    last_click: Option<mouse::Click>,
+    /// Set when the selection was pressed, until it's either dragged or released.
+    pending_drag: Option<PendingDrag>,
+    /// The start of the row that rows are selected from while the address area is dragged.
+    row_drag: Option<i64>,
+    /// The [`Content::id`], instance and viewport of the content the state applies to.
+    content: Option<(u64, u64, Viewport)>,
+    /// The last reported layout.
+    last_reported_layout: Option<LayoutSnapshot>,
+    /// The [`Content::id`] and update count of the content whose errors were last reported.
+    last_reported_update: Option<(u64, u64)>,
+    /// The [`Content::id`] and [`Content::generation`] that were last reported.
+    last_reported_generation: Option<(u64, u64)>,
+    /// The [`Content::id`] and size of the content as of the last update.
+    last_size: Option<(u64, i64)>,
+    /// Whether this widget is focussed, and should accept keyboard input.
+    focussed: bool,
+    /// Tracks time between scrollbar jumps when the track is being pressed, for both the horizontal
+    /// and vertical scrollbar.
+    track_timer: Option<Timer>,
+    /// Used for highlighting the byte/char header cell above the cursor.
+    hovered_column: Option<i64>,
+    /// Used for highlighting the address area cell left of the cursor.
+    hovered_row: Option<i64>,
+    /// Commands sent by operations, carried out during the next update.
+    commands: Commands,
+    /// Whether a file is dragged over the window, see [`HexViewer::on_file_dropped`].
+    file_hovered: bool,
+    /// The [`Content::id`] and number of changes of the content when changes were last seen.
+    last_change: Option<(u64, u64)>,
+    /// The changes that are highlighted and when they were found, see
+    /// [`HexViewer::change_flash`].
+    flash: Option<(Vec<Range<u64>>, Instant)>,
+    /// The start of the clock of all [`CellAnimation`]s.
+    animation_clock: Instant,
+    /// Whether `z` was pressed, which starts a key sequence that aligns the cursor.
+    align_pending: bool,
+    /// The jumps of the cursor, see [`CursorHistory`].
+    cursor_history: CursorHistory,
+    /// The [`Content::id`] and cursor as of the last update, to find jumps.
+    last_cursor: Option<(u64, i64)>,
+    /// Where going back or forward in the history moved the cursor, which isn't a new jump.
+    history_target: Option<i64>,
+    /// The history that was last reported. It starts out empty, so an empty history isn't
+    /// reported until something was recorded.
+    last_reported_history: CursorHistory,
+    /// The hovered byte that was last reported.
+    last_reported_hover: Option<HoveredByte>,
+    /// Notices waiting to be displayed, see [`show_notice`].
+    notices: VecDeque<Notice>,
+    /// The notice that's displayed and when it's dismissed.
+    notice: Option<(text::paragraph::Plain<R::Paragraph>, Timer)>,
+}
+
+impl<R: Renderer> State<R>
+where
+    R: text::Renderer<Font = Font>,
+    R::Paragraph: Clone,
+{
+    fn new() -> Self {
+        Self {
+            text_cache: TextCache::new(),
+            keyboard_modifiers: keyboard::Modifiers::default(),
+            scroll_area_state: ScrollAreaState::default(),
+            last_reported_selection: None,
+            last_reported_viewport: None,
+            dragging: false,
+            block_selection: false,
+            drag_in_chars: false,
+            auto_scroll_timer: None,
+            pending_timer: None,
+            tail: None,
+            start_index: None,
+            last_click: None,
+            pending_drag: None,
+            row_drag: None,
+            content: None,
+            last_reported_layout: None,
+            last_reported_update: None,
+            last_reported_generation: None,
+            last_size: None,
+            focussed: false,
+            track_timer: None,
+            hovered_column: None,
+            hovered_row: None,
+            commands: Commands::default(),
+            file_hovered: false,
+            last_change: None,
+            flash: None,
+            animation_clock: Instant::now(),
+            align_pending: false,
+            cursor_history: CursorHistory::default(),
+            last_cursor: None,
+            history_target: None,
+            last_reported_history: CursorHistory::default(),
+            last_reported_hover: None,
+            notices: VecDeque::new(),
+            notice: None,
+        }
+    }
+
+    fn start_index_or_set(&mut self, cursor: i64) -> Index {
+        if let Some(index) = self.start_index {
+            index
+        } else {
+            let index = Index::new(cursor, Side::None);
+            self.start_index = Some(index);
+            index
+        }
+    }
+}
+
+/// The distance in pixels the mouse needs to move while pressing the selection to drag it.
+const DRAG_THRESHOLD: f32 = 4.0;
+
+/// The width in pixels of the vertical scrollbar when it draws a [`Minimap`].
+const MINIMAP_WIDTH: f32 = 24.0;
+
+/// A press on the selection that may become a drag.
+#[derive(Clone, Copy, Debug)]
+struct PendingDrag {
+    selection: Selection,
+    origin: Point,
+    /// Where the selection was pressed.
+    index: Index,
+}
+
+/// Commands that operations leave for the [`HexViewer`], since operations can't publish messages.
+/// Unlike [`State`] this isn't generic, so operations can find it.
+#[derive(Debug, Default)]
+struct Commands {
+    selection: Option<SelectionCommand>,
+    notices: Vec<NoticeCommand>,
+    history: Option<HistoryCommand>,
+    scroll: Option<ScrollCommand>,
+    scroll_by: Option<ScrollAmount>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum SelectionCommand {
+    Set(Option<Selection>),
+    Extend(u64),
+}
+
+/// An [`Operation`] that sends a [`SelectionCommand`] to the [`HexViewer`] with the target [`Id`].
+struct SelectionOperation {
+    target: Id,
+    command: SelectionCommand,
+}
+
+impl<T> Operation<T> for SelectionOperation {
+    fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation<T>)) {
+        operate(self);
+    }
+
+    fn custom(&mut self, id: Option<&Id>, _bounds: Rectangle, state: &mut dyn Any) {
+        if id == Some(&self.target)
+            && let Some(commands) = state.downcast_mut::<Commands>()
+        {
+            commands.selection = Some(self.command);
+        }
+    }
+}
+
+/// Produces an [`Operation`] that retrieves the [`LayoutSnapshot`] of the [`HexViewer`] with the
+/// given [`Id`].
+pub fn layout_snapshot(id: impl Into<Id>) -> impl Operation<LayoutSnapshot> {
+    struct Snapshot {
+        target: Id,
+        snapshot: Option<LayoutSnapshot>,
+    }
+
+    impl Operation<LayoutSnapshot> for Snapshot {
+        fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation<LayoutSnapshot>)) {
+            operate(self);
+        }
+
+        fn custom(&mut self, id: Option<&Id>, _bounds: Rectangle, state: &mut dyn Any) {
+            if id == Some(&self.target)
+                && let Some(snapshot) = state.downcast_ref::<LayoutSnapshot>()
+            {
+                self.snapshot = Some(snapshot.clone());
+            }
+        }
+
+        fn finish(&self) -> Outcome<LayoutSnapshot> {
+            match &self.snapshot {
+                Some(snapshot) => Outcome::Some(snapshot.clone()),
+                None => Outcome::None,
+            }
+        }
+    }
+
+    Snapshot {
+        target: id.into(),
+        snapshot: None,
+    }
+}
+
+/// Produces an [`Operation`] that sets the selection of the [`HexViewer`] with the given [`Id`],
+/// or clears it if `None`. The selection is published with [`HexViewer::on_selection`] as if the
+/// user made it, and the user can continue it with SHIFT.
+pub fn select<T>(id: impl Into<Id>, selection: Option<Selection>) -> impl Operation<T> {
+    SelectionOperation {
+        target: id.into(),
+        command: SelectionCommand::Set(selection),
+    }
+}
+
+/// Produces an [`Operation`] that extends the selection of the [`HexViewer`] with the given [`Id`]
+/// up to and including `offset`, as if the user clicked it while holding SHIFT. If there's no
+/// selection, it's started at the cursor.
+pub fn extend_selection<T>(id: impl Into<Id>, offset: u64) -> impl Operation<T> {
+    SelectionOperation {
+        target: id.into(),
+        command: SelectionCommand::Extend(offset),
+    }
+}
+
+/// Produces an [`Operation`] that clears the selection of the [`HexViewer`] with the given [`Id`].
+pub fn clear_selection<T>(id: impl Into<Id>) -> impl Operation<T> {
+    select(id, None)
+}
+
+/// What a [`HexViewer`] displays instead of data when its [`Content`] is empty, see
+/// [`HexViewer::placeholder`]. By default it displays "Empty", and "Drop a file here" if
+/// [`HexViewer::on_file_dropped`] is set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Placeholder {
+    icon: Option<String>,
+    message: String,
+    drop_hint: Option<String>,
+}
+
+impl Default for Placeholder {
+    fn default() -> Self {
+        Self::new("Empty").drop_hint("Drop a file here")
+    }
+}
+
+impl Placeholder {
+    /// Creates a new placeholder with only a message.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            icon: None,
+            message: message.into(),
+            drop_hint: None,
+        }
+    }
+
+    /// Sets a short text that's displayed large above the message, such as an emoji or a glyph.
+    /// Like the message, it's displayed in the default font of the renderer.
+    pub fn icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Sets a hint that's displayed below the message if [`HexViewer::on_file_dropped`] is set.
+    pub fn drop_hint(mut self, hint: impl Into<String>) -> Self {
+        self.drop_hint = Some(hint.into());
+        self
+    }
+}
+
+/// A short message that's displayed over the data of a [`HexViewer`] for a while, such as "Copied
+/// 128 bytes" or "Offset out of range". Show it with [`show_notice`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notice {
+    text: String,
+    duration: Duration,
+}
+
+impl Notice {
+    /// Creates a new notice that's displayed for 2 seconds.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            duration: Duration::from_secs(2),
+        }
+    }
+
+    /// Sets how long the notice is displayed.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// The text of the notice.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl From<&str> for Notice {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for Notice {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+#[derive(Clone, Debug)]
+enum NoticeCommand {
+    Show(Notice),
+    Clear,
+}
+
+/// An [`Operation`] that sends a [`NoticeCommand`] to the [`HexViewer`] with the target [`Id`].
+struct NoticeOperation {
+    target: Id,
+    command: Option<NoticeCommand>,
+}
+
+impl<T> Operation<T> for NoticeOperation {
+    fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation<T>)) {
+        operate(self);
+    }
+
+    fn custom(&mut self, id: Option<&Id>, _bounds: Rectangle, state: &mut dyn Any) {
+        if id == Some(&self.target)
+            && let Some(commands) = state.downcast_mut::<Commands>()
+            && let Some(command) = self.command.take()
+        {
+            commands.notices.push(command);
+        }
+    }
+}
+
+/// Produces an [`Operation`] that displays a [`Notice`] over the data of the [`HexViewer`] with the
+/// given [`Id`]. Notices are displayed one at a time, in the order they were shown. While one is
+/// displayed, up to 8 others are queued, and the oldest of them are dropped after that.
+pub fn show_notice<T>(id: impl Into<Id>, notice: impl Into<Notice>) -> impl Operation<T> {
+    NoticeOperation {
+        target: id.into(),
+        command: Some(NoticeCommand::Show(notice.into())),
+    }
+}
+
+/// Produces an [`Operation`] that removes the displayed and queued notices of the [`HexViewer`]
+/// with the given [`Id`].
+pub fn clear_notices<T>(id: impl Into<Id>) -> impl Operation<T> {
+    NoticeOperation {
+        target: id.into(),
+        command: Some(NoticeCommand::Clear),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ScrollCommand {
+    /// The offset to scroll to, or `None` for the cursor.
+    offset: Option<u64>,
+    alignment: Alignment,
+}
+
+/// An [`Operation`] that sends a [`ScrollCommand`] to the [`HexViewer`] with the target [`Id`].
+struct ScrollOperation {
+    target: Id,
+    command: ScrollCommand,
+}
+
+impl<T> Operation<T> for ScrollOperation {
+    fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation<T>)) {
+        operate(self);
+    }
+
+    fn custom(&mut self, id: Option<&Id>, _bounds: Rectangle, state: &mut dyn Any) {
+        if id == Some(&self.target)
+            && let Some(commands) = state.downcast_mut::<Commands>()
+        {
+            commands.scroll = Some(self.command);
+        }
+    }
+}
+
+/// Produces an [`Operation`] that scrolls the [`HexViewer`] with the given [`Id`] to the absolute
+/// `offset`, with its row at the `alignment` of the viewport, to implement "Go to offset". Offsets
+/// past the end are clamped to the last byte. The cursor doesn't move, and the new viewport is
+/// published with [`HexViewer::on_scrolled`]. Run it with `iced::widget::operate`:
+///
+/// ```ignore
+/// Message::GoTo(offset) => operate(viewer::scroll_to(VIEWER, offset, Alignment::Center)),
+/// ```
+pub fn scroll_to<T>(id: impl Into<Id>, offset: u64, alignment: Alignment) -> impl Operation<T> {
+    ScrollOperation {
+        target: id.into(),
+        command: ScrollCommand { offset: Some(offset), alignment },
+    }
+}
+
+/// Produces an [`Operation`] that scrolls the [`HexViewer`] with the given [`Id`] so the row of
+/// the cursor is at the `alignment` of the viewport, without moving the cursor. The user does the
+/// same with `zt`, `zz` and `zb` for [`Alignment::Start`], [`Alignment::Center`] and
+/// [`Alignment::End`], as in vim.
+pub fn align_cursor<T>(id: impl Into<Id>, alignment: Alignment) -> impl Operation<T> {
+    ScrollOperation {
+        target: id.into(),
+        command: ScrollCommand { offset: None, alignment },
+    }
+}
+
+/// An [`Operation`] that sends a [`ScrollAmount`] to the [`HexViewer`] with the target [`Id`].
+struct ScrollByOperation {
+    target: Id,
+    amount: ScrollAmount,
+}
+
+impl<T> Operation<T> for ScrollByOperation {
+    fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation<T>)) {
+        operate(self);
+    }
+
+    fn custom(&mut self, id: Option<&Id>, _bounds: Rectangle, state: &mut dyn Any) {
+        if id == Some(&self.target)
+            && let Some(commands) = state.downcast_mut::<Commands>()
+        {
+            commands.scroll_by = Some(self.amount);
+        }
+    }
+}
+
+/// Produces an [`Operation`] that scrolls the [`HexViewer`] with the given [`Id`] up or down by
+/// the `amount`, without moving the cursor. The new viewport is published with
+/// [`HexViewer::on_scrolled`].
+pub fn scroll_by<T>(id: impl Into<Id>, amount: ScrollAmount) -> impl Operation<T> {
+    ScrollByOperation {
+        target: id.into(),
+        amount,
+    }
+}
+
+/// How far to scroll with [`scroll_by`]. Negative amounts scroll up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScrollAmount {
+    /// A number of rows.
+    Rows(i64),
+    /// A number of pages, which are the rows that fully fit in the viewport. `0.5` scrolls half
+    /// a page, like CTRL+D. Rounded to whole rows, but at least one row if not 0.
+    Pages(f32),
+}
+
+impl ScrollAmount {
+    /// The number of rows, with `page` rows in a page.
+    fn rows(self, page: i64) -> i64 {
+        match self {
+            ScrollAmount::Rows(rows) => rows,
+            ScrollAmount::Pages(pages) => {
+                let rows = (pages * page as f32).round() as i64;
+
+                if rows == 0 && pages != 0.0 { pages.signum() as i64 } else { rows }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum HistoryCommand {
+    Back,
+    Forward,
+}
+
+/// An [`Operation`] that sends a [`HistoryCommand`] to the [`HexViewer`] with the target [`Id`].
+struct HistoryOperation {
+    target: Id,
+    command: HistoryCommand,
+}
+
+impl<T> Operation<T> for HistoryOperation {
+    fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation<T>)) {
+        operate(self);
+    }
+
+    fn custom(&mut self, id: Option<&Id>, _bounds: Rectangle, state: &mut dyn Any) {
+        if id == Some(&self.target)
+            && let Some(commands) = state.downcast_mut::<Commands>()
+        {
+            commands.history = Some(self.command);
+        }
+    }
+}
+
+/// Produces an [`Operation`] that moves the cursor of the [`HexViewer`] with the given [`Id`]
+/// back to where it was before the last jump, like ALT+LEFT. See [`CursorHistory`].
+pub fn cursor_back<T>(id: impl Into<Id>) -> impl Operation<T> {
+    HistoryOperation {
+        target: id.into(),
+        command: HistoryCommand::Back,
+    }
+}
+
+/// Produces an [`Operation`] that moves the cursor of the [`HexViewer`] with the given [`Id`]
+/// forward again after going back, like ALT+RIGHT. See [`CursorHistory`].
+pub fn cursor_forward<T>(id: impl Into<Id>) -> impl Operation<T> {
+    HistoryOperation {
+        target: id.into(),
+        command: HistoryCommand::Forward,
+    }
+}
+
+/// The positions the cursor of a [`HexViewer`] jumped between, to go back and forward like in a
+/// code editor.
+///
+/// A jump is a move of the cursor over more bytes than fit in the viewport, such as to a search
+/// hit, to an offset the application went to, or with CTRL+HOME. Smaller moves, including a page
+/// up or down, aren't recorded. The `HexViewer` keeps the history itself and goes back and forward
+/// with ALT+LEFT and ALT+RIGHT, or [`cursor_back`] and [`cursor_forward`]. It's passed to the
+/// application with [`HexViewer::on_cursor_history`]. At most 100 positions are kept.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CursorHistory {
+    /// The positions, oldest first.
+    entries: Vec<u64>,
+    /// The index of the current position in `entries`.
+    position: usize,
+}
+
+impl CursorHistory {
+    /// The positions, oldest first.
+    pub fn entries(&self) -> &[u64] {
+        &self.entries
+    }
+
+    /// The index of the current position in [`CursorHistory::entries`], if there are any. Entries
+    /// after it were gone back from.
+    pub fn position(&self) -> Option<usize> {
+        (!self.entries.is_empty()).then_some(self.position)
+    }
+
+    /// Whether there's a position to go back to.
+    pub fn can_go_back(&self) -> bool {
+        self.position > 0
+    }
+
+    /// Whether there's a position to go forward to.
+    pub fn can_go_forward(&self) -> bool {
+        self.position + 1 < self.entries.len()
+    }
+
+    /// Whether no jumps were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Records a jump from `from` to `to`. Positions that were gone back from are dropped.
+    fn record(&mut self, from: u64, to: u64) {
+        if self.entries.is_empty() {
+            self.entries.push(from);
+        } else {
+            self.entries.truncate(self.position + 1);
+            // The cursor may have moved a little since it arrived at the current position.
+            self.entries[self.position] = from;
+        }
+
+        self.entries.push(to);
+
+        if self.entries.len() > MAX_CURSOR_HISTORY {
+            self.entries.remove(0);
+        }
+
+        self.position = self.entries.len() - 1;
+    }
+
+    /// Goes back from the `cursor`, and returns the position to move it to.
+    fn back(&mut self, cursor: u64) -> Option<u64> {
+        if !self.can_go_back() {
+            return None;
+        }
+
+        self.entries[self.position] = cursor;
+        self.position -= 1;
+
+        Some(self.entries[self.position])
+    }
+
+    /// Goes forward from the `cursor`, and returns the position to move it to.
+    fn forward(&mut self, cursor: u64) -> Option<u64> {
+        if !self.can_go_forward() {
+            return None;
+        }
+
+        self.entries[self.position] = cursor;
+        self.position += 1;
+
+        Some(self.entries[self.position])
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.position = 0;
+    }
+}
+
+/// The number of positions a [`CursorHistory`] keeps.
+const MAX_CURSOR_HISTORY: usize = 100;
+
+/// The number of notices that can wait while another one is displayed, see [`show_notice`].
+const MAX_QUEUED_NOTICES: usize = 8;
+/// The space in pixels between the text of a notice and its edges.
+const NOTICE_PADDING: Padding = Padding { top: 4.0, right: 10.0, bottom: 4.0, left: 10.0 };
+/// The space in pixels between a notice and the bottom of the data area.
+const NOTICE_MARGIN: f32 = 12.0;
+
+/// Displayed instead of every digit of a byte that couldn't be read.
+const UNREADABLE_BYTE: &str = "?";
+/// Displayed instead of a char that couldn't be read.
+const UNREADABLE_CHAR: &str = "?";
+/// Displayed instead of every digit of a byte that the [`Source`] will provide later.
+const PENDING_BYTE: &str = "\u{b7}";
+/// Displayed instead of a char that the [`Source`] will provide later.
+const PENDING_CHAR: &str = "\u{b7}";
+
+/// The text of the bytes after the first one of a multi-byte char, see [`Encoding::Utf8`].
+const CONTINUATION: char = '\u{b7}';
+
+/// The number of multi-byte chars whose paragraphs are cached before the cache starts over.
+const MAX_CACHED_GLYPHS: usize = 4096;
+
+/// The longest sequence of bytes that a char can be encoded in.
+const MAX_SEQUENCE_LENGTH: i64 = 4;
+
+/// Displayed in front of addresses that are truncated to fit the address area.
+const ELLIPSIS: &str = "\u{2026}";
+
+/// The space in pixels between the lines of a [`Placeholder`].
+const PLACEHOLDER_SPACING: f32 = 8.0;
+/// The space in pixels between the drop target highlight and the edges of the data area.
+const DROP_TARGET_INSET: f32 = 8.0;
+/// The thickness in pixels of the lines of [`Decorations`].
+const DECORATION_THICKNESS: f32 = 1.0;
+
+/// What a [`HexViewer`] that follows the tail last saw of the [`Content`].
+#[derive(Debug, Clone, Copy)]
+struct Tail {
+    content_id: u64,
+    size: i64,
+    /// Whether the last row was shown.
+    at_end: bool,
+}
+
+/// The settings of a [`HexViewer`] that the texts of the [`TextCache`] depend on.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct TextSettings {
+    font: Option<Font>,
+    font_size: Option<Pixels>,
+    byte_display: ByteDisplay,
+    lowercase: bool,
+    encoding: Encoding,
+    control_pictures: bool,
+    non_printable_char: char,
+}
+
+/// The Unicode control picture of the ASCII control char `byte`, such as `␀` for NUL.
+fn control_picture(byte: u8) -> char {
+    match byte {
+        0x7F => '\u{2421}',
+        byte => char::from_u32(0x2400 + u32::from(byte)).unwrap_or('.'),
+    }
+}
+
+/// Caches the byte and char texts.
+#[derive(Default)]
+struct TextCache<R: Renderer>
+where
+    R: text::Renderer<Font = Font> + 'static,
+{
+    settings: TextSettings,
+    uninitialized: bool,
+    byte_paragraphs: Vec<text::paragraph::Plain<R::Paragraph>>,
+    char_paragraphs: Vec<text::paragraph::Plain<R::Paragraph>>,
+    unreadable_byte: text::paragraph::Plain<R::Paragraph>,
+    unreadable_char: text::paragraph::Plain<R::Paragraph>,
+    pending_byte: text::paragraph::Plain<R::Paragraph>,
+    pending_char: text::paragraph::Plain<R::Paragraph>,
+    ellipsis: text::paragraph::Plain<R::Paragraph>,
+    /// The [`CharMap`] of the mapped paragraphs.
+    char_map: Option<CharMap>,
+    /// The char area's paragraphs of the [`CharMap`], empty if there's none.
+    mapped_paragraphs: Vec<text::paragraph::Plain<R::Paragraph>>,
+    /// The paragraphs of the multi-byte chars that were drawn, created while drawing.
+    glyphs: RefCell<HashMap<char, text::paragraph::Plain<R::Paragraph>>>,
+    /// The icon, message and drop hint of the [`Placeholder`], empty if they aren't displayed.
+    placeholder: [text::paragraph::Plain<R::Paragraph>; 3],
+}
+
+impl<R: Renderer> TextCache<R>
+where
+    R: text::Renderer<Font = Font>,
+    R::Paragraph: Clone + Default,
+{
+    fn new() -> Self {
+        Self {
+            settings: TextSettings::default(),
+            uninitialized: true,
+            byte_paragraphs: vec![Default::default(); 256],
+            char_paragraphs: vec![Default::default(); 256],
+            unreadable_byte: Default::default(),
+            unreadable_char: Default::default(),
+            pending_byte: Default::default(),
+            pending_char: Default::default(),
+            ellipsis: Default::default(),
+            char_map: None,
+            mapped_paragraphs: Vec::new(),
+            glyphs: Default::default(),
+            placeholder: Default::default(),
+        }
+    }
+
+    fn set(&mut self, settings: TextSettings, renderer: &R) {
+        // self.uninitialize is necessary because if we're given only None's then no initialization
+        // will ever happen.
+        if self.uninitialized || self.settings != settings {
+            self.settings = settings;
+
+            let TextSettings {
+                byte_display, lowercase, encoding, control_pictures, non_printable_char, ..
+            } = settings;
+            let font = settings.font.unwrap_or(Font::MONOSPACE);
+            let font_size = settings.font_size.unwrap_or_else(|| renderer.default_size());
+
+            for (byte, paragraph) in self.byte_paragraphs.iter_mut().enumerate() {
+                let mut byte_string = byte_display.format(byte as u8);
+
+                if lowercase {
+                    byte_string.make_ascii_lowercase();
+                }
+
+                let text = Self::create_text(byte_string, &font, font_size);
+                paragraph.update(text.as_ref());
+            }
+
+            for (byte, paragraph) in self.char_paragraphs.iter_mut().enumerate() {
+                let char = match byte as u8 {
+                    byte if control_pictures && byte.is_ascii_control() => control_picture(byte),
+                    byte => encoding.decode(byte).unwrap_or(non_printable_char),
+                };
+                let text = Self::create_text(char.to_string(), &font, font_size);
+                paragraph.update(text.as_ref());
+            }
+
+            // The placeholders of bytes have a symbol per digit.
+            let digits = byte_display.digits();
+
+            let text = Self::create_text(UNREADABLE_BYTE.repeat(digits), &font, font_size);
+            self.unreadable_byte.update(text.as_ref());
+
+            let text = Self::create_text(UNREADABLE_CHAR.to_string(), &font, font_size);
+            self.unreadable_char.update(text.as_ref());
+
+            let text = Self::create_text(PENDING_BYTE.repeat(digits), &font, font_size);
+            self.pending_byte.update(text.as_ref());
+
+            let text = Self::create_text(PENDING_CHAR.to_string(), &font, font_size);
+            self.pending_char.update(text.as_ref());
+
+            self.glyphs.get_mut().clear();
+
+            // Have the mapped paragraphs recreated in the new font.
+            self.char_map = None;
+            self.mapped_paragraphs.clear();
+
+            let text = Self::create_text(ELLIPSIS.to_string(), &font, font_size);
+            self.ellipsis.update(text.as_ref());
+
+            self.uninitialized = false;
+        }
+    }
+
+    /// Gets the paragraph of a non-ASCII char of a multi-byte [`Encoding`], which is created if it
+    /// isn't cached yet. `default_size` is used if no font size is set.
+    fn glyph(
+        &self, char: char, default_size: Pixels,
+    ) -> Ref<'_, text::paragraph::Plain<R::Paragraph>> {
+        if !self.glyphs.borrow().contains_key(&char) {
+            let mut glyphs = self.glyphs.borrow_mut();
+
+            if glyphs.len() >= MAX_CACHED_GLYPHS {
+                glyphs.clear();
+            }
+
+            let font = self.settings.font.unwrap_or(Font::MONOSPACE);
+            let font_size = self.settings.font_size.unwrap_or(default_size);
+            let text = Self::create_text(char.to_string(), &font, font_size);
+
+            glyphs.insert(char, text::paragraph::Plain::new(text));
+        }
+
+        Ref::map(self.glyphs.borrow(), |glyphs| &glyphs[&char])
+    }
+
+    /// Gets the cached paragraph for a byte value, ready for drawing.
+    fn byte(&self, byte: u8) -> &text::paragraph::Plain<R::Paragraph> {
+        &self.byte_paragraphs[byte as usize]
+    }
+
+    /// Gets the cached placeholder paragraph for a byte that isn't available, ready for drawing.
+    fn byte_placeholder(&self, availability: Availability) -> &text::paragraph::Plain<R::Paragraph> {
+        match availability {
+            Availability::Pending => &self.pending_byte,
+            _ => &self.unreadable_byte,
+        }
+    }
+
+    /// Gets the cached placeholder paragraph for a char that isn't available, ready for drawing.
+    fn char_placeholder(&self, availability: Availability) -> &text::paragraph::Plain<R::Paragraph> {
+        match availability {
+            Availability::Pending => &self.pending_char,
+            _ => &self.unreadable_char,
+        }
+    }
+
+    /// Gets the cached paragraph for the ellipsis of truncated addresses, ready for drawing.
+    fn ellipsis(&self) -> &text::paragraph::Plain<R::Paragraph> {
+        &self.ellipsis
+    }
+
+    /// Updates the paragraphs of the [`Placeholder`]. Unlike the data, it's displayed in the
+    /// default font of the renderer.
+    fn set_placeholder(
+        &mut self,
+        placeholder: &Placeholder,
+        show_drop_hint: bool,
+        font_size: Option<Pixels>,
+        renderer: &R,
+    ) {
+        let size = font_size.unwrap_or_else(|| renderer.default_size());
+        let drop_hint = placeholder.drop_hint.as_deref().filter(|_| show_drop_hint);
+
+        let lines = [
+            (placeholder.icon.as_deref(), size * 2.0),
+            (Some(placeholder.message.as_str()), size),
+            (drop_hint, size * 0.85),
+        ];
+
+        for (paragraph, (content, size)) in self.placeholder.iter_mut().zip(lines) {
+            paragraph.update(Text {
+                content: content.unwrap_or_default(),
+                bounds: Size::INFINITE,
+                size,
+                line_height: text::LineHeight::default(),
+                font: renderer.default_font(),
+                align_x: text::Alignment::Left,
+                align_y: alignment::Vertical::Center,
+                shaping: text::Shaping::Advanced,
+                wrapping: Wrapping::None,
+            });
+        }
+    }
+
+    /// Sets the [`CharMap`] of the char area. The paragraphs are created in the font given to
+    /// [`TextCache::set`], so this is called after it.
+    fn set_char_map(&mut self, char_map: Option<&CharMap>, renderer: &R) {
+        if self.char_map.as_ref() == char_map {
+            return;
+        }
+
+        self.char_map = char_map.cloned();
+        self.mapped_paragraphs.clear();
+
+        if let Some(char_map) = char_map {
+            let font = self.settings.font.unwrap_or(Font::MONOSPACE);
+            let font_size = self.settings.font_size.unwrap_or_else(|| renderer.default_size());
+
+            self.mapped_paragraphs = (0..=u8::MAX)
+                .map(|byte| {
+                    let text = Self::create_text(char_map.get(byte).to_owned(), &font, font_size);
+                    text::paragraph::Plain::new(text)
+                })
+                .collect();
+        }
+    }
+
+    /// Gets the cached paragraphs of the [`Placeholder`] that are displayed, ready for drawing.
+    fn placeholder_lines(&self) -> impl Iterator<Item = &text::paragraph::Plain<R::Paragraph>> {
+        self.placeholder.iter().filter(|paragraph| !paragraph.content().is_empty())
+    }
+
+    /// Gets the cached paragraph for a char value in the current encoding, ready for drawing.
+    fn char(&self, byte: u8) -> &text::paragraph::Plain<R::Paragraph> {
+        &self.char_paragraphs[byte as usize]
+    }
+
+    /// Gets the cached paragraph for a byte in the char area, from the [`CharMap`] if there is one,
+    /// ready for drawing.
+    fn mapped_char(&self, byte: u8) -> &text::paragraph::Plain<R::Paragraph> {
+        self.mapped_paragraphs.get(byte as usize).unwrap_or_else(|| self.char(byte))
+    }
+
+    /// Gets the cached paragraph for a hex digit value (0-F), in the configured case, ready for
+    /// drawing.
+    fn hex_digit(&self, hex_digit: u8) -> &text::paragraph::Plain<R::Paragraph> {
+        if hex_digit <= 9 {
+            &self.char_paragraphs[(hex_digit + 0x30) as usize]
+        } else if (0xA..0x10).contains(&hex_digit) {
+            let letter = if self.settings.lowercase { b'a' } else { b'A' };
+            &self.char_paragraphs[(hex_digit - 0xA + letter) as usize]
+        } else {
+            panic!("hex digit out of range");
+        }
+    }
+
+    /// The width of rendered bytes (e.g. "00") and rendered characters (e.g. "0"), and their height
+    fn metrics(&self) -> HexMetrics {
+        let byte_size = self.byte_paragraphs[0].min_bounds();
+        let char_size = self.char_paragraphs[0].min_bounds();
+
+        HexMetrics::new(
+            byte_size.width,
+            char_size.width,
+            char_size.height,
+        )
+    }
+
+    fn create_text(text: String, font: &Font, font_size: Pixels) -> Text {
+        Text {
+            content: text,
+            bounds: Size::INFINITE,
+            size: font_size,
+            line_height: text::LineHeight::Relative(1.0),
+            font: *font,
+            align_x: text::Alignment::Left,
+            align_y: alignment::Vertical::Center,
+            shaping: text::Shaping::Basic, // Barely makes a difference with Basic in terms of processing performance
+            wrapping: Wrapping::None,
+        }
+    }
+}
+
+/// The amount of space the text of a byte cell and a char cell occupies, excluding padding. The
+/// [`HexViewer`] publishes the metrics it measured as the [`LayoutSnapshot::metrics`], and they can
+/// be measured with [`HexViewer::cell_metrics`] given a renderer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CellMetrics {
+    /// The width of the digits of a byte, two in hex, see [`ByteDisplay`].
+    pub byte_width: f32,
+    /// The width of a char.
+    pub char_width: f32,
+    /// The height of the text.
+    pub height: f32,
+}
+
+impl From<HexMetrics> for CellMetrics {
+    fn from(metrics: HexMetrics) -> Self {
+        Self {
+            byte_width: metrics.byte_width,
+            char_width: metrics.char_width,
+            height: metrics.height,
+        }
+    }
+}
+
+impl From<CellMetrics> for HexMetrics {
+    fn from(metrics: CellMetrics) -> Self {
+        HexMetrics::new(metrics.byte_width, metrics.char_width, metrics.height)
+    }
+}
+
+/// The amount of space the byte and char paragraphs occupy.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct HexMetrics {
+    byte_width: f32,
+    char_width: f32,
+    height: f32,
+}
+
+impl HexMetrics {
+    fn new(byte_width: f32, char_width: f32, height: f32) -> Self {
+        HexMetrics {
+            byte_width,
+            char_width,
+            height,
+        }
+    }
+}
+
+/// Where a row is placed in the viewport when scrolling to it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Alignment {
+    /// At the top.
+    Start,
+    /// In the middle.
+    Center,
+    /// At the bottom.
+    End,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Scroll {
+    Lazy(LazyAlignment),
+    Aligned(Alignment),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum LazyAlignment {
+    Start,
+    End,
+}
+
+/// How movement of the cursor should affect the viewport.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Navigation {
+    // TODO: maybe add an `Ignore` variant that makes the viewport ignore cursor movement.
+    /// The viewport should move as little as possible, as long as it contains the new cursor
+    /// position. That means that if the new cursor position is visible in the current viewport, the
+    /// viewport remains the same. If the new cursor position is not currently visible, the viewport
+    /// moves as little as needed to bring it into view.
+    Lazy,
+    /// The cursor should always be aligned to a certain place in the viewport, denoted by the
+    /// [`Alignment`].
+    Aligned(Alignment),
+}
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+struct ScrollOffset {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl ScrollOffset {
+    pub fn new(x: i64, y: i64) -> Self {
+        ScrollOffset { x, y }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// The first column in our viewport. In case of Step::Pixel this column might be only partially
+    /// visible.
+    x: i64,
+    /// The first row in our viewport. Always visible as long as the viewport is high enough to fit
+    /// at least one row, since vertical scrolling always happens per row.
+    y: i64,
+    /// The number of columns (partially) visible.
+    columns: i64,
+    /// The number of rows (partially) visible.
+    rows: i64,
+    /// Percentage of a cell we're scrolled beyond our x. Always 0 in case of Step::Cell.
+    percentage_x: f32,
+    virtual_columns: i64,
+    /// The first column in the char area, with [`HorizontalScrollStrategy::Split`].
+    char_x: i64,
+    /// The number of columns (partially) visible in the char area, with
+    /// [`HorizontalScrollStrategy::Split`]. 0 if the char area shows the same columns as the byte
+    /// area.
+    char_columns: i64,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            x: 0,
+            y: 0,
+            columns: 0,
+            rows: 0,
+            percentage_x: 0.0,
+            virtual_columns: 0,
+            char_x: 0,
+            char_columns: 0,
+        }
+    }
+}
+
+impl Viewport {
+    /// The viewport a new [`Content`] reads, before it's given one by a [`HexViewer`].
+    fn initial(virtual_columns: i64) -> Self {
+        Viewport {
+            columns: virtual_columns,
+            rows: INITIAL_ROWS,
+            virtual_columns,
+            ..Viewport::default()
+        }
+    }
+
+    /// The first column that is visible in the viewport.
+    pub fn x(&self) -> u64 {
+        self.x as u64
+    }
+
+    /// The first row that is visible in the viewport.
+    pub fn y(&self) -> u64 {
+        self.y as u64
+    }
+
+    /// The number of columns that would (partially) fit in the viewport.
+    pub fn columns(&self) -> u64 {
+        self.columns as u64
+    }
+
+    /// The number of rows that would (partially) fit in the viewport.
+    pub fn rows(&self) -> u64 {
+        self.rows as u64
+    }
+
+    /// The absolute offset of the byte in the top left corner of the viewport.
+    pub fn offset(&self) -> u64 {
+        (self.virtual_columns * self.y + self.x) as u64
+    }
+
+    /// Total number of bytes that would (partially) fit in the viewport.
+    pub fn size(&self) -> usize {
+        (self.columns * self.rows) as usize
+    }
+
+    /// The first column and the number of columns of `buffer`.
+    fn window(&self, buffer: Buffer) -> (i64, i64) {
+        match buffer {
+            Buffer::Bytes => (self.x, self.columns),
+            Buffer::Chars => (self.char_x, self.char_columns),
+        }
+    }
+
+    /// The first column that is visible in the char area. Differs from [`Viewport::x`] only with
+    /// [`HorizontalScrollStrategy::Split`].
+    pub fn char_x(&self) -> u64 {
+        self.char_window().0 as u64
+    }
+
+    /// The number of columns that would (partially) fit in the char area. Differs from
+    /// [`Viewport::columns`] only with [`HorizontalScrollStrategy::Split`].
+    pub fn char_columns(&self) -> u64 {
+        self.char_window().1 as u64
+    }
+
+    /// Whether the char area shows other columns than the byte area.
+    fn is_split(&self) -> bool {
+        self.char_columns > 0
+    }
+
+    /// The first column and number of columns of the char area.
+    fn char_window(&self) -> (i64, i64) {
+        if self.is_split() {
+            (self.char_x, self.char_columns)
+        } else {
+            (self.x, self.columns)
+        }
+    }
+
+    /// Iterator that yields the absolute start and end (not inclusive) offsets of each row.
+    /// With x=2, y=1, columns=8 and virtual_columns = 16, this would yield:
+    ///   [18, 26),
+    ///   [34, 42).
+    ///   ...
+    pub fn iter_rows(&self) -> impl Iterator<Item = Range<u64>> {
+        (0..self.rows).into_iter()
+            .map(|row| {
+                let start = (self.y + row) * self.virtual_columns + self.x;
+                let end = start + self.columns;
+                Range {start: start as u64, end: end as u64}
+            })
+    }
+
+    /// Determines if, and if so, at which column and row in the viewport, the absolute `offset`
+    /// into the source is visible.
+    pub fn contains(&self, offset: u64) -> Option<(u64, u64)> {
+        self.contains_in_columns(offset, self.x, self.columns)
+    }
+
+    /// Like [`Viewport::contains`], but for the columns shown in the char area.
+    pub fn char_contains(&self, offset: u64) -> Option<(u64, u64)> {
+        let (x, columns) = self.char_window();
+        self.contains_in_columns(offset, x, columns)
+    }
+
+    fn contains_in_columns(&self, offset: u64, x: i64, columns: i64) -> Option<(u64, u64)> {
+        let col = offset as i64 % self.virtual_columns;
+        let row = offset as i64 / self.virtual_columns;
+
+        if col < x || col >= x + columns
+            || row < self.y || row >= self.y + self.rows
+        {
+            None
+        } else {
+            Some(
+                (
+                     (col - x) as u64,
+                     (row - self.y) as u64
+                )
+            )
+        }
+    }
+}
+
+/// Contains all paddings for the [`HexViewer`] relative to the font size.
+///
+/// All constructors and setters are `const`, so settings can be stored in a `const`. The named
+/// presets are listed in [`PaddingSettings::PRESETS`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PaddingSettings {
+    /// Padding above the text in the byte area header and char area header.
+    pub header_top: f32,
+    /// Padding below the text in the byte area header and char area header.
+    pub header_bottom: f32,
+    /// Padding above the data cells in the byte area and char area.
+    pub content_top: f32,
+    /// Padding below the data cells in the byte area and char area.
+    pub content_bottom: f32,
+    /// Padding left of the cells in the address area.
+    pub address_area_left: f32,
+    /// Padding right of the cells in the address area.
+    pub address_area_right: f32,
+    /// Padding left of the cells in the byte area.
+    pub byte_area_left: f32,
+    /// Padding right of the cells in the byte area.
+    pub byte_area_right: f32,
+    /// Padding left of the cells in the char area.
+    pub char_area_left: f32,
+    /// Padding right of the cells in the char area.
+    pub char_area_right: f32,
+    /// Padding above and below the byte/char cell text.
+    pub data_cell_vertical: f32,
+    /// Padding left of the byte/char cell text.
+    pub byte_cell_horizontal: f32,
+    /// Padding right of the byte/char cell text.
+    pub char_cell_horizontal: f32,
+}
+
+impl Default for PaddingSettings {
+    fn default() -> Self {
+        Self::compact()
+    }
+}
+
+impl PaddingSettings {
+    /// The named presets, in order of increasing whitespace. Useful for listing them in a settings
+    /// UI.
+    pub const PRESETS: [(&'static str, PaddingSettings); 5] = [
+        ("Dense", Self::dense()),
+        ("Terminal", Self::terminal()),
+        ("Compact", Self::compact()),
+        ("Spacious", Self::spacious()),
+        ("Touch", Self::touch()),
+    ];
+
+    /// The range every padding must be in. Values outside of it produce broken layouts, for example
+    /// negative paddings make cells overlap.
+    pub const VALID_RANGE: RangeInclusive<f32> = 0.0..=4.0;
+
+    /// The least whitespace that still keeps the cells apart. Fits the most data on screen.
+    pub const fn dense() -> Self {
+        Self {
+            header_top: 0.15,
+            header_bottom: 0.15,
+            content_top: 0.15,
+            content_bottom: 0.15,
+            address_area_left: 0.3,
+            address_area_right: 0.3,
+            byte_area_left: 0.15,
+            byte_area_right: 0.15,
+            char_area_left: 0.15,
+            char_area_right: 0.3,
+            data_cell_vertical: 0.05,
+            byte_cell_horizontal: 0.2,
+            char_cell_horizontal: 0.0,
+        }
+    }
+
+    /// Mimics the output of command line hex dump tools: no vertical space between the rows and
+    /// no space between the chars.
+    pub const fn terminal() -> Self {
+        Self {
+            header_top: 0.0,
+            header_bottom: 0.0,
+            content_top: 0.0,
+            content_bottom: 0.0,
+            address_area_left: 0.5,
+            address_area_right: 0.5,
+            byte_area_left: 0.25,
+            byte_area_right: 0.25,
+            char_area_left: 0.5,
+            char_area_right: 0.5,
+            data_cell_vertical: 0.0,
+            byte_cell_horizontal: 0.25,
+            char_cell_horizontal: 0.0,
+        }
+    }
+
+    /// The default preset.
+    pub const fn compact() -> Self {
+        Self {
+            header_top: 0.3,
+            header_bottom: 0.3,
+            // To match visual horizontal whitespace of 0.6 these should be 0.4, but 0.4 doesn't
+            // look good. Maybe it has to do with the ascent/descent.
+            content_top: 0.3,
+            content_bottom: 0.3,
+            address_area_left: 0.6,
+            address_area_right: 0.6,
+            byte_area_left: 0.3,
+            byte_area_right: 0.3,
+            char_area_left: 0.25,
+            char_area_right: 0.55,
+            data_cell_vertical: 0.2,
+            byte_cell_horizontal: 0.3,
+            char_cell_horizontal: 0.05,
+        }
+    }
+
+    /// More whitespace than [`PaddingSettings::compact`], for a calmer look.
+    pub const fn spacious() -> Self {
+        Self {
+            data_cell_vertical: 0.3,
+            byte_cell_horizontal: 0.4,
+            char_cell_horizontal: 0.1,
+            address_area_left: 0.8,
+            address_area_right: 0.8,
+            byte_area_left: 0.4,
+            byte_area_right: 0.4,
+            char_area_left: 0.3,
+            char_area_right: 0.7,
+            header_top: 0.6,
+            header_bottom: 0.6,
+            // To match visual horizontal whitespace of 0.8 these should be 0.6, but 0.6 doesn't
+            // look good. Maybe it has to do with the line height.
+            content_top: 0.4,
+            content_bottom: 0.4,
+        }
+    }
+
+    /// Rows tall enough to be tapped reliably on touch screens.
+    pub const fn touch() -> Self {
+        Self {
+            header_top: 0.8,
+            header_bottom: 0.8,
+            content_top: 0.6,
+            content_bottom: 0.6,
+            address_area_left: 1.0,
+            address_area_right: 1.0,
+            byte_area_left: 0.5,
+            byte_area_right: 0.5,
+            char_area_left: 0.4,
+            char_area_right: 0.8,
+            data_cell_vertical: 0.6,
+            byte_cell_horizontal: 0.5,
+            char_cell_horizontal: 0.2,
+        }
+    }
+
+    /// Returns the settings with all values clamped to [`PaddingSettings::VALID_RANGE`]. `NaN`
+    /// becomes the lower bound. The [`HexViewer`] does this to the settings it receives.
+    pub fn clamped(mut self) -> Self {
+        for (_, value) in self.values_mut() {
+            *value = if value.is_nan() {
+                *Self::VALID_RANGE.start()
+            } else {
+                value.clamp(*Self::VALID_RANGE.start(), *Self::VALID_RANGE.end())
+            };
+        }
+        self
+    }
+
+    /// Returns the names of the fields that are outside of [`PaddingSettings::VALID_RANGE`], and
+    /// would be changed by [`PaddingSettings::clamped`]. Settings UIs can use this to warn about
+    /// them.
+    pub fn out_of_range(&self) -> Vec<&'static str> {
+        let mut copy = *self;
+        copy.values_mut()
+            .into_iter()
+            .filter(|(_, value)| !Self::VALID_RANGE.contains(*value))
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    fn values_mut(&mut self) -> [(&'static str, &mut f32); 13] {
+        [
+            ("header_top", &mut self.header_top),
+            ("header_bottom", &mut self.header_bottom),
+            ("content_top", &mut self.content_top),
+            ("content_bottom", &mut self.content_bottom),
+            ("address_area_left", &mut self.address_area_left),
+            ("address_area_right", &mut self.address_area_right),
+            ("byte_area_left", &mut self.byte_area_left),
+            ("byte_area_right", &mut self.byte_area_right),
+            ("char_area_left", &mut self.char_area_left),
+            ("char_area_right", &mut self.char_area_right),
+            ("data_cell_vertical", &mut self.data_cell_vertical),
+            ("byte_cell_horizontal", &mut self.byte_cell_horizontal),
+            ("char_cell_horizontal", &mut self.char_cell_horizontal),
+        ]
+    }
+
+    pub const fn header_top(mut self, value: f32) -> Self {
+        self.header_top = value;
+        self
+    }
+
+    pub const fn header_bottom(mut self, value: f32) -> Self {
+        self.header_bottom = value;
+        self
+    }
+
+    pub const fn content_top(mut self, value: f32) -> Self {
+        self.content_top = value;
+        self
+    }
+
+    pub const fn content_bottom(mut self, value: f32) -> Self {
+        self.content_bottom = value;
+        self
+    }
+
+    pub const fn address_area_left(mut self, value: f32) -> Self {
+        self.address_area_left = value;
+        self
+    }
+
+    pub const fn address_area_right(mut self, value: f32) -> Self {
+        self.address_area_right = value;
+        self
+    }
+
+    pub const fn byte_area_left(mut self, value: f32) -> Self {
+        self.byte_area_left = value;
+        self
+    }
+
+    pub const fn byte_area_right(mut self, value: f32) -> Self {
+        self.byte_area_right = value;
+        self
+    }
+
+    pub const fn char_area_left(mut self, value: f32) -> Self {
+        self.char_area_left = value;
+        self
+    }
+
+    pub const fn char_area_right(mut self, value: f32) -> Self {
+        self.char_area_right = value;
+        self
+    }
+
+    pub const fn data_vertical(mut self, value: f32) -> Self {
+        self.data_cell_vertical = value;
+        self
+    }
+
+    pub const fn byte_horizontal(mut self, value: f32) -> Self {
+        self.byte_cell_horizontal = value;
+        self
+    }
+
+    pub const fn char_horizontal(mut self, value: f32) -> Self {
+        self.char_cell_horizontal = value;
+        self
+    }
+}
+
+/// Contains all paddings for the [`HexViewer`] in pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct HexPadding {
+    header_top: f32,
+    header_bottom: f32,
+    content_top: f32,
+    content_bottom: f32,
+    address_area_left: f32,
+    address_area_right: f32,
+    byte_area_left: f32,
+    byte_area_right: f32,
+    char_area_left: f32,
+    char_area_right: f32,
+    data_vertical: f32,
+    byte_horizontal: f32,
+    char_horizontal: f32,
+    /// The number of text rows in the headers. Not a padding, but it determines the space around
+    /// the data just the same.
+    header_rows: f32,
+    /// The number of digits in the address area. Like `header_rows`, not a padding.
+    address_digits: f32,
+    /// The width of the [`HexViewer::row_value`] column, which is part of the char area's right
+    /// padding so it doesn't scroll horizontally. Zero if there's no such column.
+    row_value_width: f32,
+}
+
+impl HexPadding {
+    fn new(
+        settings: &PaddingSettings,
+        metrics: HexMetrics,
+        header_rows: i64,
+        address_digits: usize,
+        row_value_chars: usize,
+    ) -> Self {
+        let abs = |
+            value: f32
+        | {
+            (value * metrics.height).round()  // without rounding to full pixels text doesn't always look good.
+        };
+
+        // The column is followed by the char area's right padding, like the chars.
+        let row_value_width = if row_value_chars > 0 {
+            row_value_chars as f32 * metrics.char_width + abs(settings.char_area_right)
+        } else {
+            0.0
+        };
+
+        Self {
+            header_top: abs(settings.header_top),
+            header_bottom: abs(settings.header_bottom),
+            content_top: abs(settings.content_top),
+            content_bottom: abs(settings.content_bottom),
+            address_area_left: abs(settings.address_area_left),
+            address_area_right: abs(settings.address_area_right),
+            byte_area_left: abs(settings.byte_area_left),
+            byte_area_right: abs(settings.byte_area_right),
+            char_area_left: abs(settings.char_area_left),
+            char_area_right: abs(settings.char_area_right),
+            data_vertical: abs(settings.data_cell_vertical),
+            byte_horizontal: abs(settings.byte_cell_horizontal),
+            char_horizontal: abs(settings.char_cell_horizontal),
+            header_rows: header_rows as f32,
+            address_digits: address_digits as f32,
+            row_value_width,
+        }
+    }
+
+    pub fn address_area_padding(&self) -> Padding {
+        Padding::default()
+            .top(self.content_top)
+            .bottom(self.content_bottom)
+            .left(self.address_area_left)
+            .right(self.address_area_right)
+    }
+
+    pub fn byte_area_padding(&self) -> Padding {
+        Padding::default()
+            .top(self.content_top)
+            .bottom(self.content_bottom)
+            .left(self.byte_area_left)
+            .right(self.byte_area_right)
+    }
+
+    pub fn char_area_padding(&self) -> Padding {
+        Padding::default()
+            .top(self.content_top)
+            .bottom(self.content_bottom)
+            .left(self.char_area_left)
+            .right(self.char_area_right + self.row_value_width)
+    }
+}
+
+/// How the byte area and char area scroll horizontally when not all columns fit.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum HorizontalScrollStrategy {
+    /// The char area shows the same columns as the byte area.
+    #[default]
+    Normal,
+    /// The char area scrolls on its own, per column, with the mouse wheel while the pointer is over
+    /// it. The scrollbar and keyboard navigation move the byte area, and the char area follows to
+    /// keep the cursor in view. The columns shown in the char area are described by
+    /// [`Viewport::char_x`] and [`Viewport::char_columns`].
+    Split
+}
+
+/// A read-only snapshot of the layout of a [`HexViewer`], for drawing companions such as
+/// connection lines between a structure tree and its bytes. It's published with
+/// [`HexViewer::on_layout`] whenever it changes, and can be retrieved with the [`layout_snapshot`]
+/// operation. All positions are absolute.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutSnapshot {
+    /// The bounds of the whole widget.
+    pub bounds: Rectangle,
+    /// The area that holds the addresses.
+    pub address_area: Rectangle,
+    /// The header above the byte area.
+    pub byte_header: Rectangle,
+    /// The header above the char area.
+    pub char_header: Rectangle,
+    /// The area that holds the bytes.
+    pub byte_area: Rectangle,
+    /// The area that holds the chars.
+    pub char_area: Rectangle,
+    /// The height of each row.
+    pub row_height: f32,
+    /// The size of the text in the cells.
+    pub metrics: CellMetrics,
+    /// The viewport the layout applies to.
+    pub viewport: Viewport,
+    /// The positions of the individual cells.
+    pub cells: CellLayout,
+}
+
+/// The positions of the cells of a [`HexViewer`] with given bounds, for hit-testing and placing
+/// overlays. Created with [`HexViewer::layout_for`]. All positions are absolute, like the bounds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellLayout {
+    layout: Layout,
+    viewport: Viewport,
+    source_size: i64,
 }
 
-impl HexMetrics {
-    fn new(byte_width: f32, char_width: f32, height: f32) -> Self {
-        HexMetrics {
-            byte_width,
-            char_width,
-            height,
+impl CellLayout {
+    /// The absolute offset of the byte whose byte cell or char cell contains the `point`, if any.
+    pub fn offset_at(&self, point: Point) -> Option<u64> {
+        let (cell, (x, columns)) = match self.layout.pointer_location(point) {
+            Location::ByteArea(DataLocation::Cell(cell)) => {
+                (cell, (self.viewport.x, self.viewport.columns))
+            }
+            Location::CharArea(DataLocation::Cell(cell)) => (cell, self.viewport.char_window()),
+            _ => return None,
+        };
+
+        let column = x + cell.col;
+        let row = self.viewport.y + cell.row;
+        let offset = row * self.viewport.virtual_columns + column;
+
+        (cell.col >= 0 && cell.row >= 0 && cell.col < columns
+            && cell.row < self.viewport.rows && offset < self.source_size)
+            .then_some(offset as u64)
+    }
+
+    /// The bounds of the byte cell of the absolute `offset`, if it's in the viewport. The cell may
+    /// be partially hidden at the edges of the byte area.
+    pub fn byte_cell(&self, offset: u64) -> Option<Rectangle> {
+        let (column, row) = self.position(offset)?;
+        Some(self.layout.byte_cell(column, row))
+    }
+
+    /// The bounds of the char cell of the absolute `offset`, if it's in the viewport. The cell may
+    /// be partially hidden at the edges of the char area.
+    pub fn char_cell(&self, offset: u64) -> Option<Rectangle> {
+        if self.viewport.virtual_columns == 0 || offset as i64 >= self.source_size {
+            return None;
+        }
+
+        let (column, row) = self.viewport.char_contains(offset)?;
+        Some(self.layout.char_cell(column as i64, row as i64))
+    }
+
+    /// The height of each row.
+    pub fn row_height(&self) -> f32 {
+        self.layout.row_height()
+    }
+
+    fn position(&self, offset: u64) -> Option<(i64, i64)> {
+        if self.viewport.virtual_columns == 0 || offset as i64 >= self.source_size {
+            return None;
+        }
+
+        self.viewport.contains(offset).map(|(column, row)| (column as i64, row as i64))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Layout {
+    dim: LayoutDimensions,
+    padding: HexPadding,
+    source_size: i64,
+    virtual_columns: i64,
+    metrics: HexMetrics,
+    byte_cell_width: f32,
+    char_cell_width: f32,
+    byte_shift: f32,
+    char_shift: f32,
+    top_left: Rectangle,
+    byte_area_header: Rectangle,
+    char_area_header: Rectangle,
+    top_right: Rectangle,
+    address_area: Rectangle,
+    byte_area: Rectangle,
+    char_area: Rectangle,
+    /// The space right of the char area that the areas don't need, if the [`HexViewer`] is wider
+    /// than its columns.
+    trailing_width: f32,
+}
+
+impl Layout {
+    fn new(
+        dim: LayoutDimensions,
+        padding: HexPadding,
+        source_size: i64,
+        virtual_columns: i64,
+        metrics: HexMetrics,
+        percentage_x: f32,
+        bounds: Rectangle,
+    ) -> Self {
+        let header_height = dim.bounded_header_height(bounds.size());
+        let content_height = dim.bounded_content_height(bounds.size());
+        let address_area_width = dim.bounded_address_area_width(bounds.size());
+
+        let (byte_area_width, char_area_width) = if dim.width() == bounds.width {
+            (dim.byte_area_width, dim.char_area_width)
+        } else {
+            // Divide the available horizontal space between the byte area and char area as fairly
+            // as possible. Scrolling happens based on the byte area's content width, i.e. the
+            // area's width without padding. Division happens based on that first. Then, If there's
+            // too little space to even fit the paddings, the space is divided based on their
+            // ratios as well.
+            let full_content_width = dim.bounded_content_width(bounds.size());
+
+            let all_paddings = padding.byte_area_padding().x()
+                + padding.char_area_padding().x();
+
+            let content_width = (full_content_width - all_paddings).max(0.0);
+
+            let byte_padding = padding.byte_area_padding().x();
+            let char_padding = padding.char_area_padding().x();
+
+            let byte_content = dim.byte_area_width - byte_padding;
+            let char_content = dim.char_area_width - char_padding;
+
+            let remaining_space = full_content_width - content_width;
+
+            let content_ratio = byte_content / (byte_content + char_content);
+            let padding_ratio = byte_padding / (byte_padding + char_padding);
+
+            (
+                content_width * content_ratio + padding_ratio * remaining_space,
+                content_width * (1.0 - content_ratio) + (1.0 - padding_ratio) * remaining_space,
+            )
+        };
+
+        let top_left = Rectangle::new(
+            Point::new(
+                bounds.x,
+                bounds.y
+            ),
+            Size::new(address_area_width, header_height)
+        );
+
+        let byte_area_header = Rectangle::new(
+            Point::new(
+                top_left.x + top_left.width,
+                bounds.y
+            ),
+            Size::new(byte_area_width, header_height)
+        );
+
+        let char_area_header = Rectangle::new(
+            Point::new(
+                byte_area_header.x + byte_area_header.width,
+                bounds.y
+            ),
+            Size::new(char_area_width, header_height)
+        );
+
+        // Space that the areas don't need is left empty, so the vertical scrollbar stays at the
+        // right edge.
+        let trailing_width = (bounds.width - dim.width()).max(0.0);
+
+        let top_right = Rectangle::new(
+            Point::new(
+                char_area_header.x + char_area_header.width + trailing_width,
+                bounds.y
+            ),
+            Size::new(dim.vertical_scrollbar_width, header_height)
+        );
+
+        let address_area = Rectangle::new(
+            Point::new(
+                bounds.x,
+                top_left.y + top_left.height
+            ),
+            Size::new(address_area_width, content_height)
+        );
+
+        let byte_area = Rectangle::new(
+            Point::new(
+                address_area.x + address_area.width,
+                byte_area_header.y + byte_area_header.height
+            ),
+            Size::new(byte_area_width, content_height)
+        );
+
+        let char_area = Rectangle::new(
+            Point::new(
+                byte_area.x + byte_area.width,
+                char_area_header.y + char_area_header.height
+            ),
+            Size::new(char_area_width, content_height)
+        );
+
+        let byte_cell_width = metrics.byte_width + 2.0 * padding.byte_horizontal;
+        let char_cell_width = metrics.char_width + 2.0 * padding.char_horizontal;
+        let byte_shift = percentage_x * byte_cell_width;
+        let char_shift = percentage_x * char_cell_width;
+
+        Layout {
+            dim,
+            padding,
+            source_size,
+            virtual_columns,
+            metrics,
+            byte_cell_width,
+            char_cell_width,
+            byte_shift,
+            char_shift,
+            top_left,
+            byte_area_header,
+            char_area_header,
+            top_right,
+            address_area,
+            byte_area,
+            char_area,
+            trailing_width,
+        }
+    }
+
+    fn width(&self) -> f32 {
+        self.address_area.width + self.byte_area.width + self.char_area.width + self.trailing_width
+            + self.top_right.width
+    }
+
+    /// The empty space between the char area and the vertical scrollbar, see
+    /// [`Layout::trailing_width`].
+    fn trailing_area(&self) -> Rectangle {
+        Rectangle::new(
+            Point::new(self.char_area.x + self.char_area.width, self.char_area.y),
+            Size::new(self.trailing_width, self.char_area.height),
+        )
+    }
+
+    fn address_area_content(&self) -> Rectangle {
+        self.address_area.shrink(self.padding.address_area_padding())
+    }
+
+    fn byte_area_content(&self) -> Rectangle {
+        self.byte_area.shrink(self.padding.byte_area_padding())
+    }
+
+    fn char_area_content(&self) -> Rectangle {
+        self.char_area.shrink(self.padding.char_area_padding())
+    }
+
+    fn headers_background(&self) -> Rectangle {
+        Rectangle::new(self.top_left.position(), Size::new(self.width(), self.top_left.height))
+    }
+
+    /// The bounding box of the byte header cell for `col`.
+    fn byte_header_cell(&self, col: i64) -> Rectangle {
+        Rectangle::new(
+            Point::new(self.byte_cell_x_offset(col), self.byte_area_header.y),
+            Size::new(self.byte_cell_width, self.byte_area_header.height)
+        )
+    }
+
+    /// The top left point of the byte header text for `col`, centered for a number of `digits`.
+    fn byte_header_text_position(&self, col: i64, digits: usize) -> Point {
+        let rect = self.byte_header_cell(col);
+
+        Point::new(
+            rect.x + self.padding.byte_horizontal
+                + (self.metrics.byte_width - digits as f32 * self.metrics.char_width) / 2.0,
+            rect.y + self.padding.header_top + self.last_header_row_offset()
+        )
+    }
+
+    /// The bounding box of the char header cell for `col`.
+    fn char_header_cell(&self, col: i64) -> Rectangle {
+        Rectangle::new(
+            Point::new(self.char_cell_x_offset(col), self.char_area_header.y),
+            Size::new(self.char_cell_width, self.char_area_header.height)
+        )
+    }
+
+    /// The top left point of the char header text for `col`, in the header's text row `row`.
+    fn char_header_text_position(&self, col: i64, row: i64) -> Point {
+        let rect = self.char_header_cell(col);
+
+        Point::new(
+            rect.x + self.padding.char_horizontal,
+            rect.y + self.padding.header_top + row as f32 * self.metrics.height
+        )
+    }
+
+    /// The vertical offset of the last text row in the headers, relative to the first.
+    fn last_header_row_offset(&self) -> f32 {
+        (self.padding.header_rows - 1.0) * self.metrics.height
+    }
+
+    /// The bounding box of the address area cell for `row`.
+    fn address_area_cell(&self, row: i64) -> Rectangle {
+        Rectangle::new(
+            Point::new(self.address_area.x, self.cell_y_offset(row)),
+            Size::new(self.address_area.width, self.row_height())
+        )
+    }
+
+    /// The top left point of the address area's col'nth digit, for `row`.
+    fn address_area_digit_position(&self, col: i64, row: i64) -> Point {
+        let rect = self.address_area_cell(row);
+
+        Point::new(
+            rect.x
+                + self.padding.address_area_left
+                + col as f32 * self.metrics.char_width,
+            rect.y
+                + self.padding.data_vertical
+        )
+    }
+
+    /// The bounds of `row` in the viewport, as wide as the `area`.
+    fn row_bounds(&self, area: Rectangle, row: i64) -> Rectangle {
+        Rectangle::new(
+            Point::new(area.x, self.cell_y_offset(row)),
+            Size::new(area.width, self.row_height()),
+        )
+    }
+
+    /// The area of the [`HexViewer::row_value`] column, at the right of the char area.
+    fn row_value_area(&self) -> Rectangle {
+        let width = self.padding.row_value_width - self.padding.char_area_right;
+
+        Rectangle::new(
+            Point::new(
+                self.char_area.x + self.char_area.width - self.padding.row_value_width,
+                self.char_area.y,
+            ),
+            Size::new(width.max(0.0), self.char_area.height),
+        )
+    }
+
+    /// The top left point of the text of the [`HexViewer::row_value`] column for `row`.
+    fn row_value_position(&self, row: i64) -> Point {
+        Point::new(
+            self.row_value_area().x,
+            self.cell_y_offset(row) + self.padding.data_vertical,
+        )
+    }
+
+    /// Calculates the bounding box for the byte cell. `col` and `row` are relative to the current
+    /// viewport. The position of the bounding box is absolute.
+    fn byte_cell(&self, col: i64, row: i64) -> Rectangle {
+        Rectangle::new(
+            Point::new(self.byte_cell_x_offset(col), self.cell_y_offset(row)),
+            Size::new(
+                self.metrics.byte_width + 2.0 * self.padding.byte_horizontal,
+                self.row_height(),
+            )
+        )
+    }
+
+    /// Calculates the bounding box for the byte text. `col` and `row` are relative to the current
+    /// viewport. The position of the bounding box is absolute.
+    fn byte_text_position(&self, col: i64, row: i64) -> Point {
+        let rect = self.byte_cell(col, row);
+
+        Point::new(
+            rect.x + self.padding.byte_horizontal,
+            rect.y + self.padding.data_vertical
+        )
+    }
+
+    /// Calculates the bounding box for the char cell. `col` and `row` are relative to the current
+    /// viewport. The position of the bounding box is absolute.
+    fn char_cell(&self, col: i64, row: i64) -> Rectangle {
+        Rectangle::new(
+            Point::new(self.char_cell_x_offset(col), self.cell_y_offset(row)),
+            Size::new(
+                self.metrics.char_width + 2.0 * self.padding.char_horizontal,
+                self.row_height(),
+            )
+        )
+    }
+
+    /// Calculates the bounding box for the char text at offset `offset`. The position of the
+    /// bounding box is absolute.
+    fn char_text_position(&self, col: i64, row: i64) -> Point {
+        let rect = self.char_cell(col, row);
+
+        Point::new(
+            rect.x + self.padding.char_horizontal,
+            rect.y + self.padding.data_vertical
+        )
+    }
+
+    /// The separator line along the top of `row` in `area`.
+    fn row_separator(&self, area: Rectangle, row: i64) -> Rectangle {
+        Rectangle::new(
+            Point::new(area.x, self.cell_y_offset(row)),
+            Size::new(area.width, 1.0),
+        )
+    }
+
+    /// The height of each row.
+    fn row_height(&self) -> f32 {
+        self.metrics.height + 2.0 * self.padding.data_vertical
+    }
+
+    fn byte_cell_x_offset(&self, col: i64) -> f32 {
+        self.byte_area.x
+            + col as f32 * (self.metrics.byte_width + 2.0 * self.padding.byte_horizontal)
+            + self.padding.byte_area_left
+            - self.byte_shift
+    }
+
+    fn char_cell_x_offset(&self, col: i64) -> f32 {
+        self.char_area.x
+            + col as f32 * (self.metrics.char_width + 2.0 * self.padding.char_horizontal)
+            + self.padding.char_area_left
+            - self.char_shift
+    }
+
+    /// The row in the viewport of the address area at the absolute `point`, if it's below the
+    /// top padding.
+    fn address_area_row(&self, point: Point) -> Option<i64> {
+        let y = point.y - self.address_area.y - self.padding.content_top;
+
+        (y >= 0.0).then(|| (y / self.row_height()).floor() as i64)
+    }
+
+    /// The column in the viewport of the byte header at the absolute `point`, if it's right of the
+    /// left padding.
+    fn byte_header_column(&self, point: Point) -> Option<i64> {
+        let x = point.x - self.byte_cell_x_offset(0);
+
+        (x >= 0.0).then(|| (x / self.byte_cell_width).floor() as i64)
+    }
+
+    /// The column in the viewport of the char header at the absolute `point`, if it's right of the
+    /// left padding.
+    fn char_header_column(&self, point: Point) -> Option<i64> {
+        let x = point.x - self.char_cell_x_offset(0);
+
+        (x >= 0.0).then(|| (x / self.char_cell_width).floor() as i64)
+    }
+
+    fn cell_y_offset(&self, row: i64) -> f32 {
+        self.address_area.y // Address, byte and char area all have the same y offset.
+            + row as f32 * self.row_height()
+            + self.padding.content_top
+    }
+
+    /// Gives the maximum number of columns that could (partially) fit in the viewport. Doesn't take
+    /// current offsets into account.
+    fn viewport_column_count_ceil(&self) -> i64 {
+        (self.byte_area_content().width / self.byte_cell_width).ceil() as i64
+    }
+
+    /// Gives the maximum number of columns that could (partially) fit in the viewport. Doesn't take
+    /// current offsets into account.
+    fn viewport_column_count_floor(&self) -> i64 {
+        let count = self.byte_area_content().width / self.byte_cell_width;
+
+        if self.virtual_columns as f32 - count < 0.01 {
+            self.virtual_columns
+        } else {
+            count.floor() as i64
+        }
+    }
+
+    fn viewport_row_count_ceil(&self) -> i64 {
+        (self.byte_area_content().height / self.row_height()).ceil() as i64
+    }
+
+    fn viewport_row_count_floor(&self) -> i64 {
+        let count = self.byte_area_content().height / self.row_height();
+
+        if self.virtual_rows_ceil() as f32 - count < 0.01 {
+            self.virtual_rows_ceil()
+        } else {
+            count.floor() as i64
+        }
+    }
+
+    fn virtual_rows_ceil(&self) -> i64 {
+        (self.source_size + self.virtual_columns - 1) / self.virtual_columns
+    }
+
+    /// Gives the maximum number of columns that could (partially) fit in the char area.
+    fn char_column_count_ceil(&self) -> i64 {
+        (self.char_area_content().width / self.char_cell_width).ceil() as i64
+    }
+
+    /// Gives the number of columns that fully fit in the char area.
+    fn char_column_count_floor(&self) -> i64 {
+        let count = self.char_area_content().width / self.char_cell_width;
+
+        if self.virtual_columns as f32 - count < 0.01 {
+            self.virtual_columns
+        } else {
+            (count.floor() as i64).max(1)
+        }
+    }
+
+    fn max_char_x_offset(&self) -> i64 {
+        (self.virtual_columns - self.char_column_count_floor()).max(0)
+    }
+
+    fn max_viewport_x_offset(&self) -> i64 {
+        (self.virtual_columns - self.viewport_column_count_floor()).max(0)
+    }
+
+    fn max_viewport_y_offset(&self) -> i64 {
+        (self.virtual_rows_ceil() - self.viewport_row_count_floor()).max(0)
+    }
+
+    /// The bounds that the [`ScrollArea`] has to its disposal to draw scrollbars. This is primarily
+    /// cosmetic, but it also influences how much scroll rail, and therefore precision, we have.
+    fn scroll_area_bounds(&self) -> Rectangle {
+        Rectangle::new(
+            self.address_area.position(),
+            Size::new(
+                self.width(),
+                self.address_area.height + self.dim.horizontal_scrollbar_height
+            )
+        )
+    }
+
+    /// Translation the mouse pointer's location to a logical location. `point` is absolute.
+    fn pointer_location(&self, point: Point) -> Location {
+        if self.byte_area_header.contains(point) {
+            Location::ByteHeader
+        } else if self.char_area_header.contains(point) {
+            Location::CharHeader
+        } else if self.address_area.contains(point) {
+            Location::AddressArea
+        } else if self.byte_area.contains(point) {
+            Location::ByteArea(self.pointer_location_in_byte_area(point))
+        } else if self.char_area.contains(point) {
+            Location::CharArea(self.pointer_location_in_char_area(point))
+        } else {
+            Location::Other
+        }
+    }
+
+    /// Translation the mouse pointer's location to a logical location assuming the mouse pointer is
+    /// in the byte area. `point` is absolute.
+    fn pointer_location_in_byte_area(&self, point: Point) -> DataLocation {
+        self.pointer_location_in_data_area(
+            point,
+            self.byte_area_content(),
+            self.byte_cell_width,
+            self.byte_shift
+        )
+    }
+
+    /// Translation the mouse pointer's location to a logical location assuming the mouse pointer is
+    /// in the char area. `point` is absolute.
+    fn pointer_location_in_char_area(&self, point: Point) -> DataLocation {
+        self.pointer_location_in_data_area(
+            point,
+            self.char_area_content(),
+            self.char_cell_width,
+            self.char_shift
+        )
+    }
+
+    /// Helper function that consolidates the translation of the mouse pointer's location for both
+    /// the byte and char area. `point` is absolute.
+    fn pointer_location_in_data_area(
+        &self,
+        point: Point,
+        content: Rectangle,
+        cell_width: f32,
+        shift: f32,
+    ) -> DataLocation {
+
+        let cell_row = ((point.y - content.y) / self.row_height()).floor() as i64;
+
+        // Click happened in a cell.
+        if content.contains(point) {
+            let cell_col = ((point.x - (content.x - shift)) / cell_width).floor() as i64;
+            let in_cell_offset = (point.x - (content.x - shift)) % cell_width;
+            let side = if in_cell_offset < cell_width / 2.0 {Side::Left} else {Side::Right};
+            return DataLocation::Cell(Cell::new(cell_col, cell_row, side))
+        }
+
+        let cell_col = ((point.x - content.x) / cell_width).floor() as i64;
+
+        // Click happened in the top padding.
+        if point.y < content.y {
+            if point.x < content.x {
+                return DataLocation::CornerTopLeft
+            } else if point.x < content.x + content.width {
+                return DataLocation::PaddingTop(cell_col)
+            } else {
+                return DataLocation::CornerTopRight
+            }
+        }
+
+        // Click happened in the bottom padding.
+        if point.y > content.y + content.height {
+            if point.x < content.x {
+                return DataLocation::CornerBottomLeft
+            } else if point.x < content.x + content.width {
+                return DataLocation::PaddingBottom(cell_col)
+            } else {
+                return DataLocation::CornerBottomRight
+            }
+        }
+
+        // Point is in the left padding.
+        if point.x < content.x {
+            return DataLocation::PaddingLeft(cell_row);
         }
+
+        // Point is in the right padding.
+        DataLocation::PaddingRight(cell_row)
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub enum Alignment {
-    Start,
-    Center,
-    End,
+/// How the column offsets in the char area header are displayed. Each char cell only has room for
+/// a single char.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum CharHeader {
+    /// Displays the last hex digit of the column offset.
+    #[default]
+    LastDigit,
+    /// Displays the last two hex digits of the column offset on two rows, like the byte area
+    /// header does on one. This makes the headers taller.
+    TwoRows,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-enum Scroll {
-    Lazy(LazyAlignment),
-    Aligned(Alignment),
+impl CharHeader {
+    /// The number of text rows the header needs.
+    fn rows(&self) -> i64 {
+        match self {
+            CharHeader::LastDigit => 1,
+            CharHeader::TwoRows => 2,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
-enum LazyAlignment {
-    Start,
-    End,
+pub enum Step {
+    Cell,
+    Pixel
 }
 
-/// How movement of the cursor should affect the viewport.
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub enum Navigation {
-    // TODO: maybe add an `Ignore` variant that makes the viewport ignore cursor movement.
-    /// The viewport should move as little as possible, as long as it contains the new cursor
-    /// position. That means that if the new cursor position is visible in the current viewport, the
-    /// viewport remains the same. If the new cursor position is not currently visible, the viewport
-    /// moves as little as needed to bring it into view.
-    Lazy,
-    /// The cursor should always be aligned to a certain place in the viewport, denoted by the
-    /// [`Alignment`].
-    Aligned(Alignment),
+impl Default for Step {
+    fn default() -> Self {
+        Self::Cell
+    }
 }
 
-#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
-struct ScrollOffset {
-    pub x: i64,
-    pub y: i64,
+#[derive(Clone, Debug, PartialEq)]
+struct LayoutDimensions {
+    header_height: f32,
+    content_height: f32,
+    address_area_width: f32,
+    byte_area_width: f32,
+    char_area_width: f32,
+    horizontal_scrollbar_height: f32,
+    vertical_scrollbar_width: f32,
 }
 
-impl ScrollOffset {
-    pub fn new(x: i64, y: i64) -> Self {
-        ScrollOffset { x, y }
-    }
-}
+impl LayoutDimensions {
+    fn new(
+        settings: &HexPadding,
+        columns: i64,
+        metrics: HexMetrics,
+        horizontal_scrollbar_height: f32,
+        vertical_scrollbar_width: f32,
+        source_size: i64,
+        bounds_size: Size,
+        height: Length,
+    ) -> LayoutDimensions {
+        let header_height = metrics.height * settings.header_rows
+            + settings.header_top
+            + settings.header_bottom;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Viewport {
-    /// The first column in our viewport. In case of Step::Pixel this column might be only partially
-    /// visible.
-    x: i64,
-    /// The first row in our viewport. Always visible as long as the viewport is high enough to fit
-    /// at least one row, since vertical scrolling always happens per row.
-    y: i64,
-    /// The number of columns (partially) visible.
-    columns: i64,
-    /// The number of rows (partially) visible.
-    rows: i64,
-    /// Percentage of a cell we're scrolled beyond our x. Always 0 in case of Step::Cell.
-    percentage_x: f32,
-    virtual_columns: i64,
-}
+        let virtual_rows_ceil = (source_size + columns - 1) / columns;
 
-impl Default for Viewport {
-    fn default() -> Self {
-        Viewport {
-            x: 0,
-            y: 0,
-            columns: 0,
-            rows: 0,
-            percentage_x: 0.0,
-            virtual_columns: 0
+        let row_height = metrics.height + 2.0 * settings.data_vertical;
+
+        let content_height = if height == Length::Shrink {
+            (virtual_rows_ceil as f64 * row_height as f64
+                + settings.content_top as f64
+                + settings.content_bottom as f64) as f32
+        } else {
+            (bounds_size.height - horizontal_scrollbar_height).max(0.0)
+        };
+
+        let address_area_width =  settings.address_digits * metrics.char_width
+            + settings.address_area_left
+            + settings.address_area_right;
+
+        let byte_area_width = columns as f32
+            * (metrics.byte_width + 2.0 * settings.byte_horizontal)
+            + settings.byte_area_left
+            + settings.byte_area_right;
+
+        let char_area_width = columns as f32
+            * (metrics.char_width + 2.0 * settings.char_horizontal)
+            + settings.char_area_left
+            + settings.char_area_right
+            + settings.row_value_width;
+
+        LayoutDimensions {
+            header_height,
+            content_height,
+            address_area_width,
+            byte_area_width,
+            char_area_width,
+            horizontal_scrollbar_height,
+            vertical_scrollbar_width,
         }
     }
-}
 
-impl Viewport {
-    /// The first column that is visible in the viewport.
-    pub fn x(&self) -> u64 {
-        self.x as u64
+    fn width(&self) -> f32 {
+        self.address_area_width + self.byte_area_width + self.char_area_width + self.vertical_scrollbar_width
     }
 
-    /// The first row that is visible in the viewport.
-    pub fn y(&self) -> u64 {
-        self.y as u64
+    fn height(&self) -> f32 {
+        self.header_height + self.content_height + self.horizontal_scrollbar_height
     }
 
-    /// The number of columns that would (partially) fit in the viewport.
-    pub fn columns(&self) -> u64 {
-        self.columns as u64
+    fn content_width(&self) -> f32 {
+        self.byte_area_width + self.char_area_width
     }
 
-    /// The number of rows that would (partially) fit in the viewport.
-    pub fn rows(&self) -> u64 {
-        self.rows as u64
+    fn bounded_header_height(&self, bounds: Size) -> f32 {
+        self.header_height.min(bounds.height)
+    }
+
+    fn bounded_content_height(&self, bounds: Size) -> f32 {
+        self.content_height.min(bounds.height - self.header_height - self.horizontal_scrollbar_height)
+            .max(0.0)
+    }
+
+    fn bounded_address_area_width(&self, bounds: Size) -> f32 {
+        self.address_area_width
+            .min(bounds.width)
+    }
+
+    fn bounded_content_width(&self, bounds: Size) -> f32 {
+        self.content_width()
+            .min(bounds.width - self.address_area_width - self.vertical_scrollbar_width)
+            .max(0.0)
+    }
+}
+
+/// A logical location within the [`HexViewer`].
+#[derive(Clone, Copy, Debug)]
+enum Location {
+    ByteHeader,
+    CharHeader,
+    AddressArea,
+    ByteArea(DataLocation),
+    CharArea(DataLocation),
+    Other,
+}
+
+impl Location {
+    /// Decides what cell we should consider "clicked" so that we can put the cursor there. If the
+    /// user clicked a cell directly, the decision is obvious. But clicks in padding may result in
+    /// the cursor being moved as well. The returned [`Cell`] is relative to the current viewport.
+    fn approximate_cell(&self, cols: i64, rows: i64) -> Option<Cell> {
+        match self {
+            Location::ByteArea(location)
+            | Location::CharArea(location) => {
+                Some(location.approximate_cell(cols, rows))
+            }
+            Location::ByteHeader
+            | Location::CharHeader
+            | Location::AddressArea
+            | Location::Other => None,
+        }
+    }
+
+    /// The column this Location applies to. No approximation is done, only strict matches are
+    /// returned.
+    fn column(&self) -> Option<i64> {
+        match self {
+            Location::ByteArea(location)
+            | Location::CharArea(location) => {
+                location.column()
+            }
+            Location::ByteHeader
+            | Location::CharHeader
+            | Location::AddressArea
+            | Location::Other => None,
+        }
     }
 
-    /// The absolute offset of the byte in the top left corner of the viewport.
-    pub fn offset(&self) -> u64 {
-        (self.virtual_columns * self.y + self.x) as u64
+    /// The row this Location applies to. No approximation is done, only strict matches are
+    /// returned.
+    fn row(&self) -> Option<i64> {
+        match self {
+            Location::ByteArea(location)
+            | Location::CharArea(location) => {
+                location.row()
+            }
+            Location::ByteHeader
+            | Location::CharHeader
+            | Location::AddressArea
+            | Location::Other => None,
+        }
     }
+}
 
-    /// Total number of bytes that would (partially) fit in the viewport.
-    pub fn size(&self) -> usize {
-        (self.columns * self.rows) as usize
-    }
+/// A logical location within the [`HexViewer`]'s byte/char area.
+#[derive(Clone, Copy, Debug)]
+enum DataLocation {
+    /// A ByteLocation
+    Cell(Cell),
+    /// Padding left, containing the row.
+    PaddingLeft(i64),
+    /// Padding right, containing the row.
+    PaddingRight(i64),
+    /// Padding top, containing the column.
+    PaddingTop(i64),
+    /// Padding bottom, containing the column.
+    PaddingBottom(i64),
+    /// Top left corner.
+    CornerTopLeft,
+    /// Top right corner.
+    CornerTopRight,
+    /// Bottom left corner.
+    CornerBottomLeft,
+    /// Bottom right corner.
+    CornerBottomRight,
+}
 
-    /// Iterator that yields the absolute start and end (not inclusive) offsets of each row.
-    /// With x=2, y=1, columns=8 and virtual_columns = 16, this would yield:
-    ///   [18, 26),
-    ///   [34, 42).
-    ///   ...
-    pub fn iter_rows(&self) -> impl Iterator<Item = Range<u64>> {
-        (0..self.rows).into_iter()
-            .map(|row| {
-                let start = (self.y + row) * self.virtual_columns + self.x;
-                let end = start + self.columns;
-                Range {start: start as u64, end: end as u64}
-            })
+impl DataLocation {
+    fn approximate_cell(&self, cols: i64, rows: i64) -> Cell {
+        match self {
+            DataLocation::Cell(location) => {
+                *location
+            }
+            DataLocation::PaddingLeft(row) => {
+                Cell::new(0, *row, Side::Left)
+            }
+            DataLocation::PaddingRight(row) => {
+                Cell::new(cols - 1, *row, Side::Right)
+            }
+            DataLocation::PaddingTop(_)
+            | DataLocation::CornerTopLeft
+            | DataLocation::CornerTopRight => {
+                Cell::new(0, 0, Side::Left)
+            }
+            DataLocation::PaddingBottom(_)
+            | DataLocation::CornerBottomLeft
+            | DataLocation::CornerBottomRight => {
+                Cell::new(0, rows, Side::Right)
+            }
+        }
     }
 
-    /// Determines if, and if so, at which column and row in the viewport, the absolute `offset`
-    /// into the source is visible.
-    pub fn contains(&self, offset: u64) -> Option<(u64, u64)> {
-        let col = offset as i64 % self.virtual_columns;
-        let row = offset as i64 / self.virtual_columns;
+    /// The column this DataLocation applies to. No approximation is done, only strict matches are
+    /// returned.
+    fn column(&self) -> Option<i64> {
+        match self {
+            DataLocation::Cell(location) => {
+                Some(location.col)
+            }
+            DataLocation::PaddingTop(row)
+            | DataLocation::PaddingBottom(row) => {
+                Some(*row)
+            }
+            DataLocation::PaddingLeft(_)
+            | DataLocation::CornerTopLeft
+            | DataLocation::CornerTopRight
+            | DataLocation::PaddingRight(_)
+            | DataLocation::CornerBottomLeft
+            | DataLocation::CornerBottomRight => None
+        }
+    }
 
-        if col < self.x || col >= self.x + self.columns
-            || row < self.y || row >= self.y + self.rows
-        {
-            None
-        } else {
-            Some(
-                (
-                     (col - self.x) as u64,
-                     (row - self.y) as u64
-                )
-            )
+    /// The row this DataLocation applies to. No approximation is done, only strict matches are
+    /// returned.
+    fn row(&self) -> Option<i64> {
+        match self {
+            DataLocation::Cell(location) => {
+                Some(location.row)
+            }
+            DataLocation::PaddingLeft(row)
+            | DataLocation::PaddingRight(row) => {
+                Some(*row)
+            }
+            DataLocation::PaddingTop(_)
+            | DataLocation::CornerTopLeft
+            | DataLocation::CornerTopRight
+            | DataLocation::PaddingBottom(_)
+            | DataLocation::CornerBottomLeft
+            | DataLocation::CornerBottomRight => None
         }
     }
 }
 
-/// Contains all paddings for the [`HexViewer`] relative to the font size.
+/// Describes the location of a cell relative to the current viewport, and a side within that cell.
+/// The cell in the top left corner has col 0 and row 0.
 #[derive(Clone, Copy, Debug)]
-pub struct PaddingSettings {
-    /// Padding above the text in the byte area header and char area header.
-    pub header_top: f32,
-    /// Padding below the text in the byte area header and char area header.
-    pub header_bottom: f32,
-    /// Padding above the data cells in the byte area and char area.
-    pub content_top: f32,
-    /// Padding below the data cells in the byte area and char area.
-    pub content_bottom: f32,
-    /// Padding left of the cells in the address area.
-    pub address_area_left: f32,
-    /// Padding right of the cells in the address area.
-    pub address_area_right: f32,
-    /// Padding left of the cells in the byte area.
-    pub byte_area_left: f32,
-    /// Padding right of the cells in the byte area.
-    pub byte_area_right: f32,
-    /// Padding left of the cells in the char area.
-    pub char_area_left: f32,
-    /// Padding right of the cells in the char area.
-    pub char_area_right: f32,
-    /// Padding above and below the byte/char cell text.
-    pub data_cell_vertical: f32,
-    /// Padding left of the byte/char cell text.
-    pub byte_cell_horizontal: f32,
-    /// Padding right of the byte/char cell text.
-    pub char_cell_horizontal: f32,
+struct Cell {
+    /// Column in the viewport.
+    col: i64,
+    /// Row in the viewport.
+    row: i64,
+    /// Side of the cell that was clicked, meaning left from center or right from center. Side::None
+    /// when the side isn't chosen, or is irrelevant.
+    side: Side,
 }
 
-impl Default for PaddingSettings {
-    fn default() -> Self {
-        Self::compact()
+impl Cell {
+    fn new(col: i64, row: i64, side: Side) -> Self {
+        Self {col, row, side}
     }
 }
 
-impl PaddingSettings {
-    pub fn compact() -> Self {
-        Self {
-            header_top: 0.3,
-            header_bottom: 0.3,
-            // To match visual horizontal whitespace of 0.6 these should be 0.4, but 0.4 doesn't
-            // look good. Maybe it has to do with the ascent/descent.
-            content_top: 0.3,
-            content_bottom: 0.3,
-            address_area_left: 0.6,
-            address_area_right: 0.6,
-            byte_area_left: 0.3,
-            byte_area_right: 0.3,
-            char_area_left: 0.25,
-            char_area_right: 0.55,
-            data_cell_vertical: 0.2,
-            byte_cell_horizontal: 0.3,
-            char_cell_horizontal: 0.05,
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd)]
+enum Side {
+    Left = 0,
+    None = 1,
+    Right = 2,
+}
+
+/// The counterpart of [`Cell`], which stores an absolute offset into the source, and the same
+/// side information as the [`Cell`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Index {
+    offset: i64,
+    side: Side,
+}
+
+impl PartialOrd for Index {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.offset < other.offset {
+            Some(Ordering::Less)
+        } else if self.offset > other.offset {
+            Some(Ordering::Greater)
+        } else {
+            self.side.partial_cmp(&other.side)
         }
     }
+}
 
-    pub fn spacious() -> Self {
+impl Index {
+    fn new(offset: i64, side: Side) -> Self {
         Self {
-            data_cell_vertical: 0.3,
-            byte_cell_horizontal: 0.4,
-            char_cell_horizontal: 0.1,
-            address_area_left: 0.8,
-            address_area_right: 0.8,
-            byte_area_left: 0.4,
-            byte_area_right: 0.4,
-            char_area_left: 0.3,
-            char_area_right: 0.7,
-            header_top: 0.6,
-            header_bottom: 0.6,
-            // To match visual horizontal whitespace of 0.8 these should be 0.6, but 0.6 doesn't
-            // look good. Maybe it has to do with the line height.
-            content_top: 0.4,
-            content_bottom: 0.4,
+            offset,
+            side
         }
     }
+}
+
+/// Holds the selection info, made for instance by mouse or by holding shift and using the arrow
+/// buttons.
+///
+/// The byte and char cells each have a `left` side and a `ride` side, which play a role in
+/// selections. When a selection is started by clicking the left side of the cell at offset `2` and
+/// the cursor is moved to the right side of the cell at offset `2`, this offset is now selected.
+/// Continuing the movement to the left side of the cell at offset `3`, the selection doesn't
+/// change. Only when the selection progresses to its right side is the cell at offset `3` selected.
+///
+/// This same principle may also play a role in selection made by keyboard, if the cursor at the
+/// start was set by mouse, and hence side information is retained.
+///
+/// Holding ALT while dragging creates a rectangular [`Block`] selection instead, which spans a
+/// number of columns over a number of rows. Use [`Selection::ranges`] to handle both kinds
+/// uniformly.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Selection {
+    /// The leftmost byte in the selection. For a block selection this is its top left byte.
+    pub offset: u64,
+    /// The number of bytes from `offset` up to and including the last selected byte. For a block
+    /// selection this includes the bytes in between its rows that aren't selected, see
+    /// [`Selection::byte_count`].
+    pub length: u64,
+    /// The last byte that was interacted with to create the selection. Whether this byte is part
+    /// of the selection depends on whether the selection was made by keyboard or by mouse, and in
+    /// the latter case, the side of it that was clicked and the direction of the selection. If you
+    /// want the last contained byte, see [`Selection::last_contained`].
+    pub last: u64,
+    /// Set if this is a rectangular block selection.
+    pub block: Option<Block>,
+}
 
-    pub fn header_top(mut self, value: f32) -> Self {
-        self.header_top = value;
-        self
-    }
+/// The shape of a rectangular block [`Selection`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Block {
+    /// The number of selected columns in each row.
+    pub width: u64,
+    /// The number of virtual columns at the time the selection was made.
+    pub virtual_columns: u64,
+}
 
-    pub fn header_bottom(mut self, value: f32) -> Self {
-        self.header_bottom = value;
-        self
+impl Selection {
+    /// Creates a new selection.
+    pub fn new(offset: u64, length: u64, last: u64) -> Self {
+        Self { offset, length, last, block: None }
     }
 
-    pub fn content_top(mut self, value: f32) -> Self {
-        self.content_top = value;
-        self
-    }
+    /// Creates a rectangular block selection spanned by the corner bytes `a` and `b`, both of
+    /// which are included. `b` is considered the last byte interacted with.
+    pub fn block(a: u64, b: u64, virtual_columns: u64) -> Self {
+        let virtual_columns = virtual_columns.max(1);
 
-    pub fn content_bottom(mut self, value: f32) -> Self {
-        self.content_bottom = value;
-        self
-    }
+        let (left, right) = sorted(a % virtual_columns, b % virtual_columns);
+        let (top, bottom) = sorted(a / virtual_columns, b / virtual_columns);
 
-    pub fn address_area_left(mut self, value: f32) -> Self {
-        self.address_area_left = value;
-        self
-    }
+        let offset = top * virtual_columns + left;
+        let end = bottom * virtual_columns + right;
 
-    pub fn address_area_right(mut self, value: f32) -> Self {
-        self.address_area_right = value;
-        self
+        Self {
+            offset,
+            length: end - offset + 1,
+            last: b,
+            block: Some(Block {
+                width: right - left + 1,
+                virtual_columns,
+            }),
+        }
     }
 
-    pub fn byte_area_left(mut self, value: f32) -> Self {
-        self.byte_area_left = value;
-        self
-    }
+    /// Cuts the selection off at the end of a [`Source`] of `size` bytes, for instance after the
+    /// source has shrunk. Block selections lose the rows that start beyond the end. Returns `None`
+    /// if the selection starts beyond the end.
+    pub fn clamp(self, size: u64) -> Option<Self> {
+        if self.offset >= size {
+            return None;
+        }
 
-    pub fn byte_area_right(mut self, value: f32) -> Self {
-        self.byte_area_right = value;
-        self
-    }
+        let length = match self.block {
+            Some(block) => {
+                // The fields are public, so the virtual columns aren't necessarily valid.
+                let virtual_columns = block.virtual_columns.max(1);
+                let rows = (size - self.offset).div_ceil(virtual_columns)
+                    .min(self.length.div_ceil(virtual_columns));
 
-    pub fn char_area_left(mut self, value: f32) -> Self {
-        self.char_area_left = value;
-        self
-    }
+                if rows == 0 {
+                    return None;
+                }
 
-    pub fn char_area_right(mut self, value: f32) -> Self {
-        self.char_area_right = value;
-        self
+                (rows - 1) * virtual_columns + block.width
+            }
+            None => self.length.min(size - self.offset),
+        };
+
+        Some(Self { length, last: self.last.min(self.offset + length), ..self })
     }
 
-    pub fn data_vertical(mut self, value: f32) -> Self {
-        self.data_cell_vertical = value;
-        self
+    /// Iterates over the contiguous ranges of selected bytes: a single range for a normal
+    /// selection, or one range per row for a block selection. Ranges aren't clamped to the size
+    /// of the [`Source`].
+    pub fn ranges(&self) -> impl Iterator<Item = Range<u64>> + use<> {
+        let offset = self.offset;
+
+        let (rows, width, stride) = match self.block {
+            Some(block) => (
+                self.length.div_ceil(block.virtual_columns.max(1)),
+                block.width,
+                block.virtual_columns.max(1),
+            ),
+            None => (1, self.length, 0),
+        };
+
+        let end = offset + self.length;
+
+        (0..rows).map(move |row| {
+            let start = offset + row * stride;
+            start..(start + width).min(end)
+        })
     }
 
-    pub fn byte_horizontal(mut self, value: f32) -> Self {
-        self.byte_cell_horizontal = value;
-        self
+    /// Like [`Selection::ranges`], but as addresses when the first byte is at the address `base`,
+    /// see [`to_address`].
+    pub fn address_ranges(&self, base: u64) -> impl Iterator<Item = Range<u64>> + use<> {
+        self.ranges().map(move |range| to_address(base, range.start)..to_address(base, range.end))
     }
 
-    pub fn char_horizontal(mut self, value: f32) -> Self {
-        self.char_cell_horizontal = value;
-        self
+    /// Whether the byte at the absolute `offset` is selected.
+    pub fn contains(&self, offset: u64) -> bool {
+        match self.block {
+            Some(block) => {
+                let column = offset % block.virtual_columns.max(1);
+                let left = self.offset % block.virtual_columns.max(1);
+
+                (self.offset..self.offset + self.length).contains(&offset)
+                    && column >= left
+                    && column < left + block.width
+            }
+            None => (self.offset..self.offset + self.length).contains(&offset),
+        }
     }
-}
 
-/// Contains all paddings for the [`HexViewer`] in pixels.
-#[derive(Clone, Copy, Debug)]
-struct HexPadding {
-    header_top: f32,
-    header_bottom: f32,
-    content_top: f32,
-    content_bottom: f32,
-    address_area_left: f32,
-    address_area_right: f32,
-    byte_area_left: f32,
-    byte_area_right: f32,
-    char_area_left: f32,
-    char_area_right: f32,
-    data_vertical: f32,
-    byte_horizontal: f32,
-    char_horizontal: f32,
-}
+    /// The end of the selection opposite of the last contained byte, from which the selection is
+    /// continued.
+    fn anchor(&self) -> Index {
+        let last = self.last_contained();
 
-impl HexPadding {
-    fn new(settings: &PaddingSettings, metrics: HexMetrics) -> Self {
-        let abs = |
-            value: f32
-        | {
-            (value * metrics.height).round()  // without rounding to full pixels text doesn't always look good.
+        let anchor = match self.block {
+            Some(block) => {
+                let columns = block.virtual_columns.max(1);
+                let left = self.offset % columns;
+                let right = left + block.width - 1;
+                let top = self.offset / columns;
+                let bottom = (self.offset + self.length - 1) / columns;
+
+                let column = if last % columns == left { right } else { left };
+                let row = if last / columns == top { bottom } else { top };
+
+                row * columns + column
+            }
+            None if last == self.offset => self.offset + self.length - 1,
+            None => self.offset,
         };
 
-        Self {
-            header_top: abs(settings.header_top),
-            header_bottom: abs(settings.header_bottom),
-            content_top: abs(settings.content_top),
-            content_bottom: abs(settings.content_bottom),
-            address_area_left: abs(settings.address_area_left),
-            address_area_right: abs(settings.address_area_right),
-            byte_area_left: abs(settings.byte_area_left),
-            byte_area_right: abs(settings.byte_area_right),
-            char_area_left: abs(settings.char_area_left),
-            char_area_right: abs(settings.char_area_right),
-            data_vertical: abs(settings.data_cell_vertical),
-            byte_horizontal: abs(settings.byte_cell_horizontal),
-            char_horizontal: abs(settings.char_cell_horizontal),
-        }
+        Index::new(anchor as i64, Side::None)
     }
 
-    pub fn address_area_padding(&self) -> Padding {
-        Padding::default()
-            .top(self.content_top)
-            .bottom(self.content_bottom)
-            .left(self.address_area_left)
-            .right(self.address_area_right)
+    /// Reads the selected bytes from the [`Source`] of the `content`, reading at most `limit`
+    /// bytes. The rows of a block selection are concatenated. Fewer bytes are returned if the
+    /// selection extends beyond the end of the source, or the source returns less data. An error is
+    /// returned if the source fails to read.
+    ///
+    /// For selections too large to keep in memory, see [`Selection::read_chunks`].
+    pub fn bytes(&self, content: &mut Content, limit: usize) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+
+        self.read_chunks(content, limit.clamp(1, 64 * 1024), |chunk| {
+            let remaining = limit - bytes.len();
+            bytes.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+            bytes.len() < limit
+        })?;
+
+        Ok(bytes)
     }
 
-    pub fn byte_area_padding(&self) -> Padding {
-        Padding::default()
-            .top(self.content_top)
-            .bottom(self.content_bottom)
-            .left(self.byte_area_left)
-            .right(self.byte_area_right)
+    /// Reads the selected bytes from the [`Source`] of the `content` in order, passing them to
+    /// `func` in chunks of at most `chunk_size` bytes. Reading stops when `func` returns false or
+    /// the source has no more data. If the source returns an error, it is returned after the chunks
+    /// that were read before it. Use this to copy, hash or inspect huge selections without holding
+    /// them in memory.
+    pub fn read_chunks(
+        &self,
+        content: &mut Content,
+        chunk_size: usize,
+        mut func: impl FnMut(&[u8]) -> bool,
+    ) -> io::Result<()> {
+        let size = content.size();
+        let mut buf = vec![0; chunk_size.max(1)];
+
+        for range in self.ranges() {
+            let mut offset = range.start;
+            let end = range.end.min(size);
+
+            while offset < end {
+                let length = ((end - offset) as usize).min(buf.len());
+                let read = content.read_source(offset, &mut buf[..length])?;
+
+                if read == 0 || !func(&buf[..read]) {
+                    return Ok(());
+                }
+
+                offset += read as u64;
+            }
+        }
+
+        Ok(())
     }
 
-    pub fn char_area_padding(&self) -> Padding {
-        Padding::default()
-            .top(self.content_top)
-            .bottom(self.content_bottom)
-            .left(self.char_area_left)
-            .right(self.char_area_right)
+    /// The number of bytes that are actually selected. This doesn't iterate over the rows of a
+    /// block selection, so it's cheap for a block that spans the whole source.
+    pub fn byte_count(&self) -> u64 {
+        let Some(block) = self.block else {
+            return self.length;
+        };
+
+        let virtual_columns = block.virtual_columns.max(1);
+
+        match self.length.div_ceil(virtual_columns).checked_sub(1) {
+            Some(full_rows) => {
+                let last_row = self.length - full_rows * virtual_columns;
+                full_rows * block.width + last_row.min(block.width)
+            }
+            None => 0,
+        }
+    }
+
+    /// The last byte that was interacted with to create the selection, that's also contained in the
+    /// selection.
+    pub fn last_contained(&self) -> u64 {
+        if self.last < self.offset {
+            self.offset
+        } else if self.last >= self.offset + self.length {
+            self.offset + self.length - 1
+        } else {
+            self.last
+        }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum HorizontalScrollStrategy {
-    Normal,
-    Split
+/// Whether `offset` is in one of the sorted, non-overlapping `ranges`.
+fn ranges_contain(ranges: &[Range<u64>], offset: u64) -> bool {
+    let index = ranges.partition_point(|range| range.end <= offset);
+    ranges.get(index).is_some_and(|range| range.start <= offset)
 }
 
-#[derive(Clone, Debug)]
-struct Layout {
-    dim: LayoutDimensions,
-    padding: HexPadding,
-    source_size: i64,
-    virtual_columns: i64,
-    metrics: HexMetrics,
-    byte_cell_width: f32,
-    char_cell_width: f32,
-    byte_shift: f32,
-    char_shift: f32,
-    top_left: Rectangle,
-    byte_area_header: Rectangle,
-    char_area_header: Rectangle,
-    top_right: Rectangle,
-    address_area: Rectangle,
-    byte_area: Rectangle,
-    char_area: Rectangle,
+/// Returns the two values in ascending order.
+fn sorted(a: u64, b: u64) -> (u64, u64) {
+    if a <= b { (a, b) } else { (b, a) }
 }
 
-impl Layout {
-    fn new(
-        dim: LayoutDimensions,
-        padding: HexPadding,
-        source_size: i64,
-        virtual_columns: i64,
-        metrics: HexMetrics,
-        percentage_x: f32,
-        bounds: Rectangle,
-    ) -> Self {
-        let header_height = dim.bounded_header_height(bounds.size());
-        let content_height = dim.bounded_content_height(bounds.size());
-        let address_area_width = dim.bounded_address_area_width(bounds.size());
+/// The former name of the [`OffsetStyler`]. It used to be indexed by position in the viewport,
+/// and has been keyed by absolute offset since, which made it the same as the [`OffsetStyler`].
+#[deprecated(note = "use `OffsetStyler`, `ContentStyler` is an alias of it")]
+pub type ContentStyler = OffsetStyler;
 
-        let (byte_area_width, char_area_width) = if dim.width() == bounds.width {
-            (dim.byte_area_width, dim.char_area_width)
-        } else {
-            // Divide the available horizontal space between the byte area and char area as fairly
-            // as possible. Scrolling happens based on the byte area's content width, i.e. the
-            // area's width without padding. Division happens based on that first. Then, If there's
-            // too little space to even fit the paddings, the space is divided based on their
-            // ratios as well.
-            let full_content_width = dim.bounded_content_width(bounds.size());
+/// Controls the text color and background color of byte/char cells by their absolute offset in
+/// the [`Source`].
+///
+/// The styling stays with the bytes when the viewport moves or is resized, so it only has to be set
+/// once. Styling is stored as ranges, so highlighting a region of many megabytes with
+/// [`OffsetStyler::set_background_range`] stores a single range, and looking up a cell is a binary
+/// search.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct OffsetStyler {
+    text: AreaRanges,
+    background: AreaRanges,
+    highlight: StyleRanges<Highlight>,
+    border: StyleRanges<CellBorder>,
+    decorations: StyleRanges<Decorations>,
+    animation: StyleRanges<CellAnimation>,
+}
 
-            let all_paddings = padding.byte_area_padding().x()
-                + padding.char_area_padding().x();
+impl OffsetStyler {
+    /// Creates an `OffsetStyler` without any styling.
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-            let content_width = (full_content_width - all_paddings).max(0.0);
+    /// Sets the text color of the byte at `offset`.
+    pub fn set_text(&mut self, offset: u64, color: Color) {
+        self.set_text_range(offset..offset.saturating_add(1), color);
+    }
 
-            let byte_padding = padding.byte_area_padding().x();
-            let char_padding = padding.char_area_padding().x();
+    /// Sets the background color of the byte at `offset`.
+    pub fn set_background(&mut self, offset: u64, background: Color) {
+        self.set_background_range(offset..offset.saturating_add(1), background);
+    }
 
-            let byte_content = dim.byte_area_width - byte_padding;
-            let char_content = dim.char_area_width - char_padding;
+    /// Sets the text color of the bytes in `range`, replacing the text color of any bytes in it
+    /// that were styled before.
+    pub fn set_text_range(&mut self, range: Range<u64>, color: Color) {
+        self.text.set(None, range, color);
+    }
 
-            let remaining_space = full_content_width - content_width;
+    /// Sets the background color of the bytes in `range`, replacing the background color of any
+    /// bytes in it that were styled before.
+    pub fn set_background_range(&mut self, range: Range<u64>, background: Color) {
+        self.background.set(None, range, background);
+    }
 
-            let content_ratio = byte_content / (byte_content + char_content);
-            let padding_ratio = byte_padding / (byte_padding + char_padding);
+    /// Sets the text color of the byte at `offset` in the `area` only.
+    pub fn set_area_text(&mut self, offset: u64, area: Area, color: Color) {
+        self.set_area_text_range(area, offset..offset.saturating_add(1), color);
+    }
 
-            (
-                content_width * content_ratio + padding_ratio * remaining_space,
-                content_width * (1.0 - content_ratio) + (1.0 - padding_ratio) * remaining_space,
-            )
-        };
+    /// Sets the background color of the byte at `offset` in the `area` only.
+    pub fn set_area_background(&mut self, offset: u64, area: Area, background: Color) {
+        self.set_area_background_range(area, offset..offset.saturating_add(1), background);
+    }
 
-        let top_left = Rectangle::new(
-            Point::new(
-                bounds.x,
-                bounds.y
-            ),
-            Size::new(address_area_width, header_height)
-        );
+    /// Sets the text color of the bytes in `range` in the `area` only, such as to emphasize a
+    /// string match in the char area.
+    pub fn set_area_text_range(&mut self, area: Area, range: Range<u64>, color: Color) {
+        self.text.set(Some(area), range, color);
+    }
 
-        let byte_area_header = Rectangle::new(
-            Point::new(
-                top_left.x + top_left.width,
-                bounds.y
-            ),
-            Size::new(byte_area_width, header_height)
-        );
+    /// Sets the background color of the bytes in `range` in the `area` only.
+    pub fn set_area_background_range(&mut self, area: Area, range: Range<u64>, background: Color) {
+        self.background.set(Some(area), range, background);
+    }
 
-        let char_area_header = Rectangle::new(
-            Point::new(
-                byte_area_header.x + byte_area_header.width,
-                bounds.y
-            ),
-            Size::new(char_area_width, header_height)
-        );
+    /// Sets the [`Highlight`] of the byte at `offset`.
+    pub fn set_highlight(&mut self, offset: u64, highlight: Highlight) {
+        self.set_highlight_range(offset..offset.saturating_add(1), highlight);
+    }
 
-        let top_right = Rectangle::new(
-            Point::new(
-                char_area_header.x + char_area_header.width,
-                bounds.y
-            ),
-            Size::new(dim.vertical_scrollbar_width, header_height)
-        );
+    /// Sets the [`Highlight`] of the bytes in `range`, replacing the highlight of any bytes in it
+    /// that were highlighted before. Text and background colors that are set explicitly take
+    /// precedence over the colors of the highlight.
+    pub fn set_highlight_range(&mut self, range: Range<u64>, highlight: Highlight) {
+        self.highlight.set(range, highlight);
+    }
 
-        let address_area = Rectangle::new(
-            Point::new(
-                bounds.x,
-                top_left.y + top_left.height
-            ),
-            Size::new(address_area_width, content_height)
-        );
+    /// Sets the border of the byte at `offset`.
+    pub fn set_border(&mut self, offset: u64, border: CellBorder) {
+        self.set_border_range(offset..offset.saturating_add(1), border);
+    }
 
-        let byte_area = Rectangle::new(
-            Point::new(
-                address_area.x + address_area.width,
-                byte_area_header.y + byte_area_header.height
-            ),
-            Size::new(byte_area_width, content_height)
-        );
+    /// Sets the border of every byte in `range` to the same `border`. To outline the range as a
+    /// whole, set the outer sides of the first and last row and column separately.
+    pub fn set_border_range(&mut self, range: Range<u64>, border: CellBorder) {
+        self.border.set(range, border);
+    }
+
+    /// Sets the decorations of the byte at `offset`.
+    pub fn set_decorations(&mut self, offset: u64, decorations: Decorations) {
+        self.set_decorations_range(offset..offset.saturating_add(1), decorations);
+    }
+
+    /// Sets the decorations of the bytes in `range`, replacing the decorations of any bytes in it
+    /// that were styled before.
+    pub fn set_decorations_range(&mut self, range: Range<u64>, decorations: Decorations) {
+        self.decorations.set(range, decorations);
+    }
 
-        let char_area = Rectangle::new(
-            Point::new(
-                byte_area.x + byte_area.width,
-                char_area_header.y + char_area_header.height
-            ),
-            Size::new(char_area_width, content_height)
-        );
+    /// Sets the animation of the byte at `offset`.
+    pub fn set_animation(&mut self, offset: u64, animation: CellAnimation) {
+        self.set_animation_range(offset..offset.saturating_add(1), animation);
+    }
 
-        let byte_cell_width = metrics.byte_width + 2.0 * padding.byte_horizontal;
-        let char_cell_width = metrics.char_width + 2.0 * padding.char_horizontal;
-        let byte_shift = percentage_x * byte_cell_width;
-        let char_shift = percentage_x * char_cell_width;
+    /// Sets the animation of the bytes in `range`, replacing the animation of any bytes in it that
+    /// were styled before.
+    pub fn set_animation_range(&mut self, range: Range<u64>, animation: CellAnimation) {
+        self.animation.set(range, animation);
+    }
 
-        Layout {
-            dim,
-            padding,
-            source_size,
-            virtual_columns,
-            metrics,
-            byte_cell_width,
-            char_cell_width,
-            byte_shift,
-            char_shift,
-            top_left,
-            byte_area_header,
-            char_area_header,
-            top_right,
-            address_area,
-            byte_area,
-            char_area,
-        }
+    /// Removes the styling of the byte at `offset`.
+    pub fn remove(&mut self, offset: u64) {
+        self.remove_range(offset..offset.saturating_add(1));
     }
 
-    fn width(&self) -> f32 {
-        self.address_area.width + self.byte_area.width + self.char_area.width + self.top_right.width
+    /// Removes the styling of the bytes in `range`.
+    pub fn remove_range(&mut self, range: Range<u64>) {
+        self.text.remove(range.clone());
+        self.background.remove(range.clone());
+        self.highlight.remove(range.clone());
+        self.border.remove(range.clone());
+        self.decorations.remove(range.clone());
+        self.animation.remove(range);
     }
 
-    fn address_area_content(&self) -> Rectangle {
-        self.address_area.shrink(self.padding.address_area_padding())
+    /// The style of the byte at `offset`, if it's styled.
+    pub fn get(&self, offset: u64) -> Option<CellStyle> {
+        let text = AreaColors {
+            bytes: self.text.get(offset, Area::Bytes),
+            chars: self.text.get(offset, Area::Chars),
+        };
+        let background = AreaColors {
+            bytes: self.background.get(offset, Area::Bytes),
+            chars: self.background.get(offset, Area::Chars),
+        };
+        let highlight = self.highlight.get(offset);
+        let border = self.border.get(offset);
+        let decorations = self.decorations.get(offset);
+        let animation = self.animation.get(offset);
+        let style = CellStyle { text, background, highlight, border, decorations, animation };
+
+        (style != CellStyle::default()).then_some(style)
     }
 
-    fn byte_area_content(&self) -> Rectangle {
-        self.byte_area.shrink(self.padding.byte_area_padding())
+    /// Whether no bytes are styled.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty() && self.background.is_empty() && self.highlight.ranges.is_empty()
+            && self.border.ranges.is_empty() && self.decorations.ranges.is_empty()
+            && self.animation.ranges.is_empty()
     }
 
-    fn char_area_content(&self) -> Rectangle {
-        self.char_area.shrink(self.padding.char_area_padding())
+    /// Removes all styling.
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.background.clear();
+        self.highlight.ranges.clear();
+        self.border.ranges.clear();
+        self.decorations.ranges.clear();
+        self.animation.ranges.clear();
     }
 
-    fn headers_background(&self) -> Rectangle {
-        Rectangle::new(self.top_left.position(), Size::new(self.width(), self.top_left.height))
+    fn text_color(&self, offset: u64, area: Area, resolve: &Resolve) -> Option<Color> {
+        self.text.get(offset, area)
+            .or_else(|| Some(resolve(self.highlight.get(offset)?).text))
     }
 
-    /// The bounding box of the byte header cell for `col`.
-    fn byte_header_cell(&self, col: i64) -> Rectangle {
-        Rectangle::new(
-            Point::new(self.byte_cell_x_offset(col), self.byte_area_header.y),
-            Size::new(self.byte_cell_width, self.byte_area_header.height)
-        )
+    fn background_color(&self, offset: u64, area: Area, resolve: &Resolve) -> Option<Color> {
+        self.background.get(offset, area)
+            .or_else(|| Some(resolve(self.highlight.get(offset)?).background))
     }
 
-    /// The top left point of the byte header text for `col`.
-    fn byte_header_text_position(&self, col: i64, col_val: i64) -> Point {
-        let rect = self.byte_header_cell(col);
+    fn border(&self, offset: u64) -> Option<CellBorder> {
+        self.border.get(offset)
+    }
 
-        Point::new(
-            rect.x + self.padding.byte_horizontal
-                + (if col_val < 0x10 {self.metrics.byte_width * 0.25} else {0.0}),
-            rect.y + self.padding.header_top
-        )
+    fn decorations(&self, offset: u64) -> Option<Decorations> {
+        self.decorations.get(offset)
     }
 
-    /// The bounding box of the char header cell for `col`.
-    fn char_header_cell(&self, col: i64) -> Rectangle {
-        Rectangle::new(
-            Point::new(self.char_cell_x_offset(col), self.char_area_header.y),
-            Size::new(self.char_cell_width, self.char_area_header.height)
-        )
+    fn animation(&self, offset: u64) -> Option<CellAnimation> {
+        self.animation.get(offset)
     }
+}
 
-    /// The top left point of the char header text for `col`.
-    fn char_header_text_position(&self, col: i64) -> Point {
-        let rect = self.char_header_cell(col);
+/// The colors of an [`OffsetStyler`] per [`Area`].
+#[derive(Debug, Default, Clone, PartialEq)]
+struct AreaRanges {
+    bytes: StyleRanges<Color>,
+    chars: StyleRanges<Color>,
+}
 
-        Point::new(
-            rect.x + self.padding.char_horizontal,
-            rect.y + self.padding.header_top
-        )
+impl AreaRanges {
+    fn get(&self, offset: u64, area: Area) -> Option<Color> {
+        match area {
+            Area::Bytes => self.bytes.get(offset),
+            Area::Chars => self.chars.get(offset),
+        }
     }
 
-    /// The bounding box of the address area cell for `row`.
-    fn address_area_cell(&self, row: i64) -> Rectangle {
-        Rectangle::new(
-            Point::new(self.address_area.x, self.cell_y_offset(row)),
-            Size::new(self.address_area.width, self.row_height())
-        )
+    /// Sets the color of the `range` in the `area`, or in both areas if it's `None`.
+    fn set(&mut self, area: Option<Area>, range: Range<u64>, color: Color) {
+        if area != Some(Area::Chars) {
+            self.bytes.set(range.clone(), color);
+        }
+
+        if area != Some(Area::Bytes) {
+            self.chars.set(range, color);
+        }
     }
 
-    /// The top left point of the address area's col'nth digit, for `row`.
-    fn address_area_digit_position(&self, col: i64, row: i64) -> Point {
-        let rect = self.address_area_cell(row);
+    fn remove(&mut self, range: Range<u64>) {
+        self.bytes.remove(range.clone());
+        self.chars.remove(range);
+    }
 
-        Point::new(
-            rect.x
-                + self.padding.address_area_left
-                + col as f32 * self.metrics.char_width,
-            rect.y
-                + self.padding.data_vertical
-        )
+    fn is_empty(&self) -> bool {
+        self.bytes.ranges.is_empty() && self.chars.ranges.is_empty()
     }
 
-    /// Calculates the bounding box for the byte cell. `col` and `row` are relative to the current
-    /// viewport. The position of the bounding box is absolute.
-    fn byte_cell(&self, col: i64, row: i64) -> Rectangle {
-        Rectangle::new(
-            Point::new(self.byte_cell_x_offset(col), self.cell_y_offset(row)),
-            Size::new(
-                self.metrics.byte_width + 2.0 * self.padding.byte_horizontal,
-                self.row_height(),
-            )
-        )
+    fn clear(&mut self) {
+        self.bytes.ranges.clear();
+        self.chars.ranges.clear();
     }
+}
 
-    /// Calculates the bounding box for the byte text. `col` and `row` are relative to the current
-    /// viewport. The position of the bounding box is absolute.
-    fn byte_text_position(&self, col: i64, row: i64) -> Point {
-        let rect = self.byte_cell(col, row);
+/// Sorted, non-overlapping ranges of absolute offsets with a value each, such as a color. Adjacent
+/// ranges with the same value are merged.
+#[derive(Debug, Clone, PartialEq)]
+struct StyleRanges<T> {
+    /// The ranges by their start, with their end and value.
+    ranges: BTreeMap<u64, (u64, T)>,
+}
 
-        Point::new(
-            rect.x + self.padding.byte_horizontal,
-            rect.y + self.padding.data_vertical
-        )
+impl<T> Default for StyleRanges<T> {
+    fn default() -> Self {
+        Self { ranges: BTreeMap::new() }
     }
+}
 
-    /// Calculates the bounding box for the char cell. `col` and `row` are relative to the current
-    /// viewport. The position of the bounding box is absolute.
-    fn char_cell(&self, col: i64, row: i64) -> Rectangle {
-        Rectangle::new(
-            Point::new(self.char_cell_x_offset(col), self.cell_y_offset(row)),
-            Size::new(
-                self.metrics.char_width + 2.0 * self.padding.char_horizontal,
-                self.row_height(),
-            )
-        )
+impl<T: Copy + PartialEq> StyleRanges<T> {
+    fn get(&self, offset: u64) -> Option<T> {
+        let (_, &(end, color)) = self.ranges.range(..=offset).next_back()?;
+        (offset < end).then_some(color)
     }
 
-    /// Calculates the bounding box for the char text at offset `offset`. The position of the
-    /// bounding box is absolute.
-    fn char_text_position(&self, col: i64, row: i64) -> Point {
-        let rect = self.char_cell(col, row);
+    fn set(&mut self, range: Range<u64>, value: T) {
+        if range.is_empty() {
+            return;
+        }
 
-        Point::new(
-            rect.x + self.padding.char_horizontal,
-            rect.y + self.padding.data_vertical
-        )
+        self.remove(range.clone());
+
+        let mut start = range.start;
+        let mut end = range.end;
+
+        let previous = self.ranges.range(..start).next_back();
+
+        if let Some((&previous, &(previous_end, previous_value))) = previous
+            && previous_end == start && previous_value == value
+        {
+            self.ranges.remove(&previous);
+            start = previous;
+        }
+
+        if let Some(&(next_end, next_value)) = self.ranges.get(&end)
+            && next_value == value
+        {
+            self.ranges.remove(&end);
+            end = next_end;
+        }
+
+        self.ranges.insert(start, (end, value));
     }
 
-    /// The height of each row.
-    fn row_height(&self) -> f32 {
-        self.metrics.height + 2.0 * self.padding.data_vertical
+    fn remove(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return;
+        }
+
+        // Cut off the range that starts before `range` and overlaps it, keeping what's after it.
+        if let Some((&start, &(end, value))) = self.ranges.range(..range.start).next_back()
+            && end > range.start
+        {
+            self.ranges.insert(start, (range.start, value));
+
+            if end > range.end {
+                self.ranges.insert(range.end, (end, value));
+            }
+        }
+
+        let inside: Vec<u64> = self.ranges.range(range.clone()).map(|(&start, _)| start).collect();
+
+        for start in inside {
+            if let Some((end, value)) = self.ranges.remove(&start)
+                && end > range.end
+            {
+                self.ranges.insert(range.end, (end, value));
+            }
+        }
     }
+}
 
-    fn byte_cell_x_offset(&self, col: i64) -> f32 {
-        self.byte_area.x
-            + col as f32 * (self.metrics.byte_width + 2.0 * self.padding.byte_horizontal)
-            + self.padding.byte_area_left
-            - self.byte_shift
+/// Computes the style of byte/char cells from their absolute offset and value.
+///
+/// Nothing is stored, so there's nothing to rebuild when the viewport or the data changes. The
+/// style is computed for every visible cell each time the [`HexViewer`] is drawn, so it should be
+/// cheap. It's implemented for closures of the form `Fn(u64, u8) -> Option<CellStyle>`.
+pub trait StyleProvider {
+    /// The style of the byte with `value` at `offset`, if it's styled.
+    fn style(&self, offset: u64, value: u8) -> Option<CellStyle>;
+}
+
+impl<F> StyleProvider for F
+where
+    F: Fn(u64, u8) -> Option<CellStyle>,
+{
+    fn style(&self, offset: u64, value: u8) -> Option<CellStyle> {
+        self(offset, value)
     }
+}
 
-    fn char_cell_x_offset(&self, col: i64) -> f32 {
-        self.char_area.x
-            + col as f32 * (self.metrics.char_width + 2.0 * self.padding.char_horizontal)
-            + self.padding.char_area_left
-            - self.char_shift
+/// A named category of styling in [`StyleLayers`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layer {
+    /// The base coloring, such as coloring by byte value.
+    Base,
+    /// Regions, such as the fields of a parsed file format.
+    Regions,
+    /// Search results.
+    Search,
+    /// Differences with other data.
+    Diff,
+    /// The selection.
+    Selection,
+    /// The cursor and its surroundings.
+    Cursor,
+}
+
+impl Layer {
+    /// All layers, from the lowest to the highest default priority.
+    pub const ALL: [Layer; 6] = [
+        Layer::Base,
+        Layer::Regions,
+        Layer::Search,
+        Layer::Diff,
+        Layer::Selection,
+        Layer::Cursor,
+    ];
+}
+
+/// Styles the byte/char cells with an [`OffsetStyler`] per [`Layer`].
+///
+/// Each layer can be enabled and disabled and has a priority. When multiple enabled layers style
+/// the same cell, the layer with the highest priority wins. This lets the application toggle
+/// categories, for instance with a [`Legend`](crate::hex::legend::Legend), without having to
+/// rebuild the styling data.
+pub struct StyleLayers {
+    /// Sorted from the highest to the lowest priority.
+    layers: Vec<StyleLayer>,
+}
+
+struct StyleLayer {
+    layer: Layer,
+    styler: OffsetStyler,
+    enabled: bool,
+    priority: i32,
+}
+
+impl Default for StyleLayers {
+    fn default() -> Self {
+        StyleLayers::new()
     }
+}
 
-    fn cell_y_offset(&self, row: i64) -> f32 {
-        self.address_area.y // Address, byte and char area all have the same y offset.
-            + row as f32 * self.row_height()
-            + self.padding.content_top
+impl StyleLayers {
+    /// Creates all layers without any styling. They're enabled, and their priority follows
+    /// [`Layer::ALL`].
+    pub fn new() -> Self {
+        let mut layers: Vec<_> = Layer::ALL.iter()
+            .enumerate()
+            .map(|(priority, &layer)| StyleLayer {
+                layer,
+                styler: OffsetStyler::new(),
+                enabled: true,
+                priority: priority as i32,
+            })
+            .collect();
+
+        layers.reverse();
+
+        Self { layers }
     }
 
-    /// Gives the maximum number of columns that could (partially) fit in the viewport. Doesn't take
-    /// current offsets into account.
-    fn viewport_column_count_ceil(&self) -> i64 {
-        (self.byte_area_content().width / self.byte_cell_width).ceil() as i64
+    /// The [`OffsetStyler`] of the `layer`.
+    pub fn styler(&self, layer: Layer) -> &OffsetStyler {
+        &self.get(layer).styler
     }
 
-    /// Gives the maximum number of columns that could (partially) fit in the viewport. Doesn't take
-    /// current offsets into account.
-    fn viewport_column_count_floor(&self) -> i64 {
-        let count = self.byte_area_content().width / self.byte_cell_width;
+    /// The [`OffsetStyler`] of the `layer`, to change its styling.
+    pub fn styler_mut(&mut self, layer: Layer) -> &mut OffsetStyler {
+        &mut self.get_mut(layer).styler
+    }
 
-        if self.virtual_columns as f32 - count < 0.01 {
-            self.virtual_columns
-        } else {
-            count.floor() as i64
-        }
+    /// Whether the `layer` is drawn.
+    pub fn is_enabled(&self, layer: Layer) -> bool {
+        self.get(layer).enabled
     }
 
-    fn viewport_row_count_ceil(&self) -> i64 {
-        (self.byte_area_content().height / self.row_height()).ceil() as i64
+    /// Sets whether the `layer` is drawn. Its styling is kept either way.
+    pub fn set_enabled(&mut self, layer: Layer, enabled: bool) {
+        self.get_mut(layer).enabled = enabled;
     }
 
-    fn viewport_row_count_floor(&self) -> i64 {
-        let count = self.byte_area_content().height / self.row_height();
+    /// The priority of the `layer`.
+    pub fn priority(&self, layer: Layer) -> i32 {
+        self.get(layer).priority
+    }
 
-        if self.virtual_rows_ceil() as f32 - count < 0.01 {
-            self.virtual_rows_ceil()
-        } else {
-            count.floor() as i64
+    /// Sets the priority of the `layer`. Layers with a higher priority are drawn over layers with a
+    /// lower priority. Layers with the same priority keep their relative order.
+    pub fn set_priority(&mut self, layer: Layer, priority: i32) {
+        self.get_mut(layer).priority = priority;
+        self.layers.sort_by_key(|layer| std::cmp::Reverse(layer.priority));
+    }
+
+    /// Removes the styling of all layers. Whether they're enabled and their priorities are kept.
+    pub fn clear(&mut self) {
+        for layer in &mut self.layers {
+            layer.styler.clear();
         }
     }
 
-    fn virtual_rows_ceil(&self) -> i64 {
-        (self.source_size + self.virtual_columns - 1) / self.virtual_columns
+    fn get(&self, layer: Layer) -> &StyleLayer {
+        self.layers.iter()
+            .find(|style_layer| style_layer.layer == layer)
+            .expect("all layers are created")
     }
 
-    fn max_viewport_x_offset(&self) -> i64 {
-        (self.virtual_columns - self.viewport_column_count_floor()).max(0)
+    fn get_mut(&mut self, layer: Layer) -> &mut StyleLayer {
+        self.layers.iter_mut()
+            .find(|style_layer| style_layer.layer == layer)
+            .expect("all layers are created")
     }
 
-    fn max_viewport_y_offset(&self) -> i64 {
-        (self.virtual_rows_ceil() - self.viewport_row_count_floor()).max(0)
+    fn enabled(&self) -> impl Iterator<Item = &StyleLayer> {
+        self.layers.iter().filter(|layer| layer.enabled)
     }
 
-    /// The bounds that the [`ScrollArea`] has to its disposal to draw scrollbars. This is primarily
-    /// cosmetic, but it also influences how much scroll rail, and therefore precision, we have.
-    fn scroll_area_bounds(&self) -> Rectangle {
-        Rectangle::new(
-            self.address_area.position(),
-            Size::new(
-                self.width(),
-                self.address_area.height + self.dim.horizontal_scrollbar_height
-            )
-        )
+    fn text_color(&self, offset: u64, area: Area, resolve: &Resolve) -> Option<Color> {
+        self.enabled().find_map(|layer| layer.styler.text_color(offset, area, resolve))
     }
 
-    /// Translation the mouse pointer's location to a logical location. `point` is absolute.
-    fn pointer_location(&self, point: Point) -> Location {
-        if self.byte_area_header.contains(point) {
-            Location::ByteHeader
-        } else if self.char_area_header.contains(point) {
-            Location::CharHeader
-        } else if self.address_area.contains(point) {
-            Location::AddressArea
-        } else if self.byte_area.contains(point) {
-            Location::ByteArea(self.pointer_location_in_byte_area(point))
-        } else if self.char_area.contains(point) {
-            Location::CharArea(self.pointer_location_in_char_area(point))
-        } else {
-            Location::Other
-        }
+    fn background_color(&self, offset: u64, area: Area, resolve: &Resolve) -> Option<Color> {
+        self.enabled().find_map(|layer| layer.styler.background_color(offset, area, resolve))
     }
 
-    /// Translation the mouse pointer's location to a logical location assuming the mouse pointer is
-    /// in the byte area. `point` is absolute.
-    fn pointer_location_in_byte_area(&self, point: Point) -> DataLocation {
-        self.pointer_location_in_data_area(
-            point,
-            self.byte_area_content(),
-            self.byte_cell_width,
-            self.byte_shift
-        )
+    fn border(&self, offset: u64) -> Option<CellBorder> {
+        self.enabled().find_map(|layer| layer.styler.border(offset))
+    }
+
+    fn decorations(&self, offset: u64) -> Option<Decorations> {
+        self.enabled().find_map(|layer| layer.styler.decorations(offset))
     }
 
-    /// Translation the mouse pointer's location to a logical location assuming the mouse pointer is
-    /// in the char area. `point` is absolute.
-    fn pointer_location_in_char_area(&self, point: Point) -> DataLocation {
-        self.pointer_location_in_data_area(
-            point,
-            self.char_area_content(),
-            self.char_cell_width,
-            self.char_shift
-        )
+    fn animation(&self, offset: u64) -> Option<CellAnimation> {
+        self.enabled().find_map(|layer| layer.styler.animation(offset))
     }
+}
 
-    /// Helper function that consolidates the translation of the mouse pointer's location for both
-    /// the byte and char area. `point` is absolute.
-    fn pointer_location_in_data_area(
-        &self,
-        point: Point,
-        content: Rectangle,
-        cell_width: f32,
-        shift: f32,
-    ) -> DataLocation {
+/// Styles the byte/char cells by absolute, run-length encoded ranges.
+///
+/// This holds a [`CellStyle`] per run of equally styled bytes in the whole source. A coloring of a
+/// file of many gigabytes typically only takes up kilobytes, and needn't be rebuilt when the
+/// viewport changes. Looking up the style of a byte is a binary search.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StyleRuns {
+    /// Sorted, non-overlapping runs.
+    runs: Vec<StyleRun>,
+}
 
-        let cell_row = ((point.y - content.y) / self.row_height()).floor() as i64;
+#[derive(Debug, Clone, PartialEq)]
+struct StyleRun {
+    range: Range<u64>,
+    style: CellStyle,
+}
 
-        // Click happened in a cell.
-        if content.contains(point) {
-            let cell_col = ((point.x - (content.x - shift)) / cell_width).floor() as i64;
-            let in_cell_offset = (point.x - (content.x - shift)) % cell_width;
-            let side = if in_cell_offset < cell_width / 2.0 {Side::Left} else {Side::Right};
-            return DataLocation::Cell(Cell::new(cell_col, cell_row, side))
+impl StyleRuns {
+    /// Creates empty runs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a run. A run that directly follows an equally styled run is merged with it, and
+    /// empty runs are ignored.
+    ///
+    /// # Panics
+    /// Panics if the run starts before the end of the previous run. Use
+    /// [`StyleRuns::from_iter`] to collect runs in any order.
+    pub fn push(&mut self, range: Range<u64>, style: CellStyle) {
+        if range.is_empty() {
+            return;
         }
 
-        let cell_col = ((point.x - content.x) / cell_width).floor() as i64;
+        if let Some(last) = self.runs.last_mut() {
+            assert!(range.start >= last.range.end, "runs must be pushed in ascending order");
 
-        // Click happened in the top padding.
-        if point.y < content.y {
-            if point.x < content.x {
-                return DataLocation::CornerTopLeft
-            } else if point.x < content.x + content.width {
-                return DataLocation::PaddingTop(cell_col)
-            } else {
-                return DataLocation::CornerTopRight
+            if last.range.end == range.start && last.style == style {
+                last.range.end = range.end;
+                return;
             }
         }
 
-        // Click happened in the bottom padding.
-        if point.y > content.y + content.height {
-            if point.x < content.x {
-                return DataLocation::CornerBottomLeft
-            } else if point.x < content.x + content.width {
-                return DataLocation::PaddingBottom(cell_col)
-            } else {
-                return DataLocation::CornerBottomRight
-            }
-        }
+        self.runs.push(StyleRun { range, style });
+    }
 
-        // Point is in the left padding.
-        if point.x < content.x {
-            return DataLocation::PaddingLeft(cell_row);
-        }
+    /// The style of the byte at the absolute `offset`, if any.
+    pub fn get(&self, offset: u64) -> Option<&CellStyle> {
+        let index = self.runs.partition_point(|run| run.range.end <= offset);
 
-        // Point is in the right padding.
-        DataLocation::PaddingRight(cell_row)
+        self.runs.get(index)
+            .filter(|run| run.range.contains(&offset))
+            .map(|run| &run.style)
     }
-}
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub enum Step {
-    Cell,
-    Pixel
-}
+    /// Iterates over the runs in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = (Range<u64>, &CellStyle)> {
+        self.runs.iter().map(|run| (run.range.clone(), &run.style))
+    }
 
-impl Default for Step {
-    fn default() -> Self {
-        Self::Cell
+    /// The number of runs.
+    pub fn len(&self) -> usize {
+        self.runs.len()
     }
-}
 
-#[derive(Clone, Debug)]
-struct LayoutDimensions {
-    header_height: f32,
-    content_height: f32,
-    address_area_width: f32,
-    byte_area_width: f32,
-    char_area_width: f32,
-    horizontal_scrollbar_height: f32,
-    vertical_scrollbar_width: f32,
+    /// Whether there are no runs.
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Removes all runs.
+    pub fn clear(&mut self) {
+        self.runs.clear();
+    }
 }
 
-impl LayoutDimensions {
-    fn new(
-        settings: &HexPadding,
-        columns: i64,
-        metrics: HexMetrics,
-        horizontal_scrollbar_height: f32,
-        vertical_scrollbar_width: f32,
-        source_size: i64,
-        bounds_size: Size,
-        height: Length,
-    ) -> LayoutDimensions {
-        let header_height = metrics.height
-            + settings.header_top
-            + settings.header_bottom;
+impl FromIterator<(Range<u64>, CellStyle)> for StyleRuns {
+    /// Collects runs in any order. Where runs overlap, the run that starts first wins.
+    fn from_iter<I: IntoIterator<Item = (Range<u64>, CellStyle)>>(iter: I) -> Self {
+        let mut runs: Vec<_> = iter.into_iter().collect();
+        runs.sort_by_key(|(range, _)| range.start);
 
-        let virtual_rows_ceil = (source_size + columns - 1) / columns;
+        let mut style_runs = Self::new();
 
-        let row_height = metrics.height + 2.0 * settings.data_vertical;
+        for (range, style) in runs {
+            let start = style_runs.runs.last()
+                .map_or(range.start, |last| range.start.max(last.range.end));
 
-        let content_height = if height == Length::Shrink {
-            (virtual_rows_ceil as f64 * row_height as f64
-                + settings.content_top as f64
-                + settings.content_bottom as f64) as f32
-        } else {
-            (bounds_size.height - horizontal_scrollbar_height).max(0.0)
-        };
+            style_runs.push(start..range.end.max(start), style);
+        }
 
-        let address_area_char_count = format!("{}", source_size).chars().count() as f32;
+        style_runs
+    }
+}
 
-        let address_area_width =  address_area_char_count * metrics.char_width
-            + settings.address_area_left
-            + settings.address_area_right;
+/// The style of a byte/char cell.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CellStyle {
+    text: AreaColors,
+    background: AreaColors,
+    highlight: Option<Highlight>,
+    border: Option<CellBorder>,
+    decorations: Option<Decorations>,
+    animation: Option<CellAnimation>,
+}
 
-        let byte_area_width = columns as f32
-            * (metrics.byte_width + 2.0 * settings.byte_horizontal)
-            + settings.byte_area_left
-            + settings.byte_area_right;
+impl CellStyle {
+    /// Creates a style that doesn't change the cell's appearance.
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        let char_area_width = columns as f32
-            * (metrics.char_width + 2.0 * settings.char_horizontal)
-            + settings.char_area_left
-            + settings.char_area_right;
+    /// Sets the text color in both areas.
+    pub fn text(mut self, color: Color) -> Self {
+        self.text = AreaColors::both(color);
+        self
+    }
 
-        LayoutDimensions {
-            header_height,
-            content_height,
-            address_area_width,
-            byte_area_width,
-            char_area_width,
-            horizontal_scrollbar_height,
-            vertical_scrollbar_width,
-        }
+    /// Sets the background color in both areas.
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = AreaColors::both(color);
+        self
     }
 
-    fn width(&self) -> f32 {
-        self.address_area_width + self.byte_area_width + self.char_area_width + self.vertical_scrollbar_width
+    /// Sets the text color in the `area`, replacing the color set by [`CellStyle::text`] there.
+    /// Without a color in the other area, only this area is styled.
+    pub fn area_text(mut self, area: Area, color: Color) -> Self {
+        self.text.set(area, color);
+        self
     }
 
-    fn height(&self) -> f32 {
-        self.header_height + self.content_height + self.horizontal_scrollbar_height
+    /// Sets the background color in the `area`, replacing the color set by
+    /// [`CellStyle::background`] there. Without a color in the other area, only this area is
+    /// styled.
+    pub fn area_background(mut self, area: Area, color: Color) -> Self {
+        self.background.set(area, color);
+        self
     }
 
-    fn content_width(&self) -> f32 {
-        self.byte_area_width + self.char_area_width
+    /// Sets the [`Highlight`], whose colors come from the theme. Colors set with
+    /// [`CellStyle::text`] and [`CellStyle::background`] take precedence over them.
+    pub fn highlight(mut self, highlight: Highlight) -> Self {
+        self.highlight = Some(highlight);
+        self
     }
 
-    fn bounded_header_height(&self, bounds: Size) -> f32 {
-        self.header_height.min(bounds.height)
+    /// Sets the border, which is drawn over the background.
+    pub fn border(mut self, border: CellBorder) -> Self {
+        self.border = Some(border);
+        self
     }
 
-    fn bounded_content_height(&self, bounds: Size) -> f32 {
-        self.content_height.min(bounds.height - self.header_height - self.horizontal_scrollbar_height)
-            .max(0.0)
+    fn text_color(&self, area: Area, resolve: &Resolve) -> Option<Color> {
+        self.text.get(area).or_else(|| Some(resolve(self.highlight?).text))
     }
 
-    fn bounded_address_area_width(&self, bounds: Size) -> f32 {
-        self.address_area_width
-            .min(bounds.width)
+    fn background_color(&self, area: Area, resolve: &Resolve) -> Option<Color> {
+        self.background.get(area).or_else(|| Some(resolve(self.highlight?).background))
     }
 
-    fn bounded_content_width(&self, bounds: Size) -> f32 {
-        self.content_width()
-            .min(bounds.width - self.address_area_width - self.vertical_scrollbar_width)
-            .max(0.0)
+    /// Sets the lines drawn under, through or over the text, in the text color.
+    pub fn decorations(mut self, decorations: Decorations) -> Self {
+        self.decorations = Some(decorations);
+        self
+    }
+
+    /// Sets the animation of the background, to draw attention to the cell.
+    pub fn animation(mut self, animation: CellAnimation) -> Self {
+        self.animation = Some(animation);
+        self
     }
 }
 
-/// A logical location within the [`HexViewer`].
-#[derive(Clone, Copy, Debug)]
-enum Location {
-    ByteHeader,
-    CharHeader,
-    AddressArea,
-    ByteArea(DataLocation),
-    CharArea(DataLocation),
-    Other,
+/// A semantic class of highlighted bytes, whose colors come from the theme through
+/// [`Catalog::highlight`], so highlights look right in both light and dark themes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Highlight {
+    /// A match, such as a search hit.
+    Match,
+    /// Something that deserves attention.
+    Warning,
+    /// Something that's wrong, such as a field that failed to parse.
+    Error,
+    /// An application-defined class, such as a category of regions. The default theme gives every
+    /// number a distinct color from a [`HighlightPalette`].
+    Custom(usize),
 }
 
-impl Location {
-    /// Decides what cell we should consider "clicked" so that we can put the cursor there. If the
-    /// user clicked a cell directly, the decision is obvious. But clicks in padding may result in
-    /// the cursor being moved as well. The returned [`Cell`] is relative to the current viewport.
-    fn approximate_cell(&self, cols: i64, rows: i64) -> Option<Cell> {
-        match self {
-            Location::ByteArea(location)
-            | Location::CharArea(location) => {
-                Some(location.approximate_cell(cols, rows))
-            }
-            Location::ByteHeader
-            | Location::CharHeader
-            | Location::AddressArea
-            | Location::Other => None,
-        }
-    }
+/// Turns a [`Highlight`] into colors while drawing.
+type Resolve<'a> = dyn Fn(Highlight) -> HighlightColor + 'a;
+
+/// How the addresses in the address area of the [`HexViewer`] are written, see
+/// [`HexViewer::address_format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AddressFormat {
+    /// The base of the addresses, [`Radix::Hexadecimal`] by default.
+    pub radix: Radix,
+    /// Whether the addresses have the prefix of their radix, such as `0x`. Off by default.
+    pub prefix: bool,
+    /// Whether shorter addresses are padded with zeros, or aligned to the right with spaces. On by
+    /// default.
+    pub zero_padding: bool,
+}
 
-    /// The column this Location applies to. No approximation is done, only strict matches are
-    /// returned.
-    fn column(&self) -> Option<i64> {
-        match self {
-            Location::ByteArea(location)
-            | Location::CharArea(location) => {
-                location.column()
-            }
-            Location::ByteHeader
-            | Location::CharHeader
-            | Location::AddressArea
-            | Location::Other => None,
-        }
+impl AddressFormat {
+    /// The prefix the addresses are written with, if any.
+    fn prefix(self) -> &'static str {
+        if self.prefix { self.radix.prefix() } else { "" }
     }
+}
 
-    /// The row this Location applies to. No approximation is done, only strict matches are
-    /// returned.
-    fn row(&self) -> Option<i64> {
-        match self {
-            Location::ByteArea(location)
-            | Location::CharArea(location) => {
-                location.row()
-            }
-            Location::ByteHeader
-            | Location::CharHeader
-            | Location::AddressArea
-            | Location::Other => None,
+impl Default for AddressFormat {
+    fn default() -> Self {
+        Self {
+            radix: Radix::Hexadecimal,
+            prefix: false,
+            zero_padding: true,
         }
     }
 }
 
-/// A logical location within the [`HexViewer`]'s byte/char area.
-#[derive(Clone, Copy, Debug)]
-enum DataLocation {
-    /// A ByteLocation
-    Cell(Cell),
-    /// Padding left, containing the row.
-    PaddingLeft(i64),
-    /// Padding right, containing the row.
-    PaddingRight(i64),
-    /// Padding top, containing the column.
-    PaddingTop(i64),
-    /// Padding bottom, containing the column.
-    PaddingBottom(i64),
-    /// Top left corner.
-    CornerTopLeft,
-    /// Top right corner.
-    CornerTopRight,
-    /// Bottom left corner.
-    CornerBottomLeft,
-    /// Bottom right corner.
-    CornerBottomRight,
+/// The order of the bytes in a word, see [`HexViewer::word_grouping`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// The least significant byte first, as on x86 and most ARM systems.
+    #[default]
+    Little,
+    /// The most significant byte first, as in network protocols.
+    Big,
 }
 
-impl DataLocation {
-    fn approximate_cell(&self, cols: i64, rows: i64) -> Cell {
+/// How the byte area of the [`HexViewer`] displays the values of the bytes, see
+/// [`HexViewer::byte_display`]. All values have the same number of digits, with leading zeros.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ByteDisplay {
+    /// Two hexadecimal digits, such as `7F`.
+    #[default]
+    Hex,
+    /// Eight binary digits, such as `01111111`, for bit fields and protocols.
+    Binary,
+    /// Three octal digits, such as `177`.
+    Octal,
+    /// Three decimal digits, such as `127`.
+    Decimal,
+}
+
+impl ByteDisplay {
+    /// The number of digits of a value.
+    pub fn digits(self) -> usize {
         match self {
-            DataLocation::Cell(location) => {
-                *location
-            }
-            DataLocation::PaddingLeft(row) => {
-                Cell::new(0, *row, Side::Left)
-            }
-            DataLocation::PaddingRight(row) => {
-                Cell::new(cols - 1, *row, Side::Right)
-            }
-            DataLocation::PaddingTop(_)
-            | DataLocation::CornerTopLeft
-            | DataLocation::CornerTopRight => {
-                Cell::new(0, 0, Side::Left)
-            }
-            DataLocation::PaddingBottom(_)
-            | DataLocation::CornerBottomLeft
-            | DataLocation::CornerBottomRight => {
-                Cell::new(0, rows, Side::Right)
-            }
+            ByteDisplay::Hex => 2,
+            ByteDisplay::Binary => 8,
+            ByteDisplay::Octal | ByteDisplay::Decimal => 3,
         }
     }
 
-    /// The column this DataLocation applies to. No approximation is done, only strict matches are
-    /// returned.
-    fn column(&self) -> Option<i64> {
+    /// Formats `value` the way it's displayed.
+    pub fn format(self, value: u8) -> String {
         match self {
-            DataLocation::Cell(location) => {
-                Some(location.col)
-            }
-            DataLocation::PaddingTop(row)
-            | DataLocation::PaddingBottom(row) => {
-                Some(*row)
-            }
-            DataLocation::PaddingLeft(_)
-            | DataLocation::CornerTopLeft
-            | DataLocation::CornerTopRight
-            | DataLocation::PaddingRight(_)
-            | DataLocation::CornerBottomLeft
-            | DataLocation::CornerBottomRight => None
+            ByteDisplay::Hex => format!("{value:02X}"),
+            ByteDisplay::Binary => format!("{value:08b}"),
+            ByteDisplay::Octal => format!("{value:03o}"),
+            ByteDisplay::Decimal => format!("{value:03}"),
         }
     }
+}
 
-    /// The row this DataLocation applies to. No approximation is done, only strict matches are
-    /// returned.
-    fn row(&self) -> Option<i64> {
+/// A value that the [`HexViewer::row_value`] column shows for every row, decoded from the bytes at
+/// the start of the row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RowValue {
+    /// An unsigned 16-bit integer.
+    U16(Endianness),
+    /// An unsigned 32-bit integer.
+    U32(Endianness),
+    /// An unsigned 64-bit integer.
+    U64(Endianness),
+    /// A signed 32-bit integer.
+    I32(Endianness),
+    /// A signed 64-bit integer.
+    I64(Endianness),
+    /// A 32-bit floating point number.
+    F32(Endianness),
+    /// A 64-bit floating point number.
+    F64(Endianness),
+    /// An unsigned 32-bit number of seconds since the Unix epoch, as a date and time in UTC.
+    UnixTime32(Endianness),
+}
+
+impl RowValue {
+    /// The number of bytes the value is decoded from.
+    pub fn size(self) -> usize {
         match self {
-            DataLocation::Cell(location) => {
-                Some(location.row)
+            RowValue::U16(_) => 2,
+            RowValue::U32(_) | RowValue::I32(_) | RowValue::F32(_) | RowValue::UnixTime32(_) => 4,
+            RowValue::U64(_) | RowValue::I64(_) | RowValue::F64(_) => 8,
+        }
+    }
+
+    /// Decodes the value from the start of `bytes` and formats it, if there are enough bytes.
+    pub fn format(self, bytes: &[u8]) -> Option<String> {
+        let bytes = bytes.get(..self.size())?;
+
+        // The bytes in big endian order, as a number of `size` bytes.
+        let value = |endianness: Endianness| {
+            let fold = |value: u64, byte: &u8| value << 8 | u64::from(*byte);
+
+            match endianness {
+                Endianness::Little => bytes.iter().rev().fold(0, fold),
+                Endianness::Big => bytes.iter().fold(0, fold),
             }
-            DataLocation::PaddingLeft(row)
-            | DataLocation::PaddingRight(row) => {
-                Some(*row)
+        };
+
+        Some(match self {
+            RowValue::U16(endianness) | RowValue::U32(endianness) | RowValue::U64(endianness) => {
+                value(endianness).to_string()
             }
-            DataLocation::PaddingTop(_)
-            | DataLocation::CornerTopLeft
-            | DataLocation::CornerTopRight
-            | DataLocation::PaddingBottom(_)
-            | DataLocation::CornerBottomLeft
-            | DataLocation::CornerBottomRight => None
+            RowValue::I32(endianness) => (value(endianness) as u32 as i32).to_string(),
+            RowValue::I64(endianness) => (value(endianness) as i64).to_string(),
+            RowValue::F32(endianness) => format_float(f32::from_bits(value(endianness) as u32)),
+            RowValue::F64(endianness) => format_float(f64::from_bits(value(endianness))),
+            RowValue::UnixTime32(endianness) => format_utc(value(endianness)),
+        })
+    }
+
+    /// The number of chars that the longest value takes.
+    fn chars(self) -> usize {
+        match self {
+            RowValue::U16(_) => 5,
+            RowValue::U32(_) => 10,
+            RowValue::I32(_) => 11,
+            RowValue::U64(_) | RowValue::I64(_) => 20,
+            RowValue::F32(_) | RowValue::F64(_) => FLOAT_CHARS,
+            RowValue::UnixTime32(_) => 19,
         }
     }
 }
 
-/// Describes the location of a cell relative to the current viewport, and a side within that cell.
-/// The cell in the top left corner has col 0 and row 0.
-#[derive(Clone, Copy, Debug)]
-struct Cell {
-    /// Column in the viewport.
-    col: i64,
-    /// Row in the viewport.
-    row: i64,
-    /// Side of the cell that was clicked, meaning left from center or right from center. Side::None
-    /// when the side isn't chosen, or is irrelevant.
-    side: Side,
-}
+/// The number of chars that floating point numbers of a [`RowValue`] are formatted in at most.
+const FLOAT_CHARS: usize = 12;
 
-impl Cell {
-    fn new(col: i64, row: i64, side: Side) -> Self {
-        Self {col, row, side}
+/// Formats `value` in at most [`FLOAT_CHARS`] chars, in scientific notation if it's too long
+/// otherwise.
+fn format_float<F: fmt::Display + fmt::LowerExp>(value: F) -> String {
+    let plain = value.to_string();
+
+    if plain.len() <= FLOAT_CHARS {
+        plain
+    } else {
+        format!("{value:.5e}")
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd)]
-enum Side {
-    Left = 0,
-    None = 1,
-    Right = 2,
+/// Produces the text of a row of the [`HexViewer::row_value`] column from its offset and bytes.
+type RowValueFn<'a> = Box<dyn Fn(u64, &[u8]) -> Option<String> + 'a>;
+
+/// The [`HexViewer::row_value`] column.
+struct RowValueColumn<'a> {
+    /// The width in chars.
+    chars: usize,
+    format: RowValueFn<'a>,
 }
 
-/// The counterpart of [`Cell`], which stores an absolute offset into the source, and the same
-/// side information as the [`Cell`]
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct Index {
-    offset: i64,
-    side: Side,
+/// The encoding that the char area of the [`HexViewer`] decodes the bytes with, one byte per char,
+/// see [`HexViewer::encoding`]. Bytes that don't decode to a printable char are shown as the
+/// [`HexViewer::non_printable_char`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// Printable ASCII.
+    #[default]
+    Ascii,
+    /// ISO-8859-1, which maps every byte to the Unicode code point with the same value.
+    Latin1,
+    /// UTF-8. A valid sequence shows its char in the cell of its first byte, and the cells of the
+    /// other bytes are dimmed.
+    Utf8,
+    /// UTF-16 in the given byte order, with the code units at even offsets. Like with
+    /// [`Encoding::Utf8`], a char is shown in the cell of its first byte.
+    Utf16(Endianness),
+    /// A single-byte encoding of `encoding_rs`, such as [`encoding_rs::WINDOWS_1252`] or
+    /// [`encoding_rs::KOI8_R`]. Of multi-byte encodings such as [`encoding_rs::SHIFT_JIS`], only
+    /// the bytes that are a char by themselves are decoded.
+    #[cfg(feature = "encoding")]
+    EncodingRs(&'static encoding_rs::Encoding),
 }
 
-impl PartialOrd for Index {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.offset < other.offset {
-            Some(Ordering::Less)
-        } else if self.offset > other.offset {
-            Some(Ordering::Greater)
-        } else {
-            self.side.partial_cmp(&other.side)
+impl Encoding {
+    /// Decodes `byte` as a char by itself, if it's a printable one. UTF-8 and UTF-16 only decode
+    /// ASCII this way, see [`Encoding::decode_sequence`].
+    pub fn decode(self, byte: u8) -> Option<char> {
+        let char = match self {
+            Encoding::Ascii | Encoding::Utf8 | Encoding::Utf16(_) => {
+                Some(char::from(byte)).filter(char::is_ascii)
+            }
+            Encoding::Latin1 => Some(char::from(byte)),
+            #[cfg(feature = "encoding")]
+            Encoding::EncodingRs(encoding) => {
+                let bytes = [byte];
+                let decoded = encoding.decode_without_bom_handling_and_without_replacement(&bytes)?;
+                let mut chars = decoded.chars();
+
+                chars.next().filter(|_| chars.next().is_none())
+            }
+        };
+
+        char.filter(|char| !char.is_control())
+    }
+
+    /// Decodes the char that `bytes` start with, if it's a valid and printable one, and returns it
+    /// with the number of bytes it spans. Single-byte encodings decode only the first byte, like
+    /// [`Encoding::decode`].
+    pub fn decode_sequence(self, bytes: &[u8]) -> Option<(char, usize)> {
+        let (char, length) = match self {
+            Encoding::Utf8 => {
+                let length = match bytes.first()? {
+                    0x00..=0x7F => 1,
+                    0xC2..=0xDF => 2,
+                    0xE0..=0xEF => 3,
+                    0xF0..=0xF4 => 4,
+                    _ => return None,
+                };
+
+                let char = str::from_utf8(bytes.get(..length)?).ok()?.chars().next()?;
+                (char, length)
+            }
+            Encoding::Utf16(endianness) => {
+                let unit = |index: usize| {
+                    let unit = [*bytes.get(index)?, *bytes.get(index + 1)?];
+
+                    Some(match endianness {
+                        Endianness::Little => u16::from_le_bytes(unit),
+                        Endianness::Big => u16::from_be_bytes(unit),
+                    })
+                };
+
+                let char = char::decode_utf16([unit(0)?].into_iter().chain(unit(2)))
+                    .next()?
+                    .ok()?;
+                (char, 2 * char.len_utf16())
+            }
+            _ => (self.decode(*bytes.first()?)?, 1),
+        };
+
+        (!char.is_control()).then_some((char, length))
+    }
+
+    /// Whether chars may span multiple bytes.
+    fn is_multi_byte(self) -> bool {
+        matches!(self, Encoding::Utf8 | Encoding::Utf16(_))
+    }
+
+    /// The number of bytes that chars are aligned to.
+    fn code_unit(self) -> i64 {
+        match self {
+            Encoding::Utf16(_) => 2,
+            _ => 1,
         }
     }
 }
 
-impl Index {
-    fn new(offset: i64, side: Side) -> Self {
+/// The strings that the char area of the [`HexViewer`] shows the byte values as, see
+/// [`HexViewer::char_map`]. Use it for mappings that no [`Encoding`] covers, such as the text
+/// table of a game ROM:
+///
+/// ```ignore
+/// let mut char_map = CharMap::from_fn(|byte| match byte {
+///     0x80..=0x99 => char::from(b'A' + byte - 0x80).to_string(),
+///     _ => String::from("."),
+/// });
+/// char_map.set(0xFF, " ");
+/// ```
+///
+/// The strings should be a single char, since the cells are only as wide as one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CharMap {
+    /// A string per byte value.
+    strings: Vec<String>,
+}
+
+impl CharMap {
+    /// Creates a [`CharMap`] with a string per byte value.
+    pub fn new(strings: [&str; 256]) -> Self {
+        Self::from_fn(|byte| strings[byte as usize])
+    }
+
+    /// Creates a [`CharMap`] with the strings that `func` returns for every byte value.
+    pub fn from_fn<S: Into<String>>(mut func: impl FnMut(u8) -> S) -> Self {
         Self {
-            offset,
-            side
+            strings: (0..=u8::MAX).map(|byte| func(byte).into()).collect(),
         }
     }
+
+    /// Creates a [`CharMap`] that shows the bytes like the `encoding` does, as a start for a
+    /// mapping that only differs in some bytes.
+    pub fn from_encoding(encoding: Encoding) -> Self {
+        Self::from_fn(|byte| encoding.decode(byte).unwrap_or('.'))
+    }
+
+    /// The string of `byte`.
+    pub fn get(&self, byte: u8) -> &str {
+        &self.strings[byte as usize]
+    }
+
+    /// Sets the string of `byte`.
+    pub fn set(&mut self, byte: u8, string: impl Into<String>) {
+        self.strings[byte as usize] = string.into();
+    }
 }
 
-/// Holds the selection info, made for instance by mouse or by holding shift and using the arrow
-/// buttons.
-///
-/// The byte and char cells each have a `left` side and a `ride` side, which play a role in
-/// selections. When a selection is started by clicking the left side of the cell at offset `2` and
-/// the cursor is moved to the right side of the cell at offset `2`, this offset is now selected.
-/// Continuing the movement to the left side of the cell at offset `3`, the selection doesn't
-/// change. Only when the selection progresses to its right side is the cell at offset `3` selected.
-///
-/// This same principle may also play a role in selection made by keyboard, if the cursor at the
-/// start was set by mouse, and hence side information is retained.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct Selection {
-    /// The leftmost byte in the selection.
+/// How a byte is shown in the char area with a multi-byte [`Encoding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Glyph {
+    /// The first byte of a printable char.
+    Char(char),
+    /// One of the other bytes of a char.
+    Continuation,
+    /// Not part of a printable char.
+    Invalid,
+}
+
+#[cfg(feature = "encoding")]
+impl From<&'static encoding_rs::Encoding> for Encoding {
+    fn from(encoding: &'static encoding_rs::Encoding) -> Self {
+        Encoding::EncodingRs(encoding)
+    }
+}
+
+/// The area of the [`HexViewer`] that a byte is drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Area {
+    /// The byte area, with the bytes in hex.
+    Bytes,
+    /// The char area, with the bytes as chars.
+    Chars,
+}
+
+/// A part of the [`HexViewer`] that can be clicked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClickArea {
+    /// The byte area.
+    Bytes,
+    /// The char area.
+    Chars,
+    /// The address area.
+    Address,
+    /// The header above the byte area.
+    ByteHeader,
+    /// The header above the char area.
+    CharHeader,
+}
+
+/// A jump over a run of identical bytes with CTRL and an arrow key, that continues beyond the data
+/// that was read. See [`HexViewer::on_run_jump`] and [`Content::run_jump`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunJump {
+    /// The offset of the cursor.
+    pub from: u64,
+    /// The distance between the compared bytes, negative when jumping back: 1 within a row and the
+    /// [`HexViewer::virtual_columns`] across rows.
+    pub step: i64,
+    /// Whether the selection is extended to the new position, because SHIFT is held.
+    pub extend_selection: bool,
+}
+
+/// A right-click on the [`HexViewer`], see [`HexViewer::on_right_click`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RightClick {
+    /// The absolute offset of the clicked byte, or of the first byte of the clicked row in the
+    /// address area. `None` for the headers and for clicks beside or past the bytes.
+    pub offset: Option<u64>,
+    /// The part that was clicked.
+    pub area: ClickArea,
+    /// Whether `offset` is in the current selection.
+    pub in_selection: bool,
+    /// Where the click was, in window coordinates, to open a menu at.
+    pub position: Point,
+}
+
+/// The byte under the mouse, see [`HexViewer::on_hover`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HoveredByte {
+    /// The absolute offset of the byte.
     pub offset: u64,
-    /// The number of bytes that are selected.
-    pub length: u64,
-    /// The last byte that was interacted with to create the selection. Whether this byte is part
-    /// of the selection depends on whether the selection was made by keyboard or by mouse, and in
-    /// the latter case, the side of it that was clicked and the direction of the selection. If you
-    /// want the last contained byte, see [`Selection::last_contained`].
-    pub last: u64,
+    /// The area the byte is hovered in.
+    pub area: Area,
 }
 
-impl Selection {
-    /// Creates a new selection.
-    pub fn new(offset: u64, length: u64, last: u64) -> Self {
-        Self { offset, length, last }
+/// A color in each [`Area`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct AreaColors {
+    bytes: Option<Color>,
+    chars: Option<Color>,
+}
+
+impl AreaColors {
+    fn both(color: Color) -> Self {
+        Self {
+            bytes: Some(color),
+            chars: Some(color),
+        }
     }
 
-    /// The last byte that was interacted with to create the selection, that's also contained in the
-    /// selection.
-    pub fn last_contained(&self) -> u64 {
-        if self.last < self.offset {
-            self.offset
-        } else if self.last >= self.offset + self.length {
-            self.offset + self.length - 1
-        } else {
-            self.last
+    fn get(&self, area: Area) -> Option<Color> {
+        match area {
+            Area::Bytes => self.bytes,
+            Area::Chars => self.chars,
+        }
+    }
+
+    fn set(&mut self, area: Area, color: Color) {
+        match area {
+            Area::Bytes => self.bytes = Some(color),
+            Area::Chars => self.chars = Some(color),
         }
     }
 }
 
-/// Controls the text color and background color of byte/char cells.
+/// An animation of the background of a byte/char cell, see [`CellStyle::animation`].
 ///
-///
-pub struct ContentStyler {
-    styles: Vec<CellStyle>,
-    is_clear: bool
+/// The cell changes between its background and the color of the animation. All animations run on
+/// the same clock, so cells with the same period blink and pulse in step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellAnimation {
+    kind: AnimationKind,
+    color: Color,
+    period: Duration,
 }
 
-impl Default for ContentStyler {
-    fn default() -> Self {
-        ContentStyler::new(0)
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnimationKind {
+    Blink,
+    Pulse,
 }
 
-impl ContentStyler {
-    // TODO maybe change some return types to Result
-    
-    pub fn new(size: usize) -> Self {
+impl CellAnimation {
+    /// Shows `color` for the first half of every `period`, and the background for the second half.
+    /// With a zero `period`, `color` is shown all the time.
+    pub fn blink(color: Color, period: Duration) -> Self {
         Self {
-            styles: vec![Default::default(); size],
-            is_clear: true
+            kind: AnimationKind::Blink,
+            color,
+            period,
         }
     }
 
-    pub fn set_text(&mut self, index: usize, color: Color) {
-        if index < self.styles.len() {
-            self.styles[index].text = Some(color);
+    /// Fades from the background to `color` and back again every `period`. With a zero `period`,
+    /// `color` is shown all the time.
+    pub fn pulse(color: Color, period: Duration) -> Self {
+        Self {
+            kind: AnimationKind::Pulse,
+            color,
+            period,
         }
-        self.is_clear = false;
     }
 
-    pub fn set_background(&mut self, index: usize, background: Color) {
-        if index < self.styles.len() {
-            self.styles[index].background = Some(background);
+    /// How far the cell has changed to the color of the animation `elapsed` after the start of the
+    /// clock, from 0 to 1.
+    fn intensity(&self, elapsed: Duration) -> f32 {
+        if self.period.is_zero() {
+            return 1.0;
         }
-        self.is_clear = false;
-    }
 
-    /// Resets the ContentStyler for reuse, and makes sure it has the required `size`.
-    pub fn clear(&mut self, size: usize) {
-        if !self.is_clear || self.styles.len() != size {
-            if self.styles.len() == size {
-                self.styles.fill(Default::default());
-            } else {
-                self.styles = vec![Default::default(); size];
-            }
-            self.is_clear = true;
+        let phase = elapsed.as_secs_f64() % self.period.as_secs_f64() / self.period.as_secs_f64();
+
+        match self.kind {
+            AnimationKind::Blink => if phase < 0.5 { 1.0 } else { 0.0 },
+            AnimationKind::Pulse => ((1.0 - (phase * std::f64::consts::TAU).cos()) / 2.0) as f32,
         }
     }
 
-    fn text_color(&self, index: usize) -> Option<Color> {
-        self.styles.get(index)?.text
+    /// When the animation changes next, `elapsed` after the start of the clock, or `None` if it
+    /// changes every frame. An animation with a zero period always has full intensity, so it never
+    /// changes.
+    fn next_change(&self, elapsed: Duration) -> Option<Duration> {
+        if self.period.is_zero() {
+            return Some(Duration::MAX);
+        }
+
+        match self.kind {
+            AnimationKind::Blink => {
+                let half = self.period.as_secs_f64() / 2.0;
+                let changes = (elapsed.as_secs_f64() / half).floor() + 1.0;
+
+                Some(Duration::from_secs_f64(changes * half))
+            }
+            AnimationKind::Pulse => None,
+        }
     }
+}
 
-    fn background_color(&self, index: usize) -> Option<Color> {
-        self.styles.get(index)?.background
+bitflags! {
+    /// Lines drawn under, through or over the text of a byte/char cell, see
+    /// [`CellStyle::decorations`]. They span the whole width of the cell, so the lines of adjacent
+    /// cells connect.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Decorations: u32 {
+        const UNDERLINE = 0b001;
+        const STRIKETHROUGH = 0b010;
+        const OVERLINE = 0b100;
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
-pub struct CellStyle {
-    text: Option<Color>,
-    background: Option<Color>,
-    /// This is currently a placeholder, borders aren't drawn yet.
-    border: Option<CellBorder>,
+impl Decorations {
+    /// Draws the lines across the `cell` bounds, positioned relative to the `text` bounds.
+    fn draw(self, renderer: &mut impl Renderer, cell: Rectangle, text: Rectangle, color: Color) {
+        let lines = [
+            (Decorations::OVERLINE, text.y),
+            (Decorations::STRIKETHROUGH, text.center_y() - DECORATION_THICKNESS / 2.0),
+            (Decorations::UNDERLINE, text.y + text.height - DECORATION_THICKNESS),
+        ];
+
+        for (decoration, y) in lines {
+            if self.contains(decoration) {
+                renderer.fill_quad(
+                    Quad {
+                        bounds: Rectangle::new(
+                            Point::new(cell.x, y),
+                            Size::new(cell.width, DECORATION_THICKNESS),
+                        ),
+                        ..Quad::default()
+                    },
+                    color,
+                );
+            }
+        }
+    }
 }
 
+/// The border of a byte/char cell, drawn on some or all of its sides.
+///
+/// Drawing the outer sides of the cells of a range outlines it, for instance to box a field of a
+/// parsed structure. The [`Border::color`] and [`Border::width`] are used for every side, the
+/// [`Border::radius`] only when all sides are drawn.
 #[derive(Debug, Clone, Copy, PartialEq)]
-struct CellBorder {
+pub struct CellBorder {
     border: Border,
     sides: BorderFlags
 }
 
 impl CellBorder {
-    /* Unused at the moment, avoid warnings.
+    /// Creates a border on all sides.
     pub fn new(border: Border) -> Self {
         Self {
             border,
@@ -2893,6 +9073,8 @@ impl CellBorder {
         }
     }
 
+    /// Creates a border without any sides, to add them with [`CellBorder::top`],
+    /// [`CellBorder::left`], [`CellBorder::bottom`] and [`CellBorder::right`].
     pub fn empty(border: Border) -> Self {
         Self {
             border,
@@ -2900,31 +9082,91 @@ impl CellBorder {
         }
     }
 
+    /// Creates a border on the `sides`.
+    pub fn sides(border: Border, sides: BorderFlags) -> Self {
+        Self {
+            border,
+            sides
+        }
+    }
+
+    /// Adds the top side.
     pub fn top(mut self) -> Self {
         self.sides |= BorderFlags::TOP;
         self
     }
 
+    /// Adds the left side.
     pub fn left(mut self) -> Self {
         self.sides |= BorderFlags::LEFT;
         self
     }
 
+    /// Adds the bottom side.
     pub fn bottom(mut self) -> Self {
         self.sides |= BorderFlags::BOTTOM;
         self
     }
 
+    /// Adds the right side.
     pub fn right(mut self) -> Self {
         self.sides |= BorderFlags::RIGHT;
         self
     }
-    */
+
+    /// Draws the sides on the inside of the cell `bounds`.
+    fn draw(&self, renderer: &mut impl Renderer, bounds: Rectangle) {
+        if self.sides.is_all() {
+            renderer.fill_quad(
+                Quad {
+                    bounds,
+                    border: self.border,
+                    ..Quad::default()
+                },
+                Color::TRANSPARENT,
+            );
+
+            return;
+        }
+
+        let width = self.border.width.min(bounds.width).min(bounds.height);
+        let sides = [
+            (BorderFlags::TOP, Rectangle::new(bounds.position(), Size::new(bounds.width, width))),
+            (BorderFlags::LEFT, Rectangle::new(bounds.position(), Size::new(width, bounds.height))),
+            (
+                BorderFlags::BOTTOM,
+                Rectangle::new(
+                    Point::new(bounds.x, bounds.y + bounds.height - width),
+                    Size::new(bounds.width, width),
+                ),
+            ),
+            (
+                BorderFlags::RIGHT,
+                Rectangle::new(
+                    Point::new(bounds.x + bounds.width - width, bounds.y),
+                    Size::new(width, bounds.height),
+                ),
+            ),
+        ];
+
+        for (side, bounds) in sides {
+            if self.sides.contains(side) {
+                renderer.fill_quad(
+                    Quad {
+                        bounds,
+                        ..Quad::default()
+                    },
+                    self.border.color,
+                );
+            }
+        }
+    }
 }
 
 bitflags! {
+    /// The sides of a [`CellBorder`].
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-    struct BorderFlags: u32 {
+    pub struct BorderFlags: u32 {
         const TOP = 0b0001;
         const LEFT = 0b0010;
         const BOTTOM = 0b0100;
@@ -2963,6 +9205,102 @@ pub struct Style {
     pub header_text: Color,
     /// The [`Border`] around the whole widget.
     pub border: Border,
+    /// The [`Color`] of the outline of the [`HexViewer::ghost_cursor`].
+    pub ghost_cursor: Color,
+    /// The [`Color`] of the chars of bytes without a printable char, see
+    /// [`HexViewer::non_printable_char`]. The text color is used if it's `None`.
+    pub non_printable_text: Option<Color>,
+    /// The [`Background`] of every other group of columns, see [`HexViewer::column_groups`].
+    pub column_group_shade: Background,
+    /// The [`Background`] of every other row, see [`HexViewer::zebra_stripes`].
+    pub zebra_stripe: Background,
+    /// The [`Background`] of the row and the column of the cursor, see
+    /// [`HexViewer::crosshair`].
+    pub crosshair: Background,
+    /// The [`Color`] of the placeholder text of bytes/chars that couldn't be read.
+    pub unreadable_text: Color,
+    /// The [`Background`] of bytes/chars that couldn't be read.
+    pub unreadable_background: Background,
+    /// The [`Color`] of the placeholder text of bytes/chars that are still being read.
+    pub pending_text: Color,
+    /// The [`Background`] of bytes/chars that are still being read.
+    pub pending_background: Background,
+    /// The [`Shadow`] drawn under the headers once scrolled down, and right of the address area
+    /// once scrolled right. Its vertical and horizontal offset apply to these respectively. Use a
+    /// `blur_radius` of `0.0` and an offset of `1.0` to draw a divider instead.
+    pub scroll_shadow: Option<Shadow>,
+    /// The [`Color`] of the separator lines drawn by [`HexViewer::row_groups`].
+    pub row_separator: Color,
+    /// The [`Background`] of a [`Notice`].
+    pub notice_background: Background,
+    /// The [`Color`] of the text of a [`Notice`].
+    pub notice_text: Color,
+    /// The [`Color`] of the [`Placeholder`] of an empty [`Content`].
+    pub placeholder_text: Color,
+    /// The [`Color`] of the highlight while a file is dragged over the window, see
+    /// [`HexViewer::on_file_dropped`].
+    pub drop_target: Color,
+    /// The background [`Color`] of bytes/chars that just changed, which fades out, see
+    /// [`HexViewer::change_flash`].
+    pub change_flash: Color,
+    /// The text colors of [`HexViewer::byte_class_coloring`].
+    pub byte_classes: ByteClassColors,
+}
+
+/// The class of a byte value, used by [`HexViewer::byte_class_coloring`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ByteClass {
+    /// `0x00`.
+    Null,
+    /// ASCII letters, digits and punctuation, `0x21` to `0x7E`.
+    Printable,
+    /// ASCII whitespace: space, tab, line feed, vertical tab, form feed and carriage return.
+    Whitespace,
+    /// The other ASCII control characters, including `0x7F`.
+    Control,
+    /// Values with the high bit set, `0x80` to `0xFF`.
+    HighBit,
+}
+
+impl ByteClass {
+    /// The class of `value`.
+    pub fn of(value: u8) -> Self {
+        match value {
+            0x00 => ByteClass::Null,
+            b' ' | b'\t' | b'\n' | 0x0B | 0x0C | b'\r' => ByteClass::Whitespace,
+            0x21..=0x7E => ByteClass::Printable,
+            0x80..=0xFF => ByteClass::HighBit,
+            _ => ByteClass::Control,
+        }
+    }
+}
+
+/// The text color of each [`ByteClass`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ByteClassColors {
+    /// The [`Color`] of [`ByteClass::Null`].
+    pub null: Color,
+    /// The [`Color`] of [`ByteClass::Printable`].
+    pub printable: Color,
+    /// The [`Color`] of [`ByteClass::Whitespace`].
+    pub whitespace: Color,
+    /// The [`Color`] of [`ByteClass::Control`].
+    pub control: Color,
+    /// The [`Color`] of [`ByteClass::HighBit`].
+    pub high_bit: Color,
+}
+
+impl ByteClassColors {
+    /// The color of the `class`.
+    pub fn color(&self, class: ByteClass) -> Color {
+        match class {
+            ByteClass::Null => self.null,
+            ByteClass::Printable => self.printable,
+            ByteClass::Whitespace => self.whitespace,
+            ByteClass::Control => self.control,
+            ByteClass::HighBit => self.high_bit,
+        }
+    }
 }
 
 /// The theme catalog of a [`HexViewer`].
@@ -2975,6 +9313,23 @@ pub trait Catalog: ScrollCatalog + Sized {
 
     /// The [`Style`] of a class with the given status.
     fn style(&self, class: &Self::Class<'_>, status: Status) -> Style;
+
+    /// The colors of a [`Highlight`] in a class. By default they're derived from the text color of
+    /// the [`Style`] of the class: matches, warnings and errors get a blue, amber and red
+    /// background, and [`Highlight::Custom`] classes step around the color wheel like a
+    /// [`HighlightPalette`] does.
+    fn highlight(&self, class: &Self::Class<'_>, highlight: Highlight) -> HighlightColor {
+        const MATCH_HUE: f32 = 210.0;
+
+        let hue = match highlight {
+            Highlight::Match => MATCH_HUE,
+            Highlight::Warning => 40.0,
+            Highlight::Error => 0.0,
+            Highlight::Custom(index) => (MATCH_HUE + index as f32 * GOLDEN_ANGLE) % 360.0,
+        };
+
+        HighlightColor::behind(self.style(class, Status::Active).text, hue)
+    }
 }
 
 /// A styling function for a [`HexViewer`].
@@ -2992,6 +9347,27 @@ impl Catalog for Theme {
     fn style(&self, class: &Self::Class<'_>, status: Status) -> Style {
         class(self, status)
     }
+
+    fn highlight(&self, _class: &Self::Class<'_>, highlight: Highlight) -> HighlightColor {
+        default_highlight(self, highlight)
+    }
+}
+
+/// The default colors of a [`Highlight`].
+pub fn default_highlight(theme: &Theme, highlight: Highlight) -> HighlightColor {
+    let palette = theme.extended_palette();
+
+    let pair = match highlight {
+        Highlight::Match => palette.primary.weak,
+        Highlight::Warning => palette.warning.weak,
+        Highlight::Error => palette.danger.weak,
+        Highlight::Custom(index) => return HighlightPalette::color_of(theme, index),
+    };
+
+    HighlightColor {
+        text: pair.text,
+        background: pair.color,
+    }
 }
 
 /// The default style of a [`HexViewer`].
@@ -3008,7 +9384,34 @@ pub fn default(theme: &Theme, status: Status) -> Style {
             radius: 2.0.into(),
             width: 1.0,
             color: palette.background.strong.color,
-        }
+        },
+        ghost_cursor: palette.background.base.text.scale_alpha(0.4),
+        non_printable_text: None,
+        column_group_shade: Background::Color(palette.background.weak.color.scale_alpha(0.5)),
+        zebra_stripe: Background::Color(palette.background.weak.color.scale_alpha(0.5)),
+        crosshair: Background::Color(palette.primary.base.color.scale_alpha(0.1)),
+        unreadable_text: palette.danger.base.text,
+        unreadable_background: Background::Color(palette.danger.base.color),
+        pending_text: palette.background.strong.text,
+        pending_background: Background::Color(palette.background.weak.color),
+        scroll_shadow: Some(Shadow {
+            color: Color::BLACK.scale_alpha(if palette.is_dark { 0.5 } else { 0.2 }),
+            offset: Vector::new(2.0, 2.0),
+            blur_radius: 6.0,
+        }),
+        row_separator: palette.background.strong.color,
+        notice_background: Background::Color(palette.background.base.text.scale_alpha(0.85)),
+        notice_text: palette.background.base.color,
+        placeholder_text: palette.background.base.text.scale_alpha(0.6),
+        drop_target: palette.primary.base.color,
+        change_flash: palette.warning.base.color,
+        byte_classes: ByteClassColors {
+            null: palette.background.base.text.scale_alpha(0.4),
+            printable: palette.success.base.color,
+            whitespace: palette.warning.base.color,
+            control: palette.danger.base.color,
+            high_bit: palette.primary.base.color,
+        },
     };
 
     match status {
@@ -0,0 +1,81 @@
+use iced_futures::futures::channel::mpsc;
+use iced_futures::futures::{SinkExt, Stream, StreamExt};
+use iced_futures::{stream, Subscription};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::path::PathBuf;
+
+/// A change to a file watched with [`file`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileEvent {
+    /// The file was created, written to or replaced. Call [`Content::refresh`] to show its new
+    /// data. It reloads the [`Source`], which makes a [`FileSource`] drop its read-ahead buffer and
+    /// open the file again, so a replaced file is read instead of the old one.
+    ///
+    /// [`Content::refresh`]: crate::hex::viewer::Content::refresh
+    /// [`Source`]: crate::hex::viewer::Source
+    /// [`FileSource`]: crate::hex::source::FileSource
+    Changed,
+    /// The file was removed or moved away.
+    Removed,
+    /// The file couldn't be watched, with a description of the error. If the watch couldn't be
+    /// set up, no other events follow.
+    Failed(String),
+}
+
+/// Watches the file at `path` and publishes a [`FileEvent`] every time it changes.
+///
+/// The directory of the file is watched rather than the file itself, so the file keeps being
+/// watched when it's replaced, as many editors do when saving. A single save can publish several
+/// [`FileEvent::Changed`] events.
+///
+/// ```ignore
+/// fn subscription(&self) -> Subscription<Message> {
+///     watch::file(&self.path).map(Message::FileChanged)
+/// }
+/// ```
+pub fn file(path: impl Into<PathBuf>) -> Subscription<FileEvent> {
+    Subscription::run_with(path.into(), |path| events(path.clone()))
+}
+
+fn events(path: PathBuf) -> impl Stream<Item = FileEvent> {
+    stream::channel(100, async move |mut output: mpsc::Sender<FileEvent>| {
+        // Event paths are absolute, so the watched path has to be as well. A file that doesn't
+        // exist yet can't be canonicalized, so it's joined onto the current directory instead.
+        let path = path.canonicalize()
+            .or_else(|_| std::path::absolute(&path))
+            .unwrap_or(path);
+        let directory = path.parent().map(PathBuf::from).unwrap_or_else(|| path.clone());
+        let (sender, mut receiver) = mpsc::unbounded();
+
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = sender.unbounded_send(event);
+        })
+        .and_then(|mut watcher| {
+            watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        });
+
+        // The watcher stops when it's dropped, so it's kept until the subscription ends.
+        let _watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(error) => {
+                let _ = output.send(FileEvent::Failed(error.to_string())).await;
+                return;
+            }
+        };
+
+        while let Some(event) = receiver.next().await {
+            let file_event = match event {
+                Ok(event) if matches!(event.kind, EventKind::Access(_)) => continue,
+                Ok(event) if !event.paths.contains(&path) => continue,
+                Ok(_) if path.exists() => FileEvent::Changed,
+                Ok(_) => FileEvent::Removed,
+                Err(error) => FileEvent::Failed(error.to_string()),
+            };
+
+            if output.send(file_event).await.is_err() {
+                break;
+            }
+        }
+    })
+}